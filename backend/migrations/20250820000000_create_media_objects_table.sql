@@ -0,0 +1,23 @@
+-- Tabel untuk file yang diupload (foto SPBU, logo brand, icon service, foto profil user)
+CREATE TABLE media_objects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_type VARCHAR(20) NOT NULL
+        CHECK (owner_type IN ('spbu', 'brand', 'service', 'user')),
+    owner_id UUID NOT NULL,
+    kind VARCHAR(20) NOT NULL
+        CHECK (kind IN ('photo', 'logo', 'icon', 'avatar')),
+    storage_key TEXT NOT NULL UNIQUE,
+    thumbnail_key TEXT,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    sort_order INT NOT NULL DEFAULT 0,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index untuk mengambil semua file milik satu entitas
+CREATE INDEX idx_media_objects_owner ON media_objects(owner_type, owner_id, kind);
+
+COMMENT ON TABLE media_objects IS 'Metadata file yang disimpan di object storage';
+COMMENT ON COLUMN media_objects.storage_key IS 'Key file di storage backend (relatif terhadap root storage)';
+COMMENT ON COLUMN media_objects.thumbnail_key IS 'Key thumbnail JPEG, NULL jika thumbnail gagal dibuat';
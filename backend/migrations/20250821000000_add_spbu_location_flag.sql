@@ -0,0 +1,5 @@
+-- Tandai SPBU yang lokasinya perlu dicek manual
+ALTER TABLE spbu
+    ADD COLUMN IF NOT EXISTS location_flag VARCHAR(50);
+
+COMMENT ON COLUMN spbu.location_flag IS 'NULL jika lokasi valid, selain itu alasan: outside_indonesia atau address_mismatch';
@@ -0,0 +1,34 @@
+-- Tipe kendaraan
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'vehicle_type') THEN
+        CREATE TYPE vehicle_type AS ENUM ('motorcycle', 'car', 'truck');
+    END IF;
+END $$;
+
+-- Kendaraan milik user (garasi)
+CREATE TABLE vehicles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Nomor polisi dinormalisasi: huruf besar tanpa spasi, misalnya B1234XYZ
+    plate_number VARCHAR(15) NOT NULL UNIQUE,
+    vehicle_type vehicle_type NOT NULL,
+    name VARCHAR(100),
+    tank_capacity DECIMAL(8, 2) NOT NULL CHECK (tank_capacity > 0),
+    recommended_fuel_types TEXT[] NOT NULL DEFAULT '{}',
+    avg_consumption_km_per_liter DOUBLE PRECISION CHECK (avg_consumption_km_per_liter > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_vehicles_user_id ON vehicles(user_id);
+
+CREATE TRIGGER update_vehicles_updated_at
+BEFORE UPDATE ON vehicles
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Transaksi boleh dikaitkan dengan kendaraan
+ALTER TABLE transactions
+    ADD COLUMN vehicle_id UUID REFERENCES vehicles(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_vehicle_id ON transactions(vehicle_id);
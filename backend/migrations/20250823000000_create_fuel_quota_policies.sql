@@ -0,0 +1,32 @@
+-- Kuota BBM bersubsidi per jenis BBM dan jenis kendaraan.
+-- NULL pada daily_limit/monthly_limit berarti tidak dibatasi untuk jendela tersebut.
+CREATE TABLE fuel_quota_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fuel_type VARCHAR(50) NOT NULL,
+    vehicle_type vehicle_type NOT NULL,
+    daily_limit DECIMAL(10, 2) CHECK (daily_limit > 0),
+    monthly_limit DECIMAL(10, 2) CHECK (monthly_limit > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_fuel_quota_policy UNIQUE (fuel_type, vehicle_type)
+);
+
+CREATE TRIGGER update_fuel_quota_policies_updated_at
+BEFORE UPDATE ON fuel_quota_policies
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Kebijakan awal Pertalite dan Solar
+INSERT INTO fuel_quota_policies (fuel_type, vehicle_type, daily_limit, monthly_limit) VALUES
+    ('PERTALITE', 'motorcycle', 10, 200),
+    ('PERTALITE', 'car', 60, 1200),
+    ('SOLAR', 'car', 60, 1200),
+    ('SOLAR', 'truck', 200, 4000);
+
+-- Nomor polisi disimpan di transaksi agar pemakaian kuota tetap tercatat
+-- walaupun kendaraan dihapus lalu didaftarkan ulang
+ALTER TABLE transactions
+    ADD COLUMN plate_number VARCHAR(15);
+
+CREATE INDEX idx_transactions_quota_usage
+    ON transactions(plate_number, fuel_type, created_at)
+    WHERE plate_number IS NOT NULL;
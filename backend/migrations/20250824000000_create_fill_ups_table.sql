@@ -0,0 +1,20 @@
+-- Catatan pengisian BBM per kendaraan beserta odometer, untuk menghitung efisiensi (km/liter).
+-- Bisa berasal dari transaksi di aplikasi (transaction_id) atau input manual
+-- untuk pengisian di SPBU di luar sistem (station_name).
+CREATE TABLE fill_ups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    vehicle_id UUID NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    transaction_id UUID UNIQUE REFERENCES transactions(id) ON DELETE CASCADE,
+    spbu_id UUID REFERENCES spbu(id) ON DELETE SET NULL,
+    station_name VARCHAR(150),
+    fuel_type VARCHAR(50),
+    liters DECIMAL(10, 2) NOT NULL CHECK (liters > 0),
+    odometer_km INTEGER NOT NULL CHECK (odometer_km >= 0),
+    -- Pengisian penuh menjadi titik acuan perhitungan km/liter (metode full-to-full)
+    is_full_tank BOOLEAN NOT NULL DEFAULT TRUE,
+    filled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fill_ups_vehicle_odometer ON fill_ups(vehicle_id, odometer_km);
@@ -0,0 +1,113 @@
+-- Poin loyalitas dengan pembukuan double-entry yang append-only.
+-- Saldo selalu dihitung ulang dari SUM(amount) entri per akun.
+
+-- Akun ledger: satu akun member per user, plus akun sistem
+-- 'issuance' (sumber poin yang diberikan) dan 'redemption' (tujuan poin yang ditukar).
+-- user_id dan transaction_id sengaja tanpa foreign key: ledger adalah catatan audit
+-- yang tetap utuh walaupun user atau transaksinya dihapus.
+CREATE TABLE loyalty_accounts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_type VARCHAR(20) NOT NULL CHECK (account_type IN ('member', 'issuance', 'redemption')),
+    user_id UUID UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_loyalty_account_owner CHECK ((account_type = 'member') = (user_id IS NOT NULL))
+);
+
+-- Hanya ada satu akun untuk setiap tipe akun sistem
+CREATE UNIQUE INDEX uq_loyalty_system_account
+    ON loyalty_accounts(account_type) WHERE user_id IS NULL;
+
+INSERT INTO loyalty_accounts (account_type) VALUES ('issuance'), ('redemption');
+
+-- Jurnal: satu kejadian bisnis (earn/redeem/reversal) yang terdiri dari beberapa entri
+CREATE TABLE loyalty_journals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('earn', 'redeem', 'reversal')),
+    transaction_id UUID,
+    rule_id UUID,
+    -- Jurnal yang dibalik oleh jurnal reversal ini; satu jurnal hanya bisa dibalik sekali
+    reverses_journal_id UUID UNIQUE REFERENCES loyalty_journals(id),
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_loyalty_reversal CHECK ((kind = 'reversal') = (reverses_journal_id IS NOT NULL))
+);
+
+-- Satu transaksi hanya bisa mendapat poin sekali dan menukar poin sekali
+CREATE UNIQUE INDEX uq_loyalty_journal_transaction_kind
+    ON loyalty_journals(transaction_id, kind) WHERE kind IN ('earn', 'redeem');
+
+CREATE TABLE loyalty_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    journal_id UUID NOT NULL REFERENCES loyalty_journals(id) ON DELETE RESTRICT,
+    account_id UUID NOT NULL REFERENCES loyalty_accounts(id) ON DELETE RESTRICT,
+    -- Positif = kredit ke akun, negatif = debit dari akun
+    amount BIGINT NOT NULL CHECK (amount <> 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_loyalty_entries_account ON loyalty_entries(account_id, created_at);
+CREATE INDEX idx_loyalty_entries_journal ON loyalty_entries(journal_id);
+
+-- Ledger append-only: tolak UPDATE dan DELETE
+CREATE OR REPLACE FUNCTION reject_loyalty_ledger_mutation()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'Loyalty ledger is append-only (% on %)', TG_OP, TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER loyalty_journals_append_only
+BEFORE UPDATE OR DELETE ON loyalty_journals
+FOR EACH ROW EXECUTE FUNCTION reject_loyalty_ledger_mutation();
+
+CREATE TRIGGER loyalty_entries_append_only
+BEFORE UPDATE OR DELETE ON loyalty_entries
+FOR EACH ROW EXECUTE FUNCTION reject_loyalty_ledger_mutation();
+
+-- Setiap jurnal harus seimbang (total entri = 0), dicek saat commit
+CREATE OR REPLACE FUNCTION check_loyalty_journal_balanced()
+RETURNS TRIGGER AS $$
+DECLARE
+    total BIGINT;
+BEGIN
+    SELECT COALESCE(SUM(amount), 0) INTO total
+    FROM loyalty_entries WHERE journal_id = NEW.journal_id;
+
+    IF total <> 0 THEN
+        RAISE EXCEPTION 'Loyalty journal % is not balanced (total %)', NEW.journal_id, total;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE CONSTRAINT TRIGGER loyalty_entries_balanced
+AFTER INSERT ON loyalty_entries
+DEFERRABLE INITIALLY DEFERRED
+FOR EACH ROW EXECUTE FUNCTION check_loyalty_journal_balanced();
+
+-- Aturan perolehan poin. Aturan paling spesifik (brand + BBM > brand > BBM > umum)
+-- yang memenuhi min_amount dipakai.
+CREATE TABLE loyalty_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    brand_id UUID REFERENCES brands(id) ON DELETE CASCADE,
+    fuel_type VARCHAR(50),
+    min_amount DECIMAL(12, 2) NOT NULL DEFAULT 0 CHECK (min_amount >= 0),
+    -- Poin per Rp 1.000 yang dibayar
+    points_per_thousand DECIMAL(8, 2) NOT NULL CHECK (points_per_thousand >= 0),
+    bonus_points INTEGER NOT NULL DEFAULT 0 CHECK (bonus_points >= 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_loyalty_rules_updated_at
+BEFORE UPDATE ON loyalty_rules
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Aturan default: 1 poin per Rp 1.000
+INSERT INTO loyalty_rules (points_per_thousand) VALUES (1);
+
+-- Poin yang ditukar sebagai potongan harga pada transaksi
+ALTER TABLE transactions
+    ADD COLUMN points_redeemed BIGINT NOT NULL DEFAULT 0 CHECK (points_redeemed >= 0),
+    ADD COLUMN points_discount DECIMAL(12, 2) NOT NULL DEFAULT 0 CHECK (points_discount >= 0);
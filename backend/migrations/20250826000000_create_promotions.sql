@@ -0,0 +1,47 @@
+-- Kode promo / voucher potongan harga transaksi
+CREATE TABLE promotions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Kode disimpan dalam huruf besar
+    code VARCHAR(50) NOT NULL UNIQUE,
+    description TEXT,
+    discount_type VARCHAR(20) NOT NULL CHECK (discount_type IN ('percentage', 'fixed')),
+    discount_value DECIMAL(12, 2) NOT NULL CHECK (discount_value > 0),
+    -- Batas potongan untuk promo persentase
+    max_discount DECIMAL(12, 2) CHECK (max_discount > 0),
+    min_purchase DECIMAL(12, 2) NOT NULL DEFAULT 0 CHECK (min_purchase >= 0),
+    -- Cakupan promo; NULL berarti berlaku untuk semua
+    brand_id UUID REFERENCES brands(id) ON DELETE CASCADE,
+    spbu_id UUID REFERENCES spbu(id) ON DELETE CASCADE,
+    fuel_type VARCHAR(50),
+    first_time_only BOOLEAN NOT NULL DEFAULT FALSE,
+    starts_at TIMESTAMPTZ,
+    ends_at TIMESTAMPTZ,
+    -- Batas pemakaian total dan per user; NULL berarti tidak dibatasi
+    max_uses INTEGER CHECK (max_uses > 0),
+    max_uses_per_user INTEGER CHECK (max_uses_per_user > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_promotion_percentage CHECK (discount_type <> 'percentage' OR discount_value <= 100),
+    CONSTRAINT chk_promotion_window CHECK (starts_at IS NULL OR ends_at IS NULL OR starts_at < ends_at)
+);
+
+CREATE TRIGGER update_promotions_updated_at
+BEFORE UPDATE ON promotions
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Pemakaian promo per transaksi; transaksi yang dibatalkan tidak dihitung dalam batas pemakaian
+CREATE TABLE promotion_redemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    promotion_id UUID NOT NULL REFERENCES promotions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    transaction_id UUID NOT NULL UNIQUE REFERENCES transactions(id) ON DELETE CASCADE,
+    discount_amount DECIMAL(12, 2) NOT NULL CHECK (discount_amount >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_promotion_redemptions_promotion_user ON promotion_redemptions(promotion_id, user_id);
+
+ALTER TABLE transactions
+    ADD COLUMN promo_code VARCHAR(50),
+    ADD COLUMN promo_discount DECIMAL(12, 2) NOT NULL DEFAULT 0 CHECK (promo_discount >= 0);
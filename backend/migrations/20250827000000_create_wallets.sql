@@ -0,0 +1,70 @@
+-- Status refund untuk pembayaran yang dikembalikan ke dompet
+ALTER TYPE payment_status ADD VALUE IF NOT EXISTS 'refunded';
+
+-- CHECK lama dari saat kolom masih VARCHAR; nilai sudah dijaga oleh tipe enum
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_payment_status_check;
+
+-- Dompet saldo dalam aplikasi, satu per user.
+-- balance adalah saldo berjalan yang dikunci (FOR UPDATE) setiap kali berubah;
+-- riwayatnya dicatat di wallet_entries sehingga saldo bisa direkonsiliasi.
+CREATE TABLE wallets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    balance DECIMAL(14, 2) NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_wallets_updated_at
+BEFORE UPDATE ON wallets
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Top-up saldo yang dibayar melalui payment gateway
+CREATE TABLE wallet_topups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_id UUID NOT NULL REFERENCES wallets(id) ON DELETE CASCADE,
+    amount DECIMAL(14, 2) NOT NULL CHECK (amount > 0),
+    payment_method VARCHAR(50) NOT NULL,
+    payment_status payment_status NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    paid_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_wallet_topups_wallet ON wallet_topups(wallet_id, created_at);
+
+-- Mutasi saldo (append-only). amount positif = masuk, negatif = keluar.
+CREATE TABLE wallet_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_id UUID NOT NULL REFERENCES wallets(id) ON DELETE CASCADE,
+    entry_type VARCHAR(20) NOT NULL CHECK (entry_type IN ('topup', 'payment', 'refund')),
+    amount DECIMAL(14, 2) NOT NULL CHECK (amount <> 0),
+    balance_after DECIMAL(14, 2) NOT NULL CHECK (balance_after >= 0),
+    transaction_id UUID REFERENCES transactions(id) ON DELETE SET NULL,
+    topup_id UUID UNIQUE REFERENCES wallet_topups(id) ON DELETE SET NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_wallet_entries_wallet ON wallet_entries(wallet_id, created_at);
+
+-- Satu transaksi hanya bisa dibayar sekali dari dompet
+CREATE UNIQUE INDEX uq_wallet_entries_payment
+    ON wallet_entries(transaction_id) WHERE entry_type = 'payment';
+
+-- Riwayat tidak boleh diubah; penghapusan hanya lewat cascade/set null dari tabel induk
+CREATE OR REPLACE FUNCTION reject_wallet_entry_update()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.amount <> OLD.amount
+        OR NEW.balance_after <> OLD.balance_after
+        OR NEW.entry_type <> OLD.entry_type
+        OR NEW.wallet_id <> OLD.wallet_id THEN
+        RAISE EXCEPTION 'Wallet entries are append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER wallet_entries_append_only
+BEFORE UPDATE ON wallet_entries
+FOR EACH ROW EXECUTE FUNCTION reject_wallet_entry_update();
@@ -0,0 +1,83 @@
+-- Akun perusahaan/armada: driver mengisi BBM atas tagihan organisasi
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(150) NOT NULL,
+    billing_email VARCHAR(255),
+    tax_id VARCHAR(50),
+    -- NULL = semua jenis BBM / semua brand diperbolehkan
+    allowed_fuel_types TEXT[],
+    allowed_brand_ids UUID[],
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_organizations_updated_at
+BEFORE UPDATE ON organizations
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Anggota organisasi. Limit NULL = tidak dibatasi.
+CREATE TABLE organization_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL DEFAULT 'driver' CHECK (role IN ('admin', 'driver')),
+    daily_amount_limit DECIMAL(14, 2) CHECK (daily_amount_limit > 0),
+    monthly_amount_limit DECIMAL(14, 2) CHECK (monthly_amount_limit > 0),
+    daily_liter_limit DECIMAL(10, 2) CHECK (daily_liter_limit > 0),
+    monthly_liter_limit DECIMAL(10, 2) CHECK (monthly_liter_limit > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (organization_id, user_id)
+);
+
+CREATE INDEX idx_organization_members_user ON organization_members(user_id);
+
+CREATE TRIGGER update_organization_members_updated_at
+BEFORE UPDATE ON organization_members
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Kendaraan armada; satu kendaraan hanya terdaftar di satu organisasi
+CREATE TABLE organization_vehicles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    vehicle_id UUID NOT NULL UNIQUE REFERENCES vehicles(id) ON DELETE CASCADE,
+    daily_amount_limit DECIMAL(14, 2) CHECK (daily_amount_limit > 0),
+    monthly_amount_limit DECIMAL(14, 2) CHECK (monthly_amount_limit > 0),
+    daily_liter_limit DECIMAL(10, 2) CHECK (daily_liter_limit > 0),
+    monthly_liter_limit DECIMAL(10, 2) CHECK (monthly_liter_limit > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_organization_vehicles_org ON organization_vehicles(organization_id);
+
+CREATE TRIGGER update_organization_vehicles_updated_at
+BEFORE UPDATE ON organization_vehicles
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Tagihan bulanan organisasi (menggantikan pembayaran langsung per transaksi)
+CREATE TABLE organization_invoices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    total_amount DECIMAL(14, 2) NOT NULL CHECK (total_amount >= 0),
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'paid')),
+    due_date DATE NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    paid_at TIMESTAMPTZ,
+    UNIQUE (organization_id, period_start),
+    CHECK (period_end > period_start)
+);
+
+ALTER TABLE transactions
+    ADD COLUMN organization_id UUID REFERENCES organizations(id),
+    ADD COLUMN invoice_id UUID REFERENCES organization_invoices(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_organization
+    ON transactions(organization_id, created_at) WHERE organization_id IS NOT NULL;
@@ -0,0 +1,68 @@
+-- Rekening koran bulanan (statement) untuk user dan organisasi.
+-- Setelah diterbitkan, header dan baris statement adalah snapshot yang tidak boleh diubah.
+CREATE SEQUENCE statement_number_seq;
+
+CREATE TABLE statements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    statement_number VARCHAR(30) NOT NULL UNIQUE,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    -- Harga BBM sudah termasuk PPN; subtotal = DPP (dasar pengenaan pajak)
+    subtotal DECIMAL(14, 2) NOT NULL,
+    tax_rate DECIMAL(5, 2) NOT NULL,
+    tax_amount DECIMAL(14, 2) NOT NULL,
+    total_amount DECIMAL(14, 2) NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((user_id IS NULL) <> (organization_id IS NULL)),
+    CHECK (period_end > period_start)
+);
+
+CREATE UNIQUE INDEX uq_statements_user_period
+    ON statements(user_id, period_start) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX uq_statements_organization_period
+    ON statements(organization_id, period_start) WHERE organization_id IS NOT NULL;
+
+CREATE TABLE statement_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    statement_id UUID NOT NULL REFERENCES statements(id) ON DELETE CASCADE,
+    line_number INTEGER NOT NULL,
+    transaction_id UUID REFERENCES transactions(id) ON DELETE SET NULL,
+    transaction_date TIMESTAMPTZ NOT NULL,
+    user_id UUID,
+    spbu_id UUID,
+    spbu_name VARCHAR(100) NOT NULL,
+    vehicle_id UUID,
+    plate_number VARCHAR(15),
+    fuel_type VARCHAR(50) NOT NULL,
+    quantity DECIMAL(10, 2) NOT NULL,
+    price_per_liter DECIMAL(10, 2) NOT NULL,
+    discount DECIMAL(14, 2) NOT NULL,
+    amount DECIMAL(14, 2) NOT NULL,
+    subtotal DECIMAL(14, 2) NOT NULL,
+    tax_amount DECIMAL(14, 2) NOT NULL,
+    UNIQUE (statement_id, line_number)
+);
+
+CREATE OR REPLACE FUNCTION reject_statement_update()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- transaction_id boleh menjadi NULL karena ON DELETE SET NULL
+    IF TG_TABLE_NAME = 'statement_lines'
+        AND NEW.transaction_id IS NULL
+        AND (to_jsonb(NEW) - 'transaction_id') = (to_jsonb(OLD) - 'transaction_id') THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'Issued statements are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER statements_immutable
+BEFORE UPDATE ON statements
+FOR EACH ROW EXECUTE FUNCTION reject_statement_update();
+
+CREATE TRIGGER statement_lines_immutable
+BEFORE UPDATE ON statement_lines
+FOR EACH ROW EXECUTE FUNCTION reject_statement_update();
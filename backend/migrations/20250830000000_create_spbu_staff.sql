@@ -0,0 +1,29 @@
+-- Operator SPBU: user yang mengelola SPBU tertentu
+CREATE TABLE spbu_staff (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    spbu_id UUID NOT NULL REFERENCES spbu(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL DEFAULT 'cashier' CHECK (role IN ('owner', 'manager', 'cashier')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (spbu_id, user_id)
+);
+
+CREATE INDEX idx_spbu_staff_user ON spbu_staff(user_id);
+
+CREATE TRIGGER update_spbu_staff_updated_at
+BEFORE UPDATE ON spbu_staff
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Jam operasional. jam_buka/jam_tutup NULL = belum diisi operator.
+-- jam_tutup < jam_buka berarti tutup keesokan harinya.
+ALTER TABLE spbu
+    ADD COLUMN buka_24_jam BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN jam_buka TIME,
+    ADD COLUMN jam_tutup TIME;
+
+-- Stok BBM per jenis. NULL = stok tidak dipantau.
+ALTER TABLE fuel_prices
+    ADD COLUMN stock_liters DECIMAL(12, 2) CHECK (stock_liters >= 0);
+
+COMMENT ON COLUMN fuel_prices.stock_liters IS 'Remaining stock in liters, reported by the station operator';
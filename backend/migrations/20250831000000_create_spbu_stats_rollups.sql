@@ -0,0 +1,85 @@
+-- Rollup statistik SPBU untuk dashboard operator.
+-- Bucket per jam (UTC); agregasi harian/mingguan dihitung dari bucket ini.
+
+CREATE TABLE spbu_hourly_sales (
+    spbu_id UUID NOT NULL REFERENCES spbu(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    fuel_type VARCHAR(50) NOT NULL,
+    payment_method VARCHAR(50) NOT NULL,
+    transaction_count INT NOT NULL DEFAULT 0,
+    completed_count INT NOT NULL DEFAULT 0,
+    cancelled_count INT NOT NULL DEFAULT 0,
+    -- Hanya dari transaksi completed
+    liters_sold DECIMAL(14, 2) NOT NULL DEFAULT 0,
+    revenue DECIMAL(16, 2) NOT NULL DEFAULT 0,
+    PRIMARY KEY (spbu_id, bucket_start, fuel_type, payment_method)
+);
+
+CREATE TABLE spbu_hourly_reviews (
+    spbu_id UUID NOT NULL REFERENCES spbu(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    review_count INT NOT NULL DEFAULT 0,
+    rating_sum DOUBLE PRECISION NOT NULL DEFAULT 0,
+    PRIMARY KEY (spbu_id, bucket_start)
+);
+
+-- Bucket yang datanya berubah sejak refresh terakhir
+CREATE TABLE spbu_stats_dirty (
+    spbu_id UUID NOT NULL,
+    source VARCHAR(20) NOT NULL CHECK (source IN ('transactions', 'reviews')),
+    bucket_start TIMESTAMPTZ NOT NULL,
+    marked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (spbu_id, source, bucket_start)
+);
+
+-- DO UPDATE (bukan DO NOTHING) supaya baris ikut terkunci sampai transaksi penulis commit;
+-- refresh yang menghapus baris ini akan menunggu dan membaca data terbaru.
+CREATE OR REPLACE FUNCTION mark_spbu_stats_dirty()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        INSERT INTO spbu_stats_dirty (spbu_id, source, bucket_start)
+        VALUES (OLD.spbu_id, TG_TABLE_NAME, date_trunc('hour', OLD.created_at, 'UTC'))
+        ON CONFLICT (spbu_id, source, bucket_start) DO UPDATE SET marked_at = NOW();
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        INSERT INTO spbu_stats_dirty (spbu_id, source, bucket_start)
+        VALUES (NEW.spbu_id, TG_TABLE_NAME, date_trunc('hour', NEW.created_at, 'UTC'))
+        ON CONFLICT (spbu_id, source, bucket_start) DO UPDATE SET marked_at = NOW();
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER mark_spbu_stats_dirty_insert_delete
+AFTER INSERT OR DELETE ON transactions
+FOR EACH ROW EXECUTE FUNCTION mark_spbu_stats_dirty();
+
+CREATE TRIGGER mark_spbu_stats_dirty_update
+AFTER UPDATE ON transactions
+FOR EACH ROW
+WHEN (
+    (OLD.spbu_id, OLD.created_at, OLD.fuel_type, OLD.payment_method, OLD.status, OLD.quantity, OLD.total_price)
+    IS DISTINCT FROM
+    (NEW.spbu_id, NEW.created_at, NEW.fuel_type, NEW.payment_method, NEW.status, NEW.quantity, NEW.total_price)
+)
+EXECUTE FUNCTION mark_spbu_stats_dirty();
+
+CREATE TRIGGER mark_spbu_stats_dirty_insert_delete
+AFTER INSERT OR DELETE ON reviews
+FOR EACH ROW EXECUTE FUNCTION mark_spbu_stats_dirty();
+
+CREATE TRIGGER mark_spbu_stats_dirty_update
+AFTER UPDATE ON reviews
+FOR EACH ROW
+WHEN ((OLD.spbu_id, OLD.created_at, OLD.rating) IS DISTINCT FROM (NEW.spbu_id, NEW.created_at, NEW.rating))
+EXECUTE FUNCTION mark_spbu_stats_dirty();
+
+CREATE INDEX IF NOT EXISTS idx_transactions_spbu_created_at ON transactions(spbu_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_reviews_spbu_created_at ON reviews(spbu_id, created_at);
+
+-- Data lama diproses pada refresh pertama
+INSERT INTO spbu_stats_dirty (spbu_id, source, bucket_start)
+SELECT DISTINCT spbu_id, 'transactions', date_trunc('hour', created_at, 'UTC') FROM transactions
+UNION
+SELECT DISTINCT spbu_id, 'reviews', date_trunc('hour', created_at, 'UTC') FROM reviews;
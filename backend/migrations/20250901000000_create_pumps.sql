@@ -0,0 +1,44 @@
+-- Pompa per SPBU. fuel_types kosong = melayani semua jenis BBM yang dijual SPBU.
+CREATE TABLE pumps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    spbu_id UUID NOT NULL REFERENCES spbu(id) ON DELETE CASCADE,
+    pump_number INT NOT NULL CHECK (pump_number > 0),
+    fuel_types TEXT[] NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'maintenance')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (spbu_id, pump_number)
+);
+
+CREATE TRIGGER update_pumps_updated_at
+BEFORE UPDATE ON pumps
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- spbu.jumlah_pompa sekarang mengikuti jumlah baris di pumps
+CREATE OR REPLACE FUNCTION sync_spbu_pump_count()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE spbu
+    SET jumlah_pompa = (
+        SELECT COUNT(*) FROM pumps WHERE spbu_id = COALESCE(NEW.spbu_id, OLD.spbu_id)
+    )
+    WHERE id = COALESCE(NEW.spbu_id, OLD.spbu_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sync_spbu_pump_count_after_change
+AFTER INSERT OR DELETE ON pumps
+FOR EACH ROW EXECUTE FUNCTION sync_spbu_pump_count();
+
+-- Pompa untuk SPBU yang sudah ada, sesuai jumlah_pompa lama
+INSERT INTO pumps (spbu_id, pump_number)
+SELECT s.id, n
+FROM spbu s
+CROSS JOIN LATERAL generate_series(1, COALESCE(s.jumlah_pompa, 0)) AS n;
+
+-- Pompa yang melayani pesanan, diatur operator
+ALTER TABLE transactions
+    ADD COLUMN pump_id UUID REFERENCES pumps(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_pump_id ON transactions(pump_id);
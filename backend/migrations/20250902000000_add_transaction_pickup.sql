@@ -0,0 +1,7 @@
+-- Pengambilan BBM dengan QR: kode pickup ditandatangani server dan hanya bisa ditukar sekali.
+-- pickup_nonce diganti setiap kali kode baru dibuat sehingga kode lama tidak berlaku.
+ALTER TABLE transactions
+    ADD COLUMN pickup_nonce UUID,
+    ADD COLUMN pickup_redeemed_at TIMESTAMPTZ,
+    ADD COLUMN pickup_redeemed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN dispensed_quantity DECIMAL(10, 2) CHECK (dispensed_quantity > 0);
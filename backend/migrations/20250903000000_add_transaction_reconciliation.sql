@@ -0,0 +1,25 @@
+-- Rekonsiliasi liter yang benar-benar dikeluarkan pompa.
+-- quantity/total_price tetap nilai pesanan; dispensed_quantity/final_price adalah nilai aktual.
+-- adjustment_amount = final_price - total_price (negatif = kelebihan bayar yang dikembalikan).
+ALTER TABLE transactions
+    ADD COLUMN final_price DECIMAL(10, 2) CHECK (final_price >= 0),
+    ADD COLUMN adjustment_amount DECIMAL(10, 2),
+    ADD COLUMN adjustment_status VARCHAR(20)
+        CHECK (adjustment_status IN ('refunded', 'underpaid', 'invoiced'));
+
+CREATE INDEX idx_transactions_underpaid ON transactions(user_id) WHERE adjustment_status = 'underpaid';
+
+-- Statistik penjualan memakai nilai aktual, jadi perubahan rekonsiliasi juga menandai bucket
+DROP TRIGGER mark_spbu_stats_dirty_update ON transactions;
+
+CREATE TRIGGER mark_spbu_stats_dirty_update
+AFTER UPDATE ON transactions
+FOR EACH ROW
+WHEN (
+    (OLD.spbu_id, OLD.created_at, OLD.fuel_type, OLD.payment_method, OLD.status, OLD.quantity, OLD.total_price,
+     OLD.dispensed_quantity, OLD.final_price)
+    IS DISTINCT FROM
+    (NEW.spbu_id, NEW.created_at, NEW.fuel_type, NEW.payment_method, NEW.status, NEW.quantity, NEW.total_price,
+     NEW.dispensed_quantity, NEW.final_price)
+)
+EXECUTE FUNCTION mark_spbu_stats_dirty();
@@ -0,0 +1,15 @@
+-- Riwayat eksekusi background job terjadwal (satu baris per run yang benar-benar berjalan)
+CREATE TABLE job_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_name VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('succeeded', 'failed')),
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    affected_rows BIGINT NOT NULL DEFAULT 0,
+    error TEXT
+);
+
+CREATE INDEX idx_job_runs_job_started ON job_runs(job_name, started_at DESC);
+
+-- Dipakai job kedaluwarsa transaksi pending
+CREATE INDEX idx_transactions_pending_created_at ON transactions(created_at) WHERE status = 'pending';
@@ -0,0 +1,43 @@
+-- Antrian job yang tahan restart. Worker mengambil job dengan FOR UPDATE SKIP LOCKED.
+-- pending -> running -> succeeded, atau kembali ke pending (retry dengan backoff) sampai
+-- max_attempts habis lalu menjadi dead.
+CREATE TABLE queued_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'succeeded', 'dead')),
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5 CHECK (max_attempts > 0),
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_at TIMESTAMPTZ,
+    locked_by VARCHAR(100),
+    last_error TEXT,
+    -- Mencegah job yang sama (mis. satu jadwal cron) masuk antrian dua kali
+    dedupe_key VARCHAR(200),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER update_queued_jobs_updated_at
+BEFORE UPDATE ON queued_jobs
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE UNIQUE INDEX idx_queued_jobs_dedupe_key ON queued_jobs(dedupe_key) WHERE dedupe_key IS NOT NULL;
+CREATE INDEX idx_queued_jobs_ready ON queued_jobs(run_at) WHERE status = 'pending';
+CREATE INDEX idx_queued_jobs_running ON queued_jobs(locked_at) WHERE status = 'running';
+CREATE INDEX idx_queued_jobs_status_created ON queued_jobs(status, created_at DESC);
+
+-- Jadwal cron yang didefinisikan di kode; next_run_at disimpan supaya tidak hilang saat restart
+CREATE TABLE job_schedules (
+    name VARCHAR(100) PRIMARY KEY,
+    cron_expression VARCHAR(100) NOT NULL,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_enqueued_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_job_schedules_updated_at
+BEFORE UPDATE ON job_schedules
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
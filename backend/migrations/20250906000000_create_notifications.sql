@@ -0,0 +1,36 @@
+-- Kotak masuk notifikasi in-app
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event VARCHAR(50) NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    -- Data pendukung untuk aplikasi, mis. transaction_id untuk membuka detail transaksi
+    data JSONB NOT NULL DEFAULT '{}',
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notifications_user_created ON notifications(user_id, created_at DESC);
+CREATE INDEX idx_notifications_user_unread ON notifications(user_id) WHERE read_at IS NULL;
+
+-- Pengaturan per event; event tanpa baris memakai default di kode
+CREATE TABLE notification_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event VARCHAR(50) NOT NULL,
+    in_app BOOLEAN NOT NULL,
+    email BOOLEAN NOT NULL,
+    sms BOOLEAN NOT NULL,
+    push BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, event)
+);
+
+CREATE TRIGGER update_notification_preferences_updated_at
+BEFORE UPDATE ON notification_preferences
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Bahasa template notifikasi
+ALTER TABLE users
+    ADD COLUMN notification_language VARCHAR(2) NOT NULL DEFAULT 'id'
+        CHECK (notification_language IN ('id', 'en'));
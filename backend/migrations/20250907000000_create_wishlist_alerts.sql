@@ -0,0 +1,26 @@
+-- Aturan notifikasi untuk SPBU di wishlist
+CREATE TABLE wishlist_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wishlist_id UUID NOT NULL REFERENCES wishlists(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('price_below', 'back_in_stock', 'queue_below')),
+    fuel_type VARCHAR(50),
+    threshold DECIMAL(12, 2) CHECK (threshold >= 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Kondisi terpenuhi pada evaluasi terakhir. Notifikasi hanya dikirim saat berubah
+    -- dari FALSE ke TRUE, sehingga kondisi yang bertahan tidak dikirim berulang kali.
+    triggered BOOLEAN NOT NULL DEFAULT FALSE,
+    last_triggered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (
+        (kind = 'price_below' AND fuel_type IS NOT NULL AND threshold IS NOT NULL)
+        OR (kind = 'back_in_stock' AND fuel_type IS NOT NULL AND threshold IS NULL)
+        OR (kind = 'queue_below' AND fuel_type IS NULL AND threshold IS NOT NULL)
+    )
+);
+
+CREATE INDEX idx_wishlist_alerts_wishlist ON wishlist_alerts(wishlist_id) WHERE is_active;
+
+CREATE TRIGGER update_wishlist_alerts_updated_at
+BEFORE UPDATE ON wishlist_alerts
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
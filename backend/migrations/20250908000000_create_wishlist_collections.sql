@@ -0,0 +1,40 @@
+-- update_modified_column() sudah didefinisikan ulang untuk transactions (memakai kolom
+-- payment_status), sehingga UPDATE pada wishlists selalu gagal. Pakai fungsi umum.
+DROP TRIGGER IF EXISTS update_wishlists_modtime ON wishlists;
+
+CREATE TRIGGER update_wishlists_updated_at
+BEFORE UPDATE ON wishlists
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Koleksi wishlist bernama, misalnya "Rute kantor" atau "Mudik"
+CREATE TABLE wishlist_collections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- Token link baca-saja; NULL = tidak dibagikan
+    share_token VARCHAR(64) UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE TRIGGER update_wishlist_collections_updated_at
+BEFORE UPDATE ON wishlist_collections
+FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Item wishlist: koleksi (opsional), catatan pribadi dan urutan
+ALTER TABLE wishlists
+    ADD COLUMN collection_id UUID REFERENCES wishlist_collections(id) ON DELETE SET NULL,
+    ADD COLUMN note TEXT,
+    ADD COLUMN position INT NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_wishlists_collection_id ON wishlists(collection_id);
+
+-- Urutan awal item yang sudah ada mengikuti waktu ditambahkan
+UPDATE wishlists w
+SET position = ordered.rn
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY created_at, id) - 1 AS rn
+    FROM wishlists
+) ordered
+WHERE ordered.id = w.id;
@@ -0,0 +1,8 @@
+-- Tombstone SPBU yang dihapus dari wishlist, dipakai sinkronisasi offline untuk membedakan
+-- "belum pernah ditambahkan" dari "sudah dihapus setelah perubahan di perangkat"
+CREATE TABLE wishlist_deletions (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    spbu_id UUID NOT NULL REFERENCES spbu(id) ON DELETE CASCADE,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, spbu_id)
+);
@@ -0,0 +1,18 @@
+-- Foto review disimpan di media_objects dengan owner_type 'review'
+ALTER TABLE media_objects DROP CONSTRAINT media_objects_owner_type_check;
+ALTER TABLE media_objects ADD CONSTRAINT media_objects_owner_type_check
+    CHECK (owner_type IN ('spbu', 'brand', 'service', 'user', 'review'));
+
+-- Vote "membantu" untuk review; satu vote per user per review
+CREATE TABLE review_votes (
+    review_id UUID NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (review_id, user_id)
+);
+
+CREATE INDEX idx_review_votes_user_id ON review_votes(user_id);
+
+-- Daftar review per SPBU diurutkan terbaru atau berdasarkan rating
+CREATE INDEX idx_reviews_spbu_created ON reviews(spbu_id, created_at DESC);
+CREATE INDEX idx_reviews_spbu_rating ON reviews(spbu_id, rating);
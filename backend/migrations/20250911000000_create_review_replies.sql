@@ -0,0 +1,25 @@
+-- Balasan publik operator SPBU untuk review; satu balasan per review
+CREATE TABLE review_replies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    review_id UUID NOT NULL UNIQUE REFERENCES reviews(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL CHECK (length(btrim(body)) > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_review_replies_updated_at
+BEFORE UPDATE ON review_replies
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
+
+-- Isi balasan sebelum setiap perubahan
+CREATE TABLE review_reply_edits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reply_id UUID NOT NULL REFERENCES review_replies(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    edited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_review_reply_edits_reply_id ON review_reply_edits(reply_id, edited_at);
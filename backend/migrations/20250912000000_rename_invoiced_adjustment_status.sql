@@ -0,0 +1,9 @@
+-- Transaksi organisasi direkonsiliasi sebelum masuk tagihan (tagihan hanya memuat transaksi completed),
+-- jadi statusnya adalah "menunggu tagihan", bukan "sudah ditagih".
+ALTER TABLE transactions DROP CONSTRAINT transactions_adjustment_status_check;
+
+UPDATE transactions SET adjustment_status = 'pending_invoice' WHERE adjustment_status = 'invoiced';
+
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_adjustment_status_check
+    CHECK (adjustment_status IN ('refunded', 'underpaid', 'pending_invoice'));
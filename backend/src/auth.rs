@@ -10,6 +10,9 @@ use std::env;
 use chrono::{Duration, Utc};
 use uuid::Uuid;
 use tracing;
+use sqlx::PgExecutor;
+
+use crate::error::AppError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -64,6 +67,19 @@ pub fn validate_token(token: &str) -> Result<Claims, AuthError> {
     }
 }
 
+/// Hanya user dengan role `admin` (admin platform, bukan admin organisasi).
+/// Role dibaca dari database, bukan dari token, agar pencabutan akses langsung berlaku.
+pub async fn require_platform_admin<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid) -> crate::error::Result<()> {
+    let role: Option<String> = sqlx::query_scalar("SELECT role FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await?;
+    if role.as_deref() != Some("admin") {
+        return Err(AppError::Forbidden("Only administrators can perform this action".to_string()));
+    }
+    Ok(())
+}
+
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
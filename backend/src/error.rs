@@ -0,0 +1,77 @@
+use axum::{
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound(String),
+    Forbidden(String),
+    Conflict(String),
+    InternalServerError(String),
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<(StatusCode, String)> for AppError {
+    fn from((status, message): (StatusCode, String)) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => AppError::BadRequest(message),
+            StatusCode::NOT_FOUND => AppError::NotFound(message),
+            StatusCode::FORBIDDEN => AppError::Forbidden(message),
+            StatusCode::CONFLICT => AppError::Conflict(message),
+            _ => AppError::InternalServerError(message),
+        }
+    }
+}
+
+// Untuk handler lama yang masih memakai (StatusCode, String) sebagai error
+impl From<AppError> for (StatusCode, String) {
+    fn from(err: AppError) -> Self {
+        match err {
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message),
+            AppError::Conflict(message) => (StatusCode::CONFLICT, message),
+            AppError::InternalServerError(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+            AppError::DatabaseError(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, error_message): (StatusCode, String) = self.into();
+
+        let body = Json(json!({
+            "error": error_message,
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::BadRequest(msg) => write!(f, "Bad Request: {}", msg),
+            AppError::NotFound(msg) => write!(f, "Not Found: {}", msg),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::InternalServerError(msg) => write!(f, "Internal Server Error: {}", msg),
+            AppError::DatabaseError(msg) => write!(f, "Database Error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+pub type Result<T> = std::result::Result<T, AppError>;
@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use std::path::Path;
+
+use super::{haversine_km, Coordinates, GeocodeError, Geocoder};
+
+// Reverse geocoding hanya mengembalikan tempat dalam radius ini
+const MAX_REVERSE_DISTANCE_KM: f64 = 25.0;
+
+struct Place {
+    name: String,
+    name_lower: String,
+    coordinates: Coordinates,
+}
+
+/// Geocoder offline berbasis file gazetteer.
+///
+/// Setiap baris berformat `latitude,longitude,nama tempat`; baris kosong dan
+/// baris yang diawali `#` diabaikan.
+pub struct GazetteerGeocoder {
+    places: Vec<Place>,
+}
+
+impl GazetteerGeocoder {
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let places = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ',');
+                let latitude = parts.next()?.trim().parse().ok()?;
+                let longitude = parts.next()?.trim().parse().ok()?;
+                let name = parts.next()?.trim().to_string();
+                Some(Place {
+                    name_lower: name.to_lowercase(),
+                    name,
+                    coordinates: Coordinates { latitude, longitude },
+                })
+            })
+            .collect();
+        Self { places }
+    }
+
+    pub fn place_count(&self) -> usize {
+        self.places.len()
+    }
+
+    fn ensure_loaded(&self) -> Result<(), GeocodeError> {
+        if self.places.is_empty() {
+            return Err(GeocodeError::Unavailable("gazetteer is empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Geocoder for GazetteerGeocoder {
+    async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, GeocodeError> {
+        self.ensure_loaded()?;
+        let address = address.to_lowercase();
+        // Semua bagian nama tempat (dipisah koma) harus muncul di alamat;
+        // jika beberapa cocok, pilih yang paling spesifik
+        let best = self
+            .places
+            .iter()
+            .filter(|place| {
+                place
+                    .name_lower
+                    .split(',')
+                    .all(|part| address.contains(part.trim()))
+            })
+            .max_by_key(|place| place.name_lower.len());
+        Ok(best.map(|place| place.coordinates))
+    }
+
+    async fn reverse_geocode(&self, coordinates: Coordinates) -> Result<Option<String>, GeocodeError> {
+        self.ensure_loaded()?;
+        let nearest = self
+            .places
+            .iter()
+            .map(|place| (place, haversine_km(coordinates, place.coordinates)))
+            .filter(|(_, distance)| *distance <= MAX_REVERSE_DISTANCE_KM)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(nearest.map(|(place, _)| place.name.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geocoding::is_within_indonesia;
+
+    const CSV: &str = "\
+# latitude,longitude,nama
+-6.2000,106.8166,Jakarta
+
+-6.1862,106.8341,Menteng, Jakarta
+-6.9147,107.6098,Bandung
+bukan,angka,Baris Rusak
+-7.2575
+-7.2575,112.7521
+";
+
+    fn point(latitude: f64, longitude: f64) -> Coordinates {
+        Coordinates { latitude, longitude }
+    }
+
+    #[test]
+    fn parse_skips_comments_blank_and_malformed_rows() {
+        let geocoder = GazetteerGeocoder::parse(CSV);
+        assert_eq!(geocoder.place_count(), 3);
+        // Koma setelah kolom kedua tetap bagian dari nama
+        assert!(geocoder.places.iter().any(|place| place.name == "Menteng, Jakarta"));
+    }
+
+    #[tokio::test]
+    async fn geocode_matches_case_insensitive_substrings() {
+        let geocoder = GazetteerGeocoder::parse(CSV);
+        assert_eq!(
+            geocoder.geocode("Jl. Asia Afrika, BANDUNG").await.unwrap(),
+            Some(point(-6.9147, 107.6098))
+        );
+        // Menteng, Jakarta lebih spesifik daripada Jakarta
+        assert_eq!(
+            geocoder.geocode("jl. cikini raya, menteng, jakarta pusat").await.unwrap(),
+            Some(point(-6.1862, 106.8341))
+        );
+        assert_eq!(geocoder.geocode("Surabaya").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reverse_geocode_respects_distance_cutoff() {
+        let geocoder = GazetteerGeocoder::parse(CSV);
+        let bandung = point(-6.9147, 107.6098);
+        let km_per_degree = haversine_km(point(0.0, 0.0), point(1.0, 0.0));
+
+        let inside = point(bandung.latitude - 24.9 / km_per_degree, bandung.longitude);
+        assert_eq!(geocoder.reverse_geocode(inside).await.unwrap(), Some("Bandung".to_string()));
+
+        let outside = point(bandung.latitude - 25.1 / km_per_degree, bandung.longitude);
+        assert_eq!(geocoder.reverse_geocode(outside).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reverse_geocode_outside_indonesia_finds_nothing() {
+        let geocoder = GazetteerGeocoder::parse(CSV);
+        let darwin = point(-12.4634, 130.8456);
+        assert!(!is_within_indonesia(darwin));
+        assert_eq!(geocoder.reverse_geocode(darwin).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn empty_gazetteer_is_unavailable() {
+        let geocoder = GazetteerGeocoder::parse("# kosong\n");
+        assert!(geocoder.geocode("Jakarta").await.is_err());
+        assert!(geocoder.reverse_geocode(point(-6.2, 106.8)).await.is_err());
+    }
+}
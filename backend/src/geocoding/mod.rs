@@ -0,0 +1,52 @@
+pub mod gazetteer;
+pub mod route;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+pub use gazetteer::GazetteerGeocoder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GeocodeError {
+    #[error("Geocoder unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Konversi alamat ke koordinat dan sebaliknya.
+///
+/// `Ok(None)` berarti geocoder berjalan normal tetapi tidak menemukan hasil.
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, GeocodeError>;
+    async fn reverse_geocode(&self, coordinates: Coordinates) -> Result<Option<String>, GeocodeError>;
+}
+
+// Kilometer per derajat lintang (kurang lebih konstan)
+pub const KM_PER_DEGREE: f64 = 111.32;
+
+// Bounding box daratan dan perairan Indonesia (Sabang - Merauke, Miangas - Rote)
+const INDONESIA_MIN_LAT: f64 = -11.2;
+const INDONESIA_MAX_LAT: f64 = 6.3;
+const INDONESIA_MIN_LNG: f64 = 94.7;
+const INDONESIA_MAX_LNG: f64 = 141.2;
+
+pub fn is_within_indonesia(coordinates: Coordinates) -> bool {
+    (INDONESIA_MIN_LAT..=INDONESIA_MAX_LAT).contains(&coordinates.latitude)
+        && (INDONESIA_MIN_LNG..=INDONESIA_MAX_LNG).contains(&coordinates.longitude)
+}
+
+/// Jarak great-circle dalam kilometer (rumus haversine)
+pub fn haversine_km(a: Coordinates, b: Coordinates) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let d_lat = (b.latitude - a.latitude).to_radians();
+    let d_lng = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2)
+        + a.latitude.to_radians().cos() * b.latitude.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
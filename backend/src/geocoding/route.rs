@@ -0,0 +1,213 @@
+use super::{haversine_km, Coordinates, KM_PER_DEGREE};
+
+/// Decode polyline format Google (presisi 5 digit desimal)
+pub fn decode_polyline(encoded: &str) -> Option<Vec<Coordinates>> {
+    let bytes = encoded.as_bytes();
+    let mut index = 0;
+    let mut lat: i64 = 0;
+    let mut lng: i64 = 0;
+    let mut points = Vec::new();
+
+    let next_value = |index: &mut usize| -> Option<i64> {
+        let mut result: i64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = (*bytes.get(*index)? as i64) - 63;
+            if !(0..64).contains(&byte) || shift > 60 {
+                return None;
+            }
+            *index += 1;
+            result |= (byte & 0x1f) << shift;
+            shift += 5;
+            if byte < 0x20 {
+                break;
+            }
+        }
+        Some(if result & 1 != 0 { !(result >> 1) } else { result >> 1 })
+    };
+
+    while index < bytes.len() {
+        lat = lat.checked_add(next_value(&mut index)?)?;
+        lng = lng.checked_add(next_value(&mut index)?)?;
+        let point = Coordinates {
+            latitude: lat as f64 / 1e5,
+            longitude: lng as f64 / 1e5,
+        };
+        // Polyline dari luar bisa saja sampah; titik di luar bumi ditolak
+        if point.latitude.abs() > 90.0 || point.longitude.abs() > 180.0 {
+            return None;
+        }
+        points.push(point);
+    }
+
+    Some(points)
+}
+
+/// Posisi sebuah titik relatif terhadap rute
+#[derive(Debug, Clone, Copy)]
+pub struct RoutePosition {
+    /// Jarak dari titik awal rute ke proyeksi titik pada rute
+    pub distance_along_km: f64,
+    /// Jarak tegak lurus titik ke rute
+    pub distance_from_route_km: f64,
+}
+
+/// Rute berupa polyline dengan panjang kumulatif per titik
+pub struct Route {
+    points: Vec<Coordinates>,
+    cumulative_km: Vec<f64>,
+}
+
+impl Route {
+    pub fn new(points: Vec<Coordinates>) -> Self {
+        let mut cumulative_km = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                total += haversine_km(points[i - 1], *point);
+            }
+            cumulative_km.push(total);
+        }
+        Self { points, cumulative_km }
+    }
+
+    pub fn length_km(&self) -> f64 {
+        self.cumulative_km.last().copied().unwrap_or(0.0)
+    }
+
+    /// Bounding box rute `(min, max)` yang diperlebar `margin_km` ke segala arah
+    pub fn bounding_box(&self, margin_km: f64) -> (Coordinates, Coordinates) {
+        let mut min = Coordinates { latitude: f64::MAX, longitude: f64::MAX };
+        let mut max = Coordinates { latitude: f64::MIN, longitude: f64::MIN };
+        for point in &self.points {
+            min.latitude = min.latitude.min(point.latitude);
+            min.longitude = min.longitude.min(point.longitude);
+            max.latitude = max.latitude.max(point.latitude);
+            max.longitude = max.longitude.max(point.longitude);
+        }
+
+        let lat_margin = margin_km / KM_PER_DEGREE;
+        // Derajat bujur makin pendek menjauhi khatulistiwa
+        let widest_lat = min.latitude.abs().max(max.latitude.abs()).min(89.0);
+        let lng_margin = margin_km / (KM_PER_DEGREE * widest_lat.to_radians().cos());
+
+        (
+            Coordinates {
+                latitude: min.latitude - lat_margin,
+                longitude: min.longitude - lng_margin,
+            },
+            Coordinates {
+                latitude: max.latitude + lat_margin,
+                longitude: max.longitude + lng_margin,
+            },
+        )
+    }
+
+    /// Cari segmen terdekat dengan titik. Jarak dihitung dengan proyeksi
+    /// equirectangular lokal per segmen, cukup akurat untuk jarak detour beberapa km.
+    pub fn locate(&self, point: Coordinates) -> Option<RoutePosition> {
+        if self.points.len() == 1 {
+            return Some(RoutePosition {
+                distance_along_km: 0.0,
+                distance_from_route_km: haversine_km(self.points[0], point),
+            });
+        }
+
+        let mut best: Option<RoutePosition> = None;
+        for (i, segment) in self.points.windows(2).enumerate() {
+            let (a, b) = (segment[0], segment[1]);
+            let km_per_lng = KM_PER_DEGREE * a.latitude.to_radians().cos();
+
+            // Koordinat planar (km) relatif terhadap titik a
+            let bx = (b.longitude - a.longitude) * km_per_lng;
+            let by = (b.latitude - a.latitude) * KM_PER_DEGREE;
+            let px = (point.longitude - a.longitude) * km_per_lng;
+            let py = (point.latitude - a.latitude) * KM_PER_DEGREE;
+
+            let length_sq = bx * bx + by * by;
+            let t = if length_sq > 0.0 {
+                ((px * bx + py * by) / length_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let offset = ((px - t * bx).powi(2) + (py - t * by).powi(2)).sqrt();
+            let segment_km = self.cumulative_km[i + 1] - self.cumulative_km[i];
+
+            let candidate = RoutePosition {
+                distance_along_km: self.cumulative_km[i] + t * segment_km,
+                distance_from_route_km: offset,
+            };
+            if best.is_none_or(|b| candidate.distance_from_route_km < b.distance_from_route_km) {
+                best = Some(candidate);
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(latitude: f64, longitude: f64) -> Coordinates {
+        Coordinates { latitude, longitude }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn decode_polyline_reference_example() {
+        // Contoh dari dokumentasi format polyline Google
+        let points = decode_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@").unwrap();
+        assert_eq!(points, vec![point(38.5, -120.2), point(40.7, -120.95), point(43.252, -126.453)]);
+    }
+
+    #[test]
+    fn decode_polyline_empty_and_invalid() {
+        assert_eq!(decode_polyline(""), Some(vec![]));
+        // Berhenti di tengah nilai
+        assert_eq!(decode_polyline("_p~iF~ps|"), None);
+        // Hanya lintang tanpa bujur
+        assert_eq!(decode_polyline("_p~iF"), None);
+        // Karakter di luar rentang encoding
+        assert_eq!(decode_polyline("_p~iF ps|U"), None);
+    }
+
+    #[test]
+    fn decode_polyline_rejects_overflow_and_out_of_range() {
+        // Nilai mendekati batas i64; dijumlahkan berulang akan overflow
+        assert_eq!(decode_polyline(&"}~~~~~~~~~~~F?".repeat(3)), None);
+        assert_eq!(decode_polyline("}~~~~~~~~~~~F?"), None);
+        // Lintang 91 dan bujur 181
+        assert_eq!(decode_polyline("_mljP?"), None);
+        assert_eq!(decode_polyline("?_qvoa@"), None);
+        // Tepat di batas masih diterima
+        assert_eq!(decode_polyline("_cidP~fsia@"), Some(vec![point(90.0, -180.0)]));
+    }
+
+    #[test]
+    fn bounding_box_without_margin_covers_all_points() {
+        let route = Route::new(vec![point(-6.2, 106.8), point(-6.9, 107.6), point(-6.5, 107.0)]);
+        let (min, max) = route.bounding_box(0.0);
+        assert_eq!(min, point(-6.9, 106.8));
+        assert_eq!(max, point(-6.2, 107.6));
+    }
+
+    #[test]
+    fn bounding_box_margin_is_in_kilometers() {
+        let route = Route::new(vec![point(0.0, 100.0)]);
+        let (min, max) = route.bounding_box(KM_PER_DEGREE);
+        assert_close(min.latitude, -1.0);
+        assert_close(max.latitude, 1.0);
+        assert_close(min.longitude, 99.0);
+        assert_close(max.longitude, 101.0);
+
+        // Di lintang 60 derajat, satu derajat bujur hanya separuh panjangnya
+        let route = Route::new(vec![point(60.0, 10.0)]);
+        let (min, max) = route.bounding_box(KM_PER_DEGREE / 2.0);
+        assert_close(max.latitude - min.latitude, 1.0);
+        assert_close(max.longitude - min.longitude, 2.0);
+    }
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::vehicle::find_user_vehicle;
+use crate::models::fill_up::{CreateFillUpRequest, EfficiencyReport, FillUp};
+use crate::models::vehicle::Vehicle;
+use crate::types::Decimal;
+use crate::AppState;
+
+// Kolom fill_ups; liters di-cast ke text karena di-parse manual ke Decimal
+pub const FILL_UP_COLUMNS: &str = r#"
+    id, vehicle_id, user_id, transaction_id, spbu_id, station_name, fuel_type,
+    liters::text AS liters,
+    odometer_km, is_full_tank, filled_at, created_at
+"#;
+
+/// Data pengisian yang akan dicatat, baik dari transaksi maupun input manual
+pub struct NewFillUp<'a> {
+    pub transaction_id: Option<Uuid>,
+    pub spbu_id: Option<Uuid>,
+    pub station_name: Option<String>,
+    pub fuel_type: Option<&'a str>,
+    pub liters: &'a Decimal,
+    pub odometer_km: i32,
+    pub is_full_tank: bool,
+    pub filled_at: DateTime<Utc>,
+}
+
+/// Simpan pengisian BBM untuk kendaraan. Odometer harus konsisten dengan
+/// pengisian sebelum dan sesudahnya (tidak boleh mundur).
+pub async fn record_fill_up(conn: &mut PgConnection, vehicle: &Vehicle, fill_up: NewFillUp<'_>) -> Result<FillUp> {
+    if fill_up.odometer_km < 0 {
+        return Err(AppError::BadRequest("odometer_km must not be negative".to_string()));
+    }
+    if fill_up.liters.0 <= BigDecimal::from(0) {
+        return Err(AppError::BadRequest("liters must be greater than 0".to_string()));
+    }
+
+    let (previous, next): (Option<i32>, Option<i32>) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT MAX(odometer_km) FROM fill_ups WHERE vehicle_id = $1 AND filled_at <= $2),
+            (SELECT MIN(odometer_km) FROM fill_ups WHERE vehicle_id = $1 AND filled_at > $2)
+        "#,
+    )
+    .bind(vehicle.id)
+    .bind(fill_up.filled_at)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(previous) = previous.filter(|p| fill_up.odometer_km < *p) {
+        return Err(AppError::BadRequest(format!(
+            "odometer_km {} is lower than the previous reading ({} km)",
+            fill_up.odometer_km, previous
+        )));
+    }
+    if let Some(next) = next.filter(|n| fill_up.odometer_km > *n) {
+        return Err(AppError::BadRequest(format!(
+            "odometer_km {} is higher than a later reading ({} km)",
+            fill_up.odometer_km, next
+        )));
+    }
+
+    let fill_up = sqlx::query_as::<_, FillUp>(&format!(
+        r#"
+        INSERT INTO fill_ups (
+            vehicle_id, user_id, transaction_id, spbu_id, station_name, fuel_type,
+            liters, odometer_km, is_full_tank, filled_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8, $9, $10)
+        RETURNING {}
+        "#,
+        FILL_UP_COLUMNS
+    ))
+    .bind(vehicle.id)
+    .bind(vehicle.user_id)
+    .bind(fill_up.transaction_id)
+    .bind(fill_up.spbu_id)
+    .bind(fill_up.station_name)
+    .bind(fill_up.fuel_type.map(|f| f.trim().to_uppercase()))
+    .bind(fill_up.liters.to_string())
+    .bind(fill_up.odometer_km)
+    .bind(fill_up.is_full_tank)
+    .bind(fill_up.filled_at)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(fill_up)
+}
+
+async fn fetch_fill_ups(state: &AppState, vehicle_id: Uuid) -> Result<Vec<FillUp>> {
+    let fill_ups = sqlx::query_as::<_, FillUp>(&format!(
+        "SELECT {} FROM fill_ups WHERE vehicle_id = $1 ORDER BY odometer_km, filled_at",
+        FILL_UP_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(fill_ups)
+}
+
+// GET /me/vehicles/:id/fill-ups
+pub async fn list_fill_ups(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+) -> Result<Json<Vec<FillUp>>> {
+    let vehicle = find_user_vehicle(&state.db, user_id, vehicle_id).await?;
+    let fill_ups = fetch_fill_ups(&state, vehicle.id).await?;
+    Ok(Json(fill_ups))
+}
+
+// POST /me/vehicles/:id/fill-ups
+pub async fn create_fill_up(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+    Json(payload): Json<CreateFillUpRequest>,
+) -> Result<(StatusCode, Json<FillUp>)> {
+    let vehicle = find_user_vehicle(&state.db, user_id, vehicle_id).await?;
+
+    let station_name = payload
+        .station_name
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if payload.spbu_id.is_none() && station_name.is_none() {
+        return Err(AppError::BadRequest("Either spbu_id or station_name is required".to_string()));
+    }
+
+    let filled_at = payload.filled_at.unwrap_or_else(Utc::now);
+    if filled_at > Utc::now() {
+        return Err(AppError::BadRequest("filled_at must not be in the future".to_string()));
+    }
+
+    let mut conn = state.db.acquire().await?;
+    let fill_up = record_fill_up(
+        &mut conn,
+        &vehicle,
+        NewFillUp {
+            transaction_id: None,
+            spbu_id: payload.spbu_id,
+            station_name,
+            fuel_type: payload.fuel_type.as_deref(),
+            liters: &payload.liters,
+            odometer_km: payload.odometer_km,
+            is_full_tank: payload.is_full_tank.unwrap_or(true),
+            filled_at,
+        },
+    )
+    .await
+    .map_err(|e| match e {
+        AppError::DatabaseError(msg) if msg.contains("foreign key") => {
+            AppError::BadRequest("SPBU not found".to_string())
+        }
+        e => e,
+    })?;
+
+    Ok((StatusCode::CREATED, Json(fill_up)))
+}
+
+// DELETE /me/vehicles/:id/fill-ups/:fill_up_id
+pub async fn delete_fill_up(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((vehicle_id, fill_up_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let transaction_id: Option<Option<Uuid>> = sqlx::query_scalar(
+        "SELECT transaction_id FROM fill_ups WHERE id = $1 AND vehicle_id = $2 AND user_id = $3",
+    )
+    .bind(fill_up_id)
+    .bind(vehicle_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match transaction_id {
+        None => Err(AppError::NotFound("Fill-up not found".to_string())),
+        // Pengisian dari transaksi ikut terhapus jika transaksinya dibatalkan
+        Some(Some(_)) => Err(AppError::BadRequest(
+            "Fill-ups recorded from a transaction cannot be deleted".to_string(),
+        )),
+        Some(None) => {
+            sqlx::query("DELETE FROM fill_ups WHERE id = $1")
+                .bind(fill_up_id)
+                .execute(&state.db)
+                .await?;
+            Ok(StatusCode::NO_CONTENT)
+        }
+    }
+}
+
+// GET /me/vehicles/:id/efficiency
+pub async fn get_vehicle_efficiency(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+) -> Result<Json<EfficiencyReport>> {
+    let vehicle = find_user_vehicle(&state.db, user_id, vehicle_id).await?;
+    let fill_ups = fetch_fill_ups(&state, vehicle.id).await?;
+    Ok(Json(EfficiencyReport::compute(vehicle.id, &fill_ups)))
+}
@@ -0,0 +1,161 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::geocoding::route::Route;
+use crate::geocoding::{self, Coordinates};
+use crate::models::fuel_price::{CheapestFuelOption, CheapestFuelQuery};
+use crate::types::Decimal;
+use crate::AppState;
+
+const DEFAULT_RADIUS_KM: f64 = 5.0;
+const MAX_RADIUS_KM: f64 = 50.0;
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(sqlx::FromRow)]
+struct PriceRow {
+    spbu_id: Uuid,
+    spbu_name: String,
+    alamat: Option<String>,
+    brand_id: Option<Uuid>,
+    brand_name: Option<String>,
+    latitude: f64,
+    longitude: f64,
+    fuel_type: String,
+    price: String,
+}
+
+// Bulatkan nominal Rupiah ke 2 angka desimal
+fn round_money(value: BigDecimal) -> Decimal {
+    Decimal(value.round(2))
+}
+
+// GET /fuel-prices/cheapest
+// Urutkan SPBU berdasarkan harga lalu jarak. Jika liters dan km_per_liter diisi,
+// urutkan berdasarkan total biaya termasuk BBM untuk menuju SPBU.
+pub async fn get_cheapest_fuel(
+    State(state): State<AppState>,
+    Query(query): Query<CheapestFuelQuery>,
+) -> Result<Json<Vec<CheapestFuelOption>>, (StatusCode, String)> {
+    let radius_km = query.radius_km.unwrap_or(DEFAULT_RADIUS_KM);
+    if !(radius_km > 0.0 && radius_km <= MAX_RADIUS_KM) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("radius_km must be between 0 and {}", MAX_RADIUS_KM),
+        ));
+    }
+    if let Some(liters) = &query.liters {
+        if liters.0 <= BigDecimal::from(0) {
+            return Err((StatusCode::BAD_REQUEST, "liters must be greater than 0".to_string()));
+        }
+    }
+    if let Some(km_per_liter) = query.km_per_liter {
+        if km_per_liter <= 0.0 {
+            return Err((StatusCode::BAD_REQUEST, "km_per_liter must be greater than 0".to_string()));
+        }
+        // Biaya menuju SPBU hanya berarti jika dibandingkan dengan total pembelian
+        if query.liters.is_none() {
+            return Err((StatusCode::BAD_REQUEST, "liters is required when km_per_liter is set".to_string()));
+        }
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let origin = Coordinates {
+        latitude: query.lat,
+        longitude: query.lng,
+    };
+    // Rute satu titik: bounding box lingkaran di sekitar origin
+    let (min, max) = Route::new(vec![origin]).bounding_box(radius_km);
+
+    let rows = sqlx::query_as::<_, PriceRow>(
+        r#"
+        SELECT
+            s.id AS spbu_id,
+            s.nama AS spbu_name,
+            s.alamat,
+            s.brand_id,
+            b.nama AS brand_name,
+            s.latitude,
+            s.longitude,
+            fp.fuel_type,
+            fp.price::text AS price
+        FROM fuel_prices fp
+        JOIN spbu s ON s.id = fp.spbu_id
+        LEFT JOIN brands b ON b.id = s.brand_id
+        WHERE UPPER(fp.fuel_type) = UPPER($1)
+          AND s.latitude BETWEEN $2 AND $3
+          AND s.longitude BETWEEN $4 AND $5
+        "#
+    )
+    .bind(&query.fuel_type)
+    .bind(min.latitude)
+    .bind(max.latitude)
+    .bind(min.longitude)
+    .bind(max.longitude)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut options: Vec<CheapestFuelOption> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let distance_km = geocoding::haversine_km(
+                origin,
+                Coordinates {
+                    latitude: row.latitude,
+                    longitude: row.longitude,
+                },
+            );
+            if distance_km > radius_km {
+                return None;
+            }
+
+            let price = BigDecimal::from_str(&row.price).ok()?;
+            let estimated_total = query
+                .liters
+                .as_ref()
+                .map(|liters| price.clone() * liters.0.clone());
+            // BBM yang terpakai untuk pulang-pergi ke SPBU, dihargai dengan harga SPBU tersebut
+            let detour_fuel_cost = query.km_per_liter.and_then(|km_per_liter| {
+                BigDecimal::from_f64(2.0 * distance_km / km_per_liter).map(|liters| liters * price.clone())
+            });
+            let effective_total = match (&estimated_total, &detour_fuel_cost) {
+                (Some(total), Some(detour)) => Some(total.clone() + detour.clone()),
+                _ => None,
+            };
+
+            Some(CheapestFuelOption {
+                spbu_id: row.spbu_id,
+                spbu_name: row.spbu_name,
+                alamat: row.alamat,
+                brand_id: row.brand_id,
+                brand_name: row.brand_name,
+                latitude: row.latitude,
+                longitude: row.longitude,
+                fuel_type: row.fuel_type,
+                price_per_liter: Decimal(price),
+                distance_km,
+                estimated_total: estimated_total.map(round_money),
+                detour_fuel_cost: detour_fuel_cost.map(round_money),
+                effective_total: effective_total.map(round_money),
+            })
+        })
+        .collect();
+
+    options.sort_by(|a, b| {
+        let by_cost = match (&a.effective_total, &b.effective_total) {
+            (Some(x), Some(y)) => x.0.cmp(&y.0),
+            _ => a.price_per_liter.0.cmp(&b.price_per_liter.0),
+        };
+        by_cost.then_with(|| a.distance_km.total_cmp(&b.distance_km))
+    });
+    options.truncate(limit as usize);
+
+    Ok(Json(options))
+}
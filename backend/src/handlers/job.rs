@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::auth::require_platform_admin;
+use crate::error::{AppError, Result};
+use crate::models::job::{JobRun, JobRunQuery, QueuedJob, QueuedJobCount, QueuedJobQuery, QueuedJobStatus};
+use crate::AppState;
+
+const QUEUED_JOB_COLUMNS: &str = r#"
+    id, job_type, payload, status, attempts, max_attempts, run_at, locked_at, locked_by,
+    last_error, dedupe_key, created_at, updated_at, finished_at
+"#;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+// GET /admin/jobs?status=dead&job_type=...
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<QueuedJobQuery>,
+) -> Result<Json<Vec<QueuedJob>>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let jobs = sqlx::query_as::<_, QueuedJob>(&format!(
+        r#"
+        SELECT {}
+        FROM queued_jobs
+        WHERE ($1::varchar IS NULL OR status = $1)
+          AND ($2::varchar IS NULL OR job_type = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        QUEUED_JOB_COLUMNS
+    ))
+    .bind(query.status)
+    .bind(query.job_type)
+    .bind(query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+    .bind(query.offset.unwrap_or(0).max(0))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(jobs))
+}
+
+// GET /admin/jobs/summary
+// Jumlah job per tipe dan status
+pub async fn job_summary(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<QueuedJobCount>>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let counts = sqlx::query_as::<_, QueuedJobCount>(
+        "SELECT job_type, status, COUNT(*) AS count FROM queued_jobs GROUP BY job_type, status ORDER BY job_type, status",
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(counts))
+}
+
+// GET /admin/jobs/:id
+pub async fn get_job(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<QueuedJob>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let job = sqlx::query_as::<_, QueuedJob>(&format!("SELECT {} FROM queued_jobs WHERE id = $1", QUEUED_JOB_COLUMNS))
+        .bind(job_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".to_string()))?;
+
+    Ok(Json(job))
+}
+
+// POST /admin/jobs/:id/retry
+// Job dead dicoba ulang dari awal; job pending yang menunggu backoff langsung dijalankan
+pub async fn retry_job(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<QueuedJob>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let status: QueuedJobStatus = sqlx::query_scalar("SELECT status FROM queued_jobs WHERE id = $1 FOR UPDATE")
+        .bind(job_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".to_string()))?;
+
+    if !matches!(status, QueuedJobStatus::Dead | QueuedJobStatus::Pending) {
+        return Err(AppError::Conflict("Only dead or pending jobs can be retried".to_string()));
+    }
+
+    let job = sqlx::query_as::<_, QueuedJob>(&format!(
+        r#"
+        UPDATE queued_jobs
+        SET status = 'pending',
+            attempts = CASE WHEN status = 'dead' THEN 0 ELSE attempts END,
+            run_at = NOW(),
+            finished_at = NULL
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        QUEUED_JOB_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(job))
+}
+
+// GET /admin/job-runs?job_name=expire_pending_transactions
+pub async fn list_job_runs(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<JobRunQuery>,
+) -> Result<Json<Vec<JobRun>>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let runs = sqlx::query_as::<_, JobRun>(
+        r#"
+        SELECT id, job_name, status, started_at, finished_at, affected_rows, error
+        FROM job_runs
+        WHERE ($1::varchar IS NULL OR job_name = $1)
+        ORDER BY started_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(query.job_name)
+    .bind(query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(runs))
+}
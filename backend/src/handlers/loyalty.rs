@@ -0,0 +1,441 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::auth::require_platform_admin;
+use crate::error::{AppError, Result};
+use crate::models::loyalty::{
+    CreateLoyaltyRuleRequest, JournalKind, LoyaltyAccountType, LoyaltyRule, PointsEntry, PointsQuery,
+    PointsResponse, UpdateLoyaltyRuleRequest,
+};
+use crate::models::Transaction;
+use crate::types::Decimal;
+use crate::AppState;
+
+// Nilai rupiah satu poin saat ditukar sebagai potongan harga
+pub const POINT_VALUE_RUPIAH: i64 = 10;
+
+const DEFAULT_ENTRY_LIMIT: i64 = 50;
+const MAX_ENTRY_LIMIT: i64 = 500;
+
+// Kolom loyalty_rules; kolom numeric di-cast ke text karena di-parse manual ke Decimal
+pub const LOYALTY_RULE_COLUMNS: &str = r#"
+    id, brand_id, fuel_type,
+    min_amount::text AS min_amount,
+    points_per_thousand::text AS points_per_thousand,
+    bonus_points, is_active, created_at, updated_at
+"#;
+
+// Batas poin yang bisa ditukar dalam satu transaksi (Rp10.000.000)
+pub const MAX_REDEEM_POINTS: i64 = 1_000_000;
+
+/// Potongan harga untuk sejumlah poin
+pub fn points_discount(points: i64) -> Result<Decimal> {
+    if points < 0 {
+        return Err(AppError::BadRequest("redeem_points must not be negative".to_string()));
+    }
+    if points > MAX_REDEEM_POINTS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} points can be redeemed per transaction",
+            MAX_REDEEM_POINTS
+        )));
+    }
+    let rupiah = points
+        .checked_mul(POINT_VALUE_RUPIAH)
+        .ok_or_else(|| AppError::BadRequest("redeem_points is too large".to_string()))?;
+    Ok(Decimal(BigDecimal::from(rupiah)))
+}
+
+/// Saldo poin user; 0 jika belum punya akun member
+pub async fn points_balance<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid) -> Result<i64> {
+    let balance: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(e.amount), 0)::bigint
+        FROM loyalty_entries e
+        JOIN loyalty_accounts a ON a.id = e.account_id
+        WHERE a.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await?;
+    Ok(balance)
+}
+
+async fn system_account(conn: &mut PgConnection, account_type: LoyaltyAccountType) -> Result<Uuid> {
+    let id = sqlx::query_scalar("SELECT id FROM loyalty_accounts WHERE account_type = $1 AND user_id IS NULL")
+        .bind(account_type)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(id)
+}
+
+// Akun member dibuat saat pertama kali dibutuhkan
+async fn member_account(conn: &mut PgConnection, user_id: Uuid) -> Result<Uuid> {
+    sqlx::query(
+        "INSERT INTO loyalty_accounts (account_type, user_id) VALUES ($1, $2) ON CONFLICT (user_id) DO NOTHING",
+    )
+    .bind(LoyaltyAccountType::Member)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    let id = sqlx::query_scalar("SELECT id FROM loyalty_accounts WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(id)
+}
+
+async fn account_balance(conn: &mut PgConnection, account_id: Uuid) -> Result<i64> {
+    let balance: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0)::bigint FROM loyalty_entries WHERE account_id = $1",
+    )
+    .bind(account_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(balance)
+}
+
+/// Catat satu jurnal beserta entrinya. Total entri harus nol
+/// (juga dicek oleh constraint trigger di database saat commit).
+async fn post_journal(
+    conn: &mut PgConnection,
+    kind: JournalKind,
+    transaction_id: Option<Uuid>,
+    rule_id: Option<Uuid>,
+    reverses_journal_id: Option<Uuid>,
+    description: String,
+    entries: &[(Uuid, i64)],
+) -> Result<Uuid> {
+    if entries.iter().map(|(_, amount)| amount).sum::<i64>() != 0 {
+        return Err(AppError::InternalServerError("Unbalanced loyalty journal".to_string()));
+    }
+
+    let journal_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO loyalty_journals (kind, transaction_id, rule_id, reverses_journal_id, description)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(kind)
+    .bind(transaction_id)
+    .bind(rule_id)
+    .bind(reverses_journal_id)
+    .bind(description)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for (account_id, amount) in entries {
+        sqlx::query("INSERT INTO loyalty_entries (journal_id, account_id, amount) VALUES ($1, $2, $3)")
+            .bind(journal_id)
+            .bind(account_id)
+            .bind(amount)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(journal_id)
+}
+
+/// Tukar poin user untuk transaksi. Akun member dikunci sampai commit agar
+/// saldo tidak bisa dipakai dua kali oleh request paralel.
+pub async fn redeem_points(conn: &mut PgConnection, user_id: Uuid, transaction_id: Uuid, points: i64) -> Result<()> {
+    let member = member_account(conn, user_id).await?;
+    sqlx::query("SELECT id FROM loyalty_accounts WHERE id = $1 FOR UPDATE")
+        .bind(member)
+        .execute(&mut *conn)
+        .await?;
+
+    let balance = account_balance(conn, member).await?;
+    if balance < points {
+        return Err(AppError::BadRequest(format!(
+            "Insufficient points: requested {}, available {}",
+            points, balance
+        )));
+    }
+
+    let redemption = system_account(conn, LoyaltyAccountType::Redemption).await?;
+    post_journal(
+        conn,
+        JournalKind::Redeem,
+        Some(transaction_id),
+        None,
+        None,
+        format!("Redeemed {} points", points),
+        &[(member, -points), (redemption, points)],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Berikan poin untuk transaksi yang sudah selesai berdasarkan aturan yang paling spesifik.
+/// Mengembalikan jumlah poin yang diberikan (0 jika tidak ada aturan yang cocok).
+pub async fn award_points(conn: &mut PgConnection, transaction: &Transaction) -> Result<i64> {
+    let rule: Option<(Uuid, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.id, (FLOOR($3::numeric / 1000 * r.points_per_thousand) + r.bonus_points)::bigint
+        FROM loyalty_rules r
+        WHERE r.is_active
+          AND (r.brand_id IS NULL OR r.brand_id = (SELECT brand_id FROM spbu WHERE id = $1))
+          AND (r.fuel_type IS NULL OR r.fuel_type = UPPER($2))
+          AND r.min_amount <= $3::numeric
+        ORDER BY (r.brand_id IS NOT NULL) DESC, (r.fuel_type IS NOT NULL) DESC, r.min_amount DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(transaction.spbu_id)
+    .bind(&transaction.fuel_type)
+    .bind(transaction.billed_price().to_string())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some((rule_id, points)) = rule.filter(|(_, points)| *points > 0) else {
+        return Ok(0);
+    };
+
+    let member = member_account(conn, transaction.user_id).await?;
+    let issuance = system_account(conn, LoyaltyAccountType::Issuance).await?;
+    post_journal(
+        conn,
+        JournalKind::Earn,
+        Some(transaction.id),
+        Some(rule_id),
+        None,
+        format!("Earned {} points", points),
+        &[(issuance, -points), (member, points)],
+    )
+    .await?;
+
+    Ok(points)
+}
+
+/// Balik semua jurnal earn/redeem milik transaksi yang belum dibalik,
+/// dipakai saat transaksi dibatalkan atau di-refund.
+pub async fn reverse_transaction_points(conn: &mut PgConnection, transaction_id: Uuid) -> Result<()> {
+    let journals: Vec<(Uuid, JournalKind)> = sqlx::query_as(
+        r#"
+        SELECT j.id, j.kind
+        FROM loyalty_journals j
+        WHERE j.transaction_id = $1
+          AND j.kind IN ('earn', 'redeem')
+          AND NOT EXISTS (SELECT 1 FROM loyalty_journals r WHERE r.reverses_journal_id = j.id)
+        ORDER BY j.created_at
+        "#,
+    )
+    .bind(transaction_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (journal_id, kind) in journals {
+        let entries: Vec<(Uuid, i64)> =
+            sqlx::query_as("SELECT account_id, -amount FROM loyalty_entries WHERE journal_id = $1")
+                .bind(journal_id)
+                .fetch_all(&mut *conn)
+                .await?;
+
+        post_journal(
+            conn,
+            JournalKind::Reversal,
+            Some(transaction_id),
+            None,
+            Some(journal_id),
+            format!("Reversal of {} journal", kind.as_str()),
+            &entries,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// GET /me/points
+pub async fn get_my_points(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<PointsQuery>,
+) -> Result<Json<PointsResponse>> {
+    let limit = query.limit.unwrap_or(DEFAULT_ENTRY_LIMIT).clamp(1, MAX_ENTRY_LIMIT);
+
+    let mut conn = state.db.acquire().await?;
+    let member = member_account(&mut conn, user_id).await?;
+    let balance = account_balance(&mut conn, member).await?;
+
+    let entries = sqlx::query_as::<_, PointsEntry>(
+        r#"
+        SELECT e.journal_id, j.kind, j.transaction_id, e.amount, j.description, e.created_at
+        FROM loyalty_entries e
+        JOIN loyalty_journals j ON j.id = e.journal_id
+        WHERE e.account_id = $1
+        ORDER BY e.created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(member)
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(PointsResponse {
+        balance,
+        point_value: Decimal::from(POINT_VALUE_RUPIAH as i32),
+        entries,
+    }))
+}
+
+fn validate_rule_amounts(
+    min_amount: Option<&Decimal>,
+    points_per_thousand: Option<&Decimal>,
+    bonus_points: Option<i32>,
+) -> Result<()> {
+    let zero = BigDecimal::from(0);
+    if min_amount.is_some_and(|m| m.0 < zero)
+        || points_per_thousand.is_some_and(|p| p.0 < zero)
+        || bonus_points.is_some_and(|b| b < 0)
+    {
+        return Err(AppError::BadRequest(
+            "min_amount, points_per_thousand and bonus_points must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// GET /loyalty-rules
+pub async fn list_loyalty_rules(State(state): State<AppState>) -> Result<Json<Vec<LoyaltyRule>>> {
+    let rules = sqlx::query_as::<_, LoyaltyRule>(&format!(
+        "SELECT {} FROM loyalty_rules ORDER BY created_at",
+        LOYALTY_RULE_COLUMNS
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(rules))
+}
+
+// POST /loyalty-rules, hanya admin
+pub async fn create_loyalty_rule(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateLoyaltyRuleRequest>,
+) -> Result<(StatusCode, Json<LoyaltyRule>)> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    validate_rule_amounts(
+        payload.min_amount.as_ref(),
+        Some(&payload.points_per_thousand),
+        payload.bonus_points,
+    )?;
+
+    let rule = sqlx::query_as::<_, LoyaltyRule>(&format!(
+        r#"
+        INSERT INTO loyalty_rules (brand_id, fuel_type, min_amount, points_per_thousand, bonus_points)
+        VALUES ($1, $2, COALESCE($3::numeric, 0), $4::numeric, COALESCE($5, 0))
+        RETURNING {}
+        "#,
+        LOYALTY_RULE_COLUMNS
+    ))
+    .bind(payload.brand_id)
+    .bind(payload.fuel_type.map(|f| f.trim().to_uppercase()).filter(|f| !f.is_empty()))
+    .bind(payload.min_amount.map(|m| m.to_string()))
+    .bind(payload.points_per_thousand.to_string())
+    .bind(payload.bonus_points)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.message().contains("foreign key") => {
+            AppError::BadRequest("Brand not found".to_string())
+        }
+        e => e.into(),
+    })?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+// PUT /loyalty-rules/:id, hanya admin
+pub async fn update_loyalty_rule(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(rule_id): Path<Uuid>,
+    Json(payload): Json<UpdateLoyaltyRuleRequest>,
+) -> Result<Json<LoyaltyRule>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    validate_rule_amounts(
+        payload.min_amount.as_ref(),
+        payload.points_per_thousand.as_ref(),
+        payload.bonus_points,
+    )?;
+
+    let rule = sqlx::query_as::<_, LoyaltyRule>(&format!(
+        r#"
+        UPDATE loyalty_rules
+        SET
+            min_amount = COALESCE($1::numeric, min_amount),
+            points_per_thousand = COALESCE($2::numeric, points_per_thousand),
+            bonus_points = COALESCE($3, bonus_points),
+            is_active = COALESCE($4, is_active)
+        WHERE id = $5
+        RETURNING {}
+        "#,
+        LOYALTY_RULE_COLUMNS
+    ))
+    .bind(payload.min_amount.map(|m| m.to_string()))
+    .bind(payload.points_per_thousand.map(|p| p.to_string()))
+    .bind(payload.bonus_points)
+    .bind(payload.is_active)
+    .bind(rule_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Loyalty rule not found".to_string()))?;
+
+    Ok(Json(rule))
+}
+
+// DELETE /loyalty-rules/:id, hanya admin
+pub async fn delete_loyalty_rule(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let result = sqlx::query("DELETE FROM loyalty_rules WHERE id = $1")
+        .bind(rule_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Loyalty rule not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_discount_uses_point_value() {
+        assert_eq!(points_discount(0).unwrap(), Decimal::from(0));
+        assert_eq!(points_discount(150).unwrap(), Decimal::from(150 * POINT_VALUE_RUPIAH as i32));
+        assert_eq!(
+            points_discount(MAX_REDEEM_POINTS).unwrap().0,
+            BigDecimal::from(MAX_REDEEM_POINTS * POINT_VALUE_RUPIAH)
+        );
+    }
+
+    #[test]
+    fn points_discount_rejects_negative_and_huge_values() {
+        assert!(matches!(points_discount(-1), Err(AppError::BadRequest(_))));
+        assert!(matches!(points_discount(MAX_REDEEM_POINTS + 1), Err(AppError::BadRequest(_))));
+        assert!(matches!(points_discount(i64::MAX), Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,673 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::handlers::operator::require_station_manager;
+use crate::models::media::{MediaObject, MediaResponse, SignedUrlQuery};
+use crate::storage::{self, StorageError};
+use crate::AppState;
+
+// Batas jumlah foto dalam satu request upload SPBU
+pub const MAX_PHOTOS_PER_UPLOAD: usize = 10;
+
+// Batas total foto untuk satu review
+pub const MAX_PHOTOS_PER_REVIEW: usize = 5;
+
+// Helper functions for response
+fn success<T: serde::Serialize>(data: T) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "success",
+        "data": data
+    }))
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "error",
+        "message": message.into()
+    });
+    (status, Json(error_response))
+}
+
+type HandlerError = (StatusCode, Json<serde_json::Value>);
+
+fn storage_error(e: StorageError) -> HandlerError {
+    match e {
+        StorageError::NotFound(_) => error(StatusCode::NOT_FOUND, "File not found"),
+        StorageError::InvalidKey(_) => error(StatusCode::BAD_REQUEST, e.to_string()),
+        StorageError::Io(_) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn app_error(e: AppError) -> HandlerError {
+    let (status, message): (StatusCode, String) = e.into();
+    error(status, message)
+}
+
+pub fn to_response(state: &AppState, media: MediaObject) -> MediaResponse {
+    MediaResponse {
+        id: media.id,
+        url: state.media.signed_url(&media.storage_key),
+        thumbnail_url: media.thumbnail_key.as_deref().map(|k| state.media.signed_url(k)),
+        owner_type: media.owner_type,
+        owner_id: media.owner_id,
+        kind: media.kind,
+        content_type: media.content_type,
+        size_bytes: media.size_bytes,
+        sort_order: media.sort_order,
+        created_at: media.created_at,
+    }
+}
+
+// Cek apakah entitas pemilik file ada
+async fn owner_exists(state: &AppState, owner_type: &str, owner_id: Uuid) -> Result<bool, HandlerError> {
+    let table = match owner_type {
+        "spbu" => "spbu",
+        "brand" => "brands",
+        "service" => "services",
+        "user" => "users",
+        "review" => "reviews",
+        _ => return Ok(false),
+    };
+    sqlx::query_scalar(&format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)", table))
+        .bind(owner_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+// Baca semua file dari request multipart
+async fn read_files(mut multipart: Multipart, max_files: usize) -> Result<Vec<Vec<u8>>, HandlerError> {
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        // Lewati field teks biasa
+        if field.file_name().is_none() {
+            continue;
+        }
+        if files.len() == max_files {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("At most {} files can be uploaded at once", max_files),
+            ));
+        }
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?;
+        files.push(bytes.to_vec());
+    }
+
+    if files.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "No file uploaded"));
+    }
+    Ok(files)
+}
+
+// Cek ukuran dan jenis file; mengembalikan (content type, ekstensi)
+fn validate_image(state: &AppState, bytes: &[u8]) -> Result<(&'static str, &'static str), HandlerError> {
+    if bytes.len() > state.media.max_upload_bytes {
+        return Err(error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("File exceeds the {} byte limit", state.media.max_upload_bytes),
+        ));
+    }
+
+    storage::sniff_image_type(bytes).ok_or_else(|| {
+        error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Only JPEG, PNG and WebP images are supported",
+        )
+    })
+}
+
+// File yang sudah ditulis ke storage tapi belum dicatat di database
+struct StoredFile {
+    storage_key: String,
+    thumbnail_key: Option<String>,
+    content_type: &'static str,
+    size_bytes: i64,
+}
+
+impl StoredFile {
+    async fn remove(&self, state: &AppState) {
+        for key in std::iter::once(&self.storage_key).chain(self.thumbnail_key.as_ref()) {
+            if let Err(e) = state.storage.delete(key).await {
+                tracing::warn!("Failed to delete {} from storage: {}", key, e);
+            }
+        }
+    }
+}
+
+// Tulis file yang sudah divalidasi beserta thumbnail-nya ke storage
+async fn write_file(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    bytes: Vec<u8>,
+    (content_type, extension): (&'static str, &'static str),
+) -> Result<StoredFile, HandlerError> {
+    let file_id = Uuid::new_v4();
+    let storage_key = format!("{}/{}/{}.{}", owner_type, owner_id, file_id, extension);
+
+    // Decode gambar cukup berat, jalankan di thread blocking
+    let thumbnail_size = state.media.thumbnail_size;
+    let (bytes, thumbnail) = tokio::task::spawn_blocking(move || {
+        let thumbnail = storage::make_thumbnail(&bytes, thumbnail_size);
+        (bytes, thumbnail)
+    })
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    state.storage.put(&storage_key, &bytes).await.map_err(storage_error)?;
+
+    let mut stored = StoredFile {
+        storage_key,
+        thumbnail_key: None,
+        content_type,
+        size_bytes: bytes.len() as i64,
+    };
+
+    match thumbnail {
+        Some(thumbnail) => {
+            let key = format!("{}/{}/{}_thumb.jpg", owner_type, owner_id, file_id);
+            if let Err(e) = state.storage.put(&key, &thumbnail).await {
+                stored.remove(state).await;
+                return Err(storage_error(e));
+            }
+            stored.thumbnail_key = Some(key);
+        }
+        None => tracing::warn!("Failed to generate thumbnail for {}", stored.storage_key),
+    }
+
+    Ok(stored)
+}
+
+async fn insert_media<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    owner_type: &str,
+    owner_id: Uuid,
+    kind: &str,
+    file: &StoredFile,
+    sort_order: i32,
+    uploaded_by: Uuid,
+) -> Result<MediaObject, sqlx::Error> {
+    sqlx::query_as::<_, MediaObject>(
+        r#"
+        INSERT INTO media_objects (
+            owner_type, owner_id, kind, storage_key, thumbnail_key,
+            content_type, size_bytes, sort_order, uploaded_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .bind(kind)
+    .bind(&file.storage_key)
+    .bind(&file.thumbnail_key)
+    .bind(file.content_type)
+    .bind(file.size_bytes)
+    .bind(sort_order)
+    .bind(uploaded_by)
+    .fetch_one(executor)
+    .await
+}
+
+// Validasi, simpan file beserta thumbnail, lalu catat metadata-nya
+async fn store_media(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    kind: &str,
+    bytes: Vec<u8>,
+    sort_order: i32,
+    uploaded_by: Uuid,
+) -> Result<MediaObject, HandlerError> {
+    let image_type = validate_image(state, &bytes)?;
+    let file = write_file(state, owner_type, owner_id, bytes, image_type).await?;
+
+    match insert_media(&state.db, owner_type, owner_id, kind, &file, sort_order, uploaded_by).await {
+        Ok(media) => Ok(media),
+        Err(e) => {
+            file.remove(state).await;
+            Err(error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
+    }
+}
+
+// Simpan beberapa foto sekaligus: semua file divalidasi dulu, lalu dicatat dalam satu transaksi.
+// Jika ada yang gagal, tidak ada foto yang tersimpan dan file yang sudah ditulis dihapus lagi.
+async fn store_photos(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    files: Vec<Vec<u8>>,
+    first_order: i32,
+    uploaded_by: Uuid,
+) -> Result<Vec<MediaObject>, HandlerError> {
+    let image_types = files
+        .iter()
+        .map(|bytes| validate_image(state, bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stored = Vec::with_capacity(files.len());
+    let mut result = Ok(());
+    for (bytes, image_type) in files.into_iter().zip(image_types) {
+        match write_file(state, owner_type, owner_id, bytes, image_type).await {
+            Ok(file) => stored.push(file),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    let result = match result {
+        Ok(()) => insert_photos(state, owner_type, owner_id, &stored, first_order, uploaded_by)
+            .await
+            .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(e) => Err(e),
+    };
+
+    if result.is_err() {
+        for file in &stored {
+            file.remove(state).await;
+        }
+    }
+    result
+}
+
+async fn insert_photos(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    files: &[StoredFile],
+    first_order: i32,
+    uploaded_by: Uuid,
+) -> Result<Vec<MediaObject>, sqlx::Error> {
+    let mut tx = state.db.begin().await?;
+    let mut media = Vec::with_capacity(files.len());
+    for (i, file) in files.iter().enumerate() {
+        media.push(insert_media(&mut *tx, owner_type, owner_id, "photo", file, first_order + i as i32, uploaded_by).await?);
+    }
+    tx.commit().await?;
+    Ok(media)
+}
+
+// Hapus file beserta thumbnail dari storage
+async fn remove_files(state: &AppState, media: &MediaObject) {
+    for key in std::iter::once(&media.storage_key).chain(media.thumbnail_key.as_ref()) {
+        if let Err(e) = state.storage.delete(key).await {
+            tracing::warn!("Failed to delete {} from storage: {}", key, e);
+        }
+    }
+}
+
+/// Hapus semua file milik satu entitas, misalnya saat review dihapus
+pub async fn delete_owner_media(state: &AppState, owner_type: &str, owner_id: Uuid) -> Result<(), HandlerError> {
+    let media = sqlx::query_as::<_, MediaObject>(
+        "DELETE FROM media_objects WHERE owner_type = $1 AND owner_id = $2 RETURNING *"
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for media in &media {
+        remove_files(state, media).await;
+    }
+    Ok(())
+}
+
+// Ganti file tunggal (logo, icon, avatar): simpan yang baru, hapus yang lama
+async fn replace_single(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    kind: &str,
+    multipart: Multipart,
+    uploaded_by: Uuid,
+) -> Result<MediaObject, HandlerError> {
+    if !owner_exists(state, owner_type, owner_id).await? {
+        return Err(error(StatusCode::NOT_FOUND, format!("{} not found", owner_type)));
+    }
+
+    let bytes = read_files(multipart, 1).await?.remove(0);
+    let media = store_media(state, owner_type, owner_id, kind, bytes, 0, uploaded_by).await?;
+
+    let old = sqlx::query_as::<_, MediaObject>(
+        r#"
+        DELETE FROM media_objects
+        WHERE owner_type = $1 AND owner_id = $2 AND kind = $3 AND id <> $4
+        RETURNING *
+        "#
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .bind(kind)
+    .bind(media.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    for media in &old {
+        remove_files(state, media).await;
+    }
+
+    Ok(media)
+}
+
+// Redirect ke URL bertanda tangan untuk file tunggal terbaru
+async fn redirect_single(
+    state: &AppState,
+    owner_type: &str,
+    owner_id: Uuid,
+    kind: &str,
+) -> Result<Redirect, HandlerError> {
+    let key: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT storage_key FROM media_objects
+        WHERE owner_type = $1 AND owner_id = $2 AND kind = $3
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .bind(kind)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let key = key.ok_or_else(|| error(StatusCode::NOT_FOUND, "File not found"))?;
+    Ok(Redirect::temporary(&state.media.signed_url(&key)))
+}
+
+// Upload satu atau beberapa foto SPBU, hanya owner/manager SPBU
+pub async fn upload_spbu_photos(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, HandlerError> {
+    require_station_manager(&state.db, spbu_id, user_id).await.map_err(app_error)?;
+
+    let files = read_files(multipart, MAX_PHOTOS_PER_UPLOAD).await?;
+
+    // Foto baru ditaruh setelah foto yang sudah ada
+    let next_order: i32 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(MAX(sort_order) + 1, 0) FROM media_objects
+        WHERE owner_type = 'spbu' AND owner_id = $1 AND kind = 'photo'
+        "#
+    )
+    .bind(spbu_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let photos: Vec<MediaResponse> = store_photos(&state, "spbu", spbu_id, files, next_order, user_id)
+        .await?
+        .into_iter()
+        .map(|media| to_response(&state, media))
+        .collect();
+
+    Ok((StatusCode::CREATED, success(photos)))
+}
+
+// Daftar foto SPBU
+pub async fn get_spbu_photos(
+    State(state): State<AppState>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<impl IntoResponse, HandlerError> {
+    if !owner_exists(&state, "spbu", spbu_id).await? {
+        return Err(error(StatusCode::NOT_FOUND, "SPBU not found"));
+    }
+
+    let photos = sqlx::query_as::<_, MediaObject>(
+        r#"
+        SELECT * FROM media_objects
+        WHERE owner_type = 'spbu' AND owner_id = $1 AND kind = 'photo'
+        ORDER BY sort_order, created_at
+        "#
+    )
+    .bind(spbu_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let photos: Vec<MediaResponse> = photos
+        .into_iter()
+        .map(|media| to_response(&state, media))
+        .collect();
+
+    Ok(success(photos))
+}
+
+// Hapus foto SPBU, hanya owner/manager SPBU
+pub async fn delete_spbu_photo(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, photo_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, HandlerError> {
+    require_station_manager(&state.db, spbu_id, user_id).await.map_err(app_error)?;
+
+    let media = sqlx::query_as::<_, MediaObject>(
+        r#"
+        DELETE FROM media_objects
+        WHERE id = $1 AND owner_type = 'spbu' AND owner_id = $2 AND kind = 'photo'
+        RETURNING *
+        "#
+    )
+    .bind(photo_id)
+    .bind(spbu_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| error(StatusCode::NOT_FOUND, "Photo not found"))?;
+
+    remove_files(&state, &media).await;
+
+    Ok((StatusCode::NO_CONTENT, ()))
+}
+
+// Foto review hanya bisa diubah oleh penulis review
+async fn require_review_author(state: &AppState, review_id: Uuid, user_id: Uuid) -> Result<(), HandlerError> {
+    let author: Option<Uuid> = sqlx::query_scalar("SELECT user_id FROM reviews WHERE id = $1")
+        .bind(review_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    match author {
+        None => Err(error(StatusCode::NOT_FOUND, "Review not found")),
+        Some(author) if author != user_id => {
+            Err(error(StatusCode::FORBIDDEN, "You can only add photos to your own review"))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+// Upload foto untuk review sendiri
+pub async fn upload_review_photos(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(review_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, HandlerError> {
+    require_review_author(&state, review_id, user_id).await?;
+
+    let (count, next_order): (i64, i32) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), COALESCE(MAX(sort_order) + 1, 0) FROM media_objects
+        WHERE owner_type = 'review' AND owner_id = $1 AND kind = 'photo'
+        "#
+    )
+    .bind(review_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let remaining = MAX_PHOTOS_PER_REVIEW.saturating_sub(count as usize);
+    if remaining == 0 {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("A review can have at most {} photos", MAX_PHOTOS_PER_REVIEW),
+        ));
+    }
+
+    let files = read_files(multipart, remaining).await?;
+
+    let photos: Vec<MediaResponse> = store_photos(&state, "review", review_id, files, next_order, user_id)
+        .await?
+        .into_iter()
+        .map(|media| to_response(&state, media))
+        .collect();
+
+    Ok((StatusCode::CREATED, success(photos)))
+}
+
+// Hapus foto dari review sendiri
+pub async fn delete_review_photo(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((review_id, photo_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, HandlerError> {
+    require_review_author(&state, review_id, user_id).await?;
+
+    let media = sqlx::query_as::<_, MediaObject>(
+        r#"
+        DELETE FROM media_objects
+        WHERE id = $1 AND owner_type = 'review' AND owner_id = $2 AND kind = 'photo'
+        RETURNING *
+        "#
+    )
+    .bind(photo_id)
+    .bind(review_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| error(StatusCode::NOT_FOUND, "Photo not found"))?;
+
+    remove_files(&state, &media).await;
+
+    Ok((StatusCode::NO_CONTENT, ()))
+}
+
+// Upload logo brand
+pub async fn upload_brand_logo(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(brand_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, HandlerError> {
+    let media = replace_single(&state, "brand", brand_id, "logo", multipart, user_id).await?;
+
+    // logo_url menunjuk ke endpoint redirect yang stabil
+    sqlx::query("UPDATE brands SET logo_url = $1 WHERE id = $2")
+        .bind(format!("/brands/{}/logo", brand_id))
+        .bind(brand_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, success(to_response(&state, media))))
+}
+
+pub async fn get_brand_logo(
+    State(state): State<AppState>,
+    Path(brand_id): Path<Uuid>,
+) -> Result<Redirect, HandlerError> {
+    redirect_single(&state, "brand", brand_id, "logo").await
+}
+
+// Upload icon service
+pub async fn upload_service_icon(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(service_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, HandlerError> {
+    let media = replace_single(&state, "service", service_id, "icon", multipart, user_id).await?;
+
+    sqlx::query("UPDATE services SET icon_url = $1 WHERE id = $2")
+        .bind(format!("/services/{}/icon", service_id))
+        .bind(service_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, success(to_response(&state, media))))
+}
+
+pub async fn get_service_icon(
+    State(state): State<AppState>,
+    Path(service_id): Path<Uuid>,
+) -> Result<Redirect, HandlerError> {
+    redirect_single(&state, "service", service_id, "icon").await
+}
+
+// Upload foto profil, hanya untuk akun sendiri
+pub async fn upload_user_avatar(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, HandlerError> {
+    if id != user_id {
+        return Err(error(StatusCode::FORBIDDEN, "You can only change your own profile picture"));
+    }
+
+    let media = replace_single(&state, "user", id, "avatar", multipart, user_id).await?;
+
+    sqlx::query("UPDATE users SET foto_profile = $1 WHERE id = $2")
+        .bind(format!("/user/{}/avatar", id))
+        .bind(id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((StatusCode::CREATED, success(to_response(&state, media))))
+}
+
+pub async fn get_user_avatar(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Redirect, HandlerError> {
+    redirect_single(&state, "user", id, "avatar").await
+}
+
+// Download file lewat URL bertanda tangan
+pub async fn download_media(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, HandlerError> {
+    if !state.media.verify(&key, query.expires, &query.signature) {
+        return Err(error(StatusCode::FORBIDDEN, "Invalid or expired signature"));
+    }
+
+    let bytes = state.storage.get(&key).await.map_err(storage_error)?;
+    let content_type = storage::sniff_image_type(&bytes)
+        .map(|(content_type, _)| content_type)
+        .unwrap_or("application/octet-stream");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CACHE_CONTROL, format!("private, max-age={}", state.media.url_ttl_secs)),
+        ],
+        bytes,
+    ))
+}
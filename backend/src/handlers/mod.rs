@@ -5,4 +5,23 @@ pub mod service;
 pub mod spbu_service;
 pub mod wishlist;
 pub mod review;
-pub mod transaction;
\ No newline at end of file
+pub mod transaction;
+pub mod media;
+pub mod fuel_price;
+pub mod vehicle;
+pub mod quota;
+pub mod fill_up;
+pub mod loyalty;
+pub mod promotion;
+pub mod wallet;
+pub mod organization;
+pub mod statement;
+pub mod operator;
+pub mod spbu_stats;
+pub mod pump;
+pub mod pickup;
+pub mod job;
+pub mod notification;
+pub mod wishlist_alert;
+pub mod wishlist_collection;
+pub mod review_reply;
\ No newline at end of file
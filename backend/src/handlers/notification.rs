@@ -0,0 +1,191 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::notification::{
+    EventPreference, Notification, NotificationChannels, NotificationEvent, NotificationInbox,
+    NotificationLanguage, NotificationPreferences, NotificationQuery, UpdateNotificationPreferencesRequest,
+    UpdateNotificationRequest,
+};
+use crate::AppState;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+const NOTIFICATION_COLUMNS: &str = "id, event, title, body, data, read_at, created_at";
+
+// GET /me/notifications?unread_only=true&limit=20&offset=0
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Json<NotificationInbox>> {
+    let notifications = sqlx::query_as::<_, Notification>(&format!(
+        r#"
+        SELECT {}
+        FROM notifications
+        WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(query.unread_only)
+    .bind(query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+    .bind(query.offset.unwrap_or(0).max(0))
+    .fetch_all(&state.db)
+    .await?;
+
+    let unread_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    Ok(Json(NotificationInbox {
+        unread_count,
+        notifications,
+    }))
+}
+
+// PUT /me/notifications/:id
+// Tandai sudah dibaca atau kembalikan ke belum dibaca
+pub async fn update_notification(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(notification_id): Path<Uuid>,
+    Json(payload): Json<UpdateNotificationRequest>,
+) -> Result<Json<Notification>> {
+    let notification = sqlx::query_as::<_, Notification>(&format!(
+        r#"
+        UPDATE notifications
+        SET read_at = CASE WHEN $3 THEN COALESCE(read_at, NOW()) END
+        WHERE id = $1 AND user_id = $2
+        RETURNING {}
+        "#,
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(notification_id)
+    .bind(user_id)
+    .bind(payload.read)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+
+    Ok(Json(notification))
+}
+
+// POST /me/notifications/read-all
+pub async fn mark_all_read(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<StatusCode> {
+    sqlx::query("UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL")
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(sqlx::FromRow)]
+struct PreferenceRow {
+    event: NotificationEvent,
+    #[sqlx(flatten)]
+    channels: NotificationChannels,
+}
+
+async fn load_preferences(db: &sqlx::PgPool, user_id: Uuid) -> Result<NotificationPreferences> {
+    let language: NotificationLanguage =
+        sqlx::query_scalar("SELECT notification_language FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let saved = sqlx::query_as::<_, PreferenceRow>(
+        "SELECT event, in_app, email, sms, push FROM notification_preferences WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+
+    // Semua event ditampilkan; yang belum diatur memakai kanal default
+    let events = NotificationEvent::ALL
+        .iter()
+        .map(|&event| EventPreference {
+            event,
+            channels: saved
+                .iter()
+                .find(|row| row.event == event)
+                .map(|row| row.channels)
+                .unwrap_or_else(|| event.default_channels()),
+        })
+        .collect();
+
+    Ok(NotificationPreferences { language, events })
+}
+
+// GET /me/notification-preferences
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<NotificationPreferences>> {
+    Ok(Json(load_preferences(&state.db, user_id).await?))
+}
+
+// PUT /me/notification-preferences
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<NotificationPreferences>> {
+    let current = load_preferences(&state.db, user_id).await?;
+    let mut tx = state.db.begin().await?;
+
+    if let Some(language) = payload.language {
+        sqlx::query("UPDATE users SET notification_language = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(language)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for update in payload.events {
+        let existing = current
+            .events
+            .iter()
+            .find(|pref| pref.event == update.event)
+            .map(|pref| pref.channels)
+            .unwrap_or_else(|| update.event.default_channels());
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (user_id, event, in_app, email, sms, push)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, event) DO UPDATE
+            SET in_app = EXCLUDED.in_app,
+                email = EXCLUDED.email,
+                sms = EXCLUDED.sms,
+                push = EXCLUDED.push
+            "#,
+        )
+        .bind(user_id)
+        .bind(update.event)
+        .bind(update.in_app.unwrap_or(existing.in_app))
+        .bind(update.email.unwrap_or(existing.email))
+        .bind(update.sms.unwrap_or(existing.sms))
+        .bind(update.push.unwrap_or(existing.push))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(load_preferences(&state.db, user_id).await?))
+}
@@ -0,0 +1,521 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::auth::require_platform_admin;
+use crate::error::{AppError, Result};
+use crate::handlers::transaction::TRANSACTION_COLUMNS;
+use crate::jobs::{queue, EvaluateWishlistAlerts};
+use crate::models::operator::{
+    AddStaffRequest, AssignOwnerRequest, OperatorSpbu, OperatorTransactionQuery, SpbuStaff, SpbuStaffRole, StationFuel,
+    UpdateFuelPriceRequest, UpdateFuelStockRequest, UpdateHoursRequest, UpdateQueueRequest,
+    UpdateStaffRequest,
+};
+use crate::models::spbu::Spbu;
+use crate::models::{Transaction, TransactionResponse};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Dipakai dengan FROM spbu_staff st JOIN users u ON u.id = st.user_id
+const STAFF_COLUMNS: &str = r#"
+    st.id, st.spbu_id, st.user_id, u.email, u.nama_lengkap, st.role, st.created_at, st.updated_at
+"#;
+
+const STATION_FUEL_COLUMNS: &str = r#"
+    fuel_type, price::text AS price, stock_liters::text AS stock_liters, updated_at
+"#;
+
+const DEFAULT_TRANSACTION_LIMIT: i64 = 50;
+const MAX_TRANSACTION_LIMIT: i64 = 200;
+
+/// Role user di SPBU; 404 jika SPBU tidak ada, 403 jika user bukan staf SPBU tersebut
+pub async fn require_staff<'e, E: PgExecutor<'e>>(executor: E, spbu_id: Uuid, user_id: Uuid) -> Result<SpbuStaffRole> {
+    let row: Option<(Option<SpbuStaffRole>,)> = sqlx::query_as(
+        r#"
+        SELECT st.role
+        FROM spbu s
+        LEFT JOIN spbu_staff st ON st.spbu_id = s.id AND st.user_id = $2
+        WHERE s.id = $1
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?;
+
+    match row {
+        None => Err(AppError::NotFound("SPBU not found".to_string())),
+        Some((None,)) => Err(AppError::Forbidden("You are not an operator of this SPBU".to_string())),
+        Some((Some(role),)) => Ok(role),
+    }
+}
+
+/// Owner atau manager SPBU
+pub async fn require_station_manager<'e, E: PgExecutor<'e>>(executor: E, spbu_id: Uuid, user_id: Uuid) -> Result<()> {
+    if !require_staff(executor, spbu_id, user_id).await?.can_manage_station() {
+        return Err(AppError::Forbidden(
+            "Only SPBU owners or managers can perform this action".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn require_owner<'e, E: PgExecutor<'e>>(executor: E, spbu_id: Uuid, user_id: Uuid) -> Result<()> {
+    if !require_staff(executor, spbu_id, user_id).await?.can_manage_staff() {
+        return Err(AppError::Forbidden("Only SPBU owners can perform this action".to_string()));
+    }
+    Ok(())
+}
+
+pub fn normalize_fuel_type(fuel_type: &str) -> Result<String> {
+    let fuel_type = fuel_type.trim().to_uppercase();
+    if fuel_type.is_empty() {
+        return Err(AppError::BadRequest("fuel_type is required".to_string()));
+    }
+    Ok(fuel_type)
+}
+
+// GET /operator/spbu
+pub async fn list_my_stations(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<OperatorSpbu>>> {
+    let roles: Vec<(Uuid, SpbuStaffRole)> =
+        sqlx::query_as("SELECT spbu_id, role FROM spbu_staff WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&state.db)
+            .await?;
+    let spbu_ids: Vec<Uuid> = roles.iter().map(|(id, _)| *id).collect();
+
+    let spbus = sqlx::query_as::<_, Spbu>("SELECT * FROM spbu WHERE id = ANY($1) ORDER BY nama")
+        .bind(&spbu_ids)
+        .fetch_all(&state.db)
+        .await?;
+
+    let stations = spbus
+        .into_iter()
+        .filter_map(|spbu| {
+            let role = roles.iter().find(|(id, _)| *id == spbu.id)?.1;
+            Some(OperatorSpbu { spbu, role })
+        })
+        .collect();
+
+    Ok(Json(stations))
+}
+
+async fn fetch_staff<'e, E: PgExecutor<'e>>(executor: E, spbu_id: Uuid, staff_user_id: Uuid) -> Result<SpbuStaff> {
+    sqlx::query_as::<_, SpbuStaff>(&format!(
+        r#"
+        SELECT {} FROM spbu_staff st
+        JOIN users u ON u.id = st.user_id
+        WHERE st.spbu_id = $1 AND st.user_id = $2
+        "#,
+        STAFF_COLUMNS
+    ))
+    .bind(spbu_id)
+    .bind(staff_user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Staff not found".to_string()))
+}
+
+async fn lock_spbu(conn: &mut PgConnection, spbu_id: Uuid) -> Result<()> {
+    sqlx::query("SELECT id FROM spbu WHERE id = $1 FOR UPDATE")
+        .bind(spbu_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn ensure_owner(conn: &mut PgConnection, spbu_id: Uuid) -> Result<()> {
+    let has_owner: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM spbu_staff WHERE spbu_id = $1 AND role = 'owner')",
+    )
+    .bind(spbu_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !has_owner {
+        return Err(AppError::BadRequest("SPBU must keep at least one owner".to_string()));
+    }
+    Ok(())
+}
+
+// GET /operator/spbu/:id/staff
+pub async fn list_staff(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<Json<Vec<SpbuStaff>>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    let staff = sqlx::query_as::<_, SpbuStaff>(&format!(
+        r#"
+        SELECT {} FROM spbu_staff st
+        JOIN users u ON u.id = st.user_id
+        WHERE st.spbu_id = $1
+        ORDER BY st.created_at
+        "#,
+        STAFF_COLUMNS
+    ))
+    .bind(spbu_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(staff))
+}
+
+// POST /operator/spbu/:id/staff
+pub async fn add_staff(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<AddStaffRequest>,
+) -> Result<(StatusCode, Json<SpbuStaff>)> {
+    require_owner(&state.db, spbu_id, user_id).await?;
+
+    let staff_user_id: Uuid = sqlx::query_scalar("SELECT id FROM users WHERE LOWER(email) = LOWER($1)")
+        .bind(payload.email.trim())
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    sqlx::query("INSERT INTO spbu_staff (spbu_id, user_id, role) VALUES ($1, $2, $3)")
+        .bind(spbu_id)
+        .bind(staff_user_id)
+        .bind(payload.role.unwrap_or(SpbuStaffRole::Cashier))
+        .execute(&state.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+                AppError::Conflict("User is already a staff member of this SPBU".to_string())
+            }
+            e => e.into(),
+        })?;
+
+    let staff = fetch_staff(&state.db, spbu_id, staff_user_id).await?;
+    Ok((StatusCode::CREATED, Json(staff)))
+}
+
+// POST /admin/spbu/:id/owner
+// Untuk SPBU baru (dibuat admin tanpa owner), SPBU lama yang dibuat sebelum ada spbu_staff,
+// atau yang owner-nya hilang akses.
+// Jika user sudah menjadi staf, role-nya dinaikkan menjadi owner.
+pub async fn assign_owner(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<AssignOwnerRequest>,
+) -> Result<Json<SpbuStaff>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query("SELECT id FROM spbu WHERE id = $1 FOR UPDATE")
+        .bind(spbu_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("SPBU not found".to_string()))?;
+
+    let owner_user_id: Uuid = sqlx::query_scalar("SELECT id FROM users WHERE LOWER(email) = LOWER($1)")
+        .bind(payload.email.trim())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO spbu_staff (spbu_id, user_id, role) VALUES ($1, $2, 'owner')
+        ON CONFLICT (spbu_id, user_id) DO UPDATE SET role = 'owner'
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(owner_user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let staff = fetch_staff(&mut *tx, spbu_id, owner_user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(staff))
+}
+
+// PUT /operator/spbu/:id/staff/:user_id
+pub async fn update_staff(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, staff_user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateStaffRequest>,
+) -> Result<Json<SpbuStaff>> {
+    require_owner(&state.db, spbu_id, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+    lock_spbu(&mut tx, spbu_id).await?;
+
+    let result = sqlx::query("UPDATE spbu_staff SET role = $1 WHERE spbu_id = $2 AND user_id = $3")
+        .bind(payload.role)
+        .bind(spbu_id)
+        .bind(staff_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Staff not found".to_string()));
+    }
+    ensure_owner(&mut tx, spbu_id).await?;
+
+    let staff = fetch_staff(&mut *tx, spbu_id, staff_user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(staff))
+}
+
+// DELETE /operator/spbu/:id/staff/:user_id
+pub async fn remove_staff(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, staff_user_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    require_owner(&state.db, spbu_id, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+    lock_spbu(&mut tx, spbu_id).await?;
+
+    let result = sqlx::query("DELETE FROM spbu_staff WHERE spbu_id = $1 AND user_id = $2")
+        .bind(spbu_id)
+        .bind(staff_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Staff not found".to_string()));
+    }
+    ensure_owner(&mut tx, spbu_id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /operator/spbu/:id/fuels
+pub async fn list_station_fuels(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<Json<Vec<StationFuel>>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    let fuels = sqlx::query_as::<_, StationFuel>(&format!(
+        "SELECT {} FROM fuel_prices WHERE spbu_id = $1 ORDER BY fuel_type",
+        STATION_FUEL_COLUMNS
+    ))
+    .bind(spbu_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(fuels))
+}
+
+// PUT /operator/spbu/:id/fuels/:fuel_type/price
+// Menambah jenis BBM baru jika belum dijual di SPBU ini
+pub async fn update_fuel_price(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, fuel_type)): Path<(Uuid, String)>,
+    Json(payload): Json<UpdateFuelPriceRequest>,
+) -> Result<Json<StationFuel>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+    let fuel_type = normalize_fuel_type(&fuel_type)?;
+
+    if payload.price <= Decimal::default() {
+        return Err(AppError::BadRequest("price must be greater than 0".to_string()));
+    }
+
+    let fuel = sqlx::query_as::<_, StationFuel>(&format!(
+        r#"
+        INSERT INTO fuel_prices (spbu_id, fuel_type, price)
+        VALUES ($1, $2, $3::numeric)
+        ON CONFLICT (spbu_id, fuel_type) DO UPDATE SET price = EXCLUDED.price
+        RETURNING {}
+        "#,
+        STATION_FUEL_COLUMNS
+    ))
+    .bind(spbu_id)
+    .bind(&fuel_type)
+    .bind(payload.price.to_string())
+    .fetch_one(&state.db)
+    .await?;
+
+    queue::enqueue(&state.db, &EvaluateWishlistAlerts { spbu_id }).await?;
+
+    Ok(Json(fuel))
+}
+
+// PUT /operator/spbu/:id/fuels/:fuel_type/stock
+pub async fn update_fuel_stock(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, fuel_type)): Path<(Uuid, String)>,
+    Json(payload): Json<UpdateFuelStockRequest>,
+) -> Result<Json<StationFuel>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+    let fuel_type = normalize_fuel_type(&fuel_type)?;
+
+    if payload.stock_liters.as_ref().is_some_and(|stock| *stock < Decimal::default()) {
+        return Err(AppError::BadRequest("stock_liters cannot be negative".to_string()));
+    }
+
+    let fuel = sqlx::query_as::<_, StationFuel>(&format!(
+        r#"
+        UPDATE fuel_prices SET stock_liters = $1::numeric
+        WHERE spbu_id = $2 AND fuel_type = $3
+        RETURNING {}
+        "#,
+        STATION_FUEL_COLUMNS
+    ))
+    .bind(payload.stock_liters.map(|stock| stock.to_string()))
+    .bind(spbu_id)
+    .bind(&fuel_type)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("{} is not sold at this SPBU", fuel_type)))?;
+
+    queue::enqueue(&state.db, &EvaluateWishlistAlerts { spbu_id }).await?;
+
+    Ok(Json(fuel))
+}
+
+// DELETE /operator/spbu/:id/fuels/:fuel_type
+pub async fn remove_fuel(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, fuel_type)): Path<(Uuid, String)>,
+) -> Result<StatusCode> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+    let fuel_type = normalize_fuel_type(&fuel_type)?;
+
+    let result = sqlx::query("DELETE FROM fuel_prices WHERE spbu_id = $1 AND fuel_type = $2")
+        .bind(spbu_id)
+        .bind(&fuel_type)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("{} is not sold at this SPBU", fuel_type)));
+    }
+
+    queue::enqueue(&state.db, &EvaluateWishlistAlerts { spbu_id }).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /operator/spbu/:id/queue
+// Boleh dilakukan semua staf, termasuk kasir
+pub async fn update_queue(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<UpdateQueueRequest>,
+) -> Result<Json<Spbu>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    if payload.jumlah_antrian < 0 {
+        return Err(AppError::BadRequest("jumlah_antrian cannot be negative".to_string()));
+    }
+
+    let spbu = sqlx::query_as::<_, Spbu>(
+        "UPDATE spbu SET jumlah_antrian = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
+    )
+    .bind(payload.jumlah_antrian)
+    .bind(spbu_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    queue::enqueue(&state.db, &EvaluateWishlistAlerts { spbu_id }).await?;
+
+    Ok(Json(spbu))
+}
+
+// PUT /operator/spbu/:id/hours
+pub async fn update_hours(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<UpdateHoursRequest>,
+) -> Result<Json<Spbu>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    // SPBU 24 jam tidak menyimpan jam buka/tutup
+    let (jam_buka, jam_tutup) = if payload.buka_24_jam {
+        (None, None)
+    } else {
+        match (payload.jam_buka, payload.jam_tutup) {
+            (Some(buka), Some(tutup)) if buka != tutup => (Some(buka), Some(tutup)),
+            (Some(_), Some(_)) => {
+                return Err(AppError::BadRequest(
+                    "jam_buka and jam_tutup must differ; use buka_24_jam instead".to_string(),
+                ))
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "jam_buka and jam_tutup are required unless buka_24_jam is true".to_string(),
+                ))
+            }
+        }
+    };
+
+    let spbu = sqlx::query_as::<_, Spbu>(
+        r#"
+        UPDATE spbu SET buka_24_jam = $1, jam_buka = $2, jam_tutup = $3, updated_at = NOW()
+        WHERE id = $4
+        RETURNING *
+        "#,
+    )
+    .bind(payload.buka_24_jam)
+    .bind(jam_buka)
+    .bind(jam_tutup)
+    .bind(spbu_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(spbu))
+}
+
+// GET /operator/spbu/:id/transactions
+// Pesanan masuk untuk SPBU, terbaru di atas
+pub async fn list_station_transactions(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Query(query): Query<OperatorTransactionQuery>,
+) -> Result<Json<Vec<TransactionResponse>>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TRANSACTION_LIMIT)
+        .clamp(1, MAX_TRANSACTION_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let transactions = sqlx::query_as::<_, Transaction>(&format!(
+        r#"
+        SELECT {} FROM transactions
+        WHERE spbu_id = $1 AND ($2::transaction_status IS NULL OR status = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        TRANSACTION_COLUMNS
+    ))
+    .bind(spbu_id)
+    .bind(query.status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(TransactionResponse::from)
+    .collect();
+
+    Ok(Json(transactions))
+}
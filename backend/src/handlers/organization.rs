@@ -0,0 +1,1097 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use chrono::{Days, FixedOffset, Months, NaiveDate, Utc};
+use sqlx::{PgConnection, PgExecutor};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::transaction::TRANSACTION_COLUMNS;
+use crate::handlers::vehicle::VEHICLE_COLUMNS;
+use crate::models::organization::{
+    AddMemberRequest, AddOrganizationVehicleRequest, CreateInvoiceRequest, CreateOrganizationRequest,
+    InvoiceDetail, InvoiceStatus, MyOrganization, Organization, OrganizationInvoice, OrganizationMember,
+    OrganizationRole, OrganizationVehicle, PayInvoiceRequest, SpendingLimits, UpdateMemberRequest,
+    UpdateOrganizationRequest, UpdateOrganizationVehicleRequest,
+};
+use crate::models::quota::{QuotaUsage, QuotaWindow};
+use crate::models::vehicle::Vehicle;
+use crate::models::{Transaction, TransactionResponse};
+use crate::payment::{self, PaymentOutcome, PAYMENT_METHOD_WALLET};
+use crate::types::Decimal;
+use crate::AppState;
+
+pub const ORGANIZATION_COLUMNS: &str = r#"
+    id, name, billing_email, tax_id, allowed_fuel_types, allowed_brand_ids,
+    is_active, created_by, created_at, updated_at
+"#;
+
+// Dipakai dengan FROM organization_members m JOIN users u ON u.id = m.user_id
+const MEMBER_COLUMNS: &str = r#"
+    m.id, m.organization_id, m.user_id, u.email, u.nama_lengkap, m.role,
+    m.daily_amount_limit::text AS daily_amount_limit,
+    m.monthly_amount_limit::text AS monthly_amount_limit,
+    m.daily_liter_limit::text AS daily_liter_limit,
+    m.monthly_liter_limit::text AS monthly_liter_limit,
+    m.is_active, m.created_at, m.updated_at
+"#;
+
+// Dipakai dengan FROM organization_vehicles ov JOIN vehicles v ON v.id = ov.vehicle_id
+const ORGANIZATION_VEHICLE_COLUMNS: &str = r#"
+    ov.id, ov.organization_id, ov.vehicle_id, v.plate_number, v.vehicle_type,
+    ov.daily_amount_limit::text AS daily_amount_limit,
+    ov.monthly_amount_limit::text AS monthly_amount_limit,
+    ov.daily_liter_limit::text AS daily_liter_limit,
+    ov.monthly_liter_limit::text AS monthly_liter_limit,
+    ov.is_active, ov.created_at, ov.updated_at
+"#;
+
+const INVOICE_COLUMNS: &str = r#"
+    id, organization_id, period_start, period_end, transaction_count,
+    total_amount::text AS total_amount,
+    status, due_date, issued_at, paid_at
+"#;
+
+// Periode tagihan mengikuti tanggal lokal (WIB)
+pub const BILLING_TIMEZONE: &str = "Asia/Jakarta";
+pub const BILLING_UTC_OFFSET_SECS: i32 = 7 * 3600;
+const INVOICE_DUE_DAYS: u64 = 14;
+
+fn normalize_fuel_types(fuel_types: Option<Vec<String>>) -> Option<Vec<String>> {
+    let mut fuel_types: Vec<String> = fuel_types?
+        .into_iter()
+        .map(|f| f.trim().to_uppercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+    fuel_types.sort();
+    fuel_types.dedup();
+    Some(fuel_types)
+}
+
+fn validate_limits(limits: &SpendingLimits) -> Result<()> {
+    let zero = BigDecimal::from(0);
+    for (name, limit) in [
+        ("daily_amount_limit", &limits.daily_amount_limit),
+        ("monthly_amount_limit", &limits.monthly_amount_limit),
+        ("daily_liter_limit", &limits.daily_liter_limit),
+        ("monthly_liter_limit", &limits.monthly_liter_limit),
+    ] {
+        if limit.as_ref().is_some_and(|l| l.0 <= zero) {
+            return Err(AppError::BadRequest(format!("{} must be greater than 0", name)));
+        }
+    }
+    Ok(())
+}
+
+fn limit_params(limits: &SpendingLimits) -> [Option<String>; 4] {
+    [
+        limits.daily_amount_limit.as_ref().map(|l| l.to_string()),
+        limits.monthly_amount_limit.as_ref().map(|l| l.to_string()),
+        limits.daily_liter_limit.as_ref().map(|l| l.to_string()),
+        limits.monthly_liter_limit.as_ref().map(|l| l.to_string()),
+    ]
+}
+
+/// Parse periode bulanan `YYYY-MM` menjadi rentang [awal, awal bulan berikutnya)
+pub fn parse_period(period: &str) -> Result<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", period.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("period must use the YYYY-MM format".to_string()))?;
+    let end = start
+        .checked_add_months(Months::new(1))
+        .ok_or_else(|| AppError::BadRequest("Invalid period".to_string()))?;
+    Ok((start, end))
+}
+
+/// Periode dianggap selesai jika tanggal hari ini (WIB) sudah mencapai akhir periode
+pub fn period_has_ended(period_end: NaiveDate) -> bool {
+    let offset = FixedOffset::east_opt(BILLING_UTC_OFFSET_SECS).expect("valid UTC offset");
+    Utc::now().with_timezone(&offset).date_naive() >= period_end
+}
+
+async fn find_member_role<'e, E: PgExecutor<'e>>(
+    executor: E,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationRole> {
+    sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2 AND is_active",
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))
+}
+
+pub async fn require_admin<'e, E: PgExecutor<'e>>(executor: E, organization_id: Uuid, user_id: Uuid) -> Result<()> {
+    match find_member_role(executor, organization_id, user_id).await? {
+        OrganizationRole::Admin => Ok(()),
+        OrganizationRole::Driver => Err(AppError::Forbidden(
+            "Only organization admins can perform this action".to_string(),
+        )),
+    }
+}
+
+/// Organisasi yang menanggung transaksi; user harus anggota aktif dari organisasi yang aktif
+pub async fn find_billing_organization<'e, E: PgExecutor<'e>>(
+    executor: E,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<Organization> {
+    let organization = sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        SELECT {} FROM organizations
+        WHERE id = $1
+          AND EXISTS (
+              SELECT 1 FROM organization_members
+              WHERE organization_id = $1 AND user_id = $2 AND is_active
+          )
+        "#,
+        ORGANIZATION_COLUMNS
+    ))
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+
+    if !organization.is_active {
+        return Err(AppError::BadRequest("Organization account is not active".to_string()));
+    }
+    Ok(organization)
+}
+
+/// Kendaraan armada yang terdaftar (aktif) di organisasi
+pub async fn find_organization_vehicle<'e, E: PgExecutor<'e>>(
+    executor: E,
+    organization_id: Uuid,
+    vehicle_id: Uuid,
+) -> Result<Vehicle> {
+    sqlx::query_as::<_, Vehicle>(&format!(
+        r#"
+        SELECT {} FROM vehicles
+        WHERE id = $1
+          AND id IN (SELECT vehicle_id FROM organization_vehicles WHERE organization_id = $2 AND is_active)
+        "#,
+        VEHICLE_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(organization_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Vehicle is not registered to this organization".to_string()))
+}
+
+/// Pembelian atas tagihan organisasi yang akan dicek terhadap limit
+pub struct OrganizationPurchase<'a> {
+    pub user_id: Uuid,
+    pub vehicle: Option<&'a Vehicle>,
+    pub spbu_id: Uuid,
+    pub fuel_type: &'a str,
+    pub quantity: &'a Decimal,
+    pub amount: &'a Decimal,
+}
+
+enum LimitScope<'a> {
+    Driver(Uuid),
+    Vehicle(Uuid, &'a str),
+}
+
+impl LimitScope<'_> {
+    fn column(&self) -> &'static str {
+        match self {
+            LimitScope::Driver(_) => "user_id",
+            LimitScope::Vehicle(..) => "vehicle_id",
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        match self {
+            LimitScope::Driver(id) | LimitScope::Vehicle(id, _) => *id,
+        }
+    }
+}
+
+impl std::fmt::Display for LimitScope<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitScope::Driver(_) => write!(f, "driver"),
+            LimitScope::Vehicle(_, plate_number) => write!(f, "vehicle {}", plate_number),
+        }
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .map_err(|_| AppError::InternalServerError("Invalid decimal format in database".to_string()))
+}
+
+async fn check_limits(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    scope: LimitScope<'_>,
+    limits: &SpendingLimits,
+    quantity: &Decimal,
+    amount: &Decimal,
+) -> Result<()> {
+    for (window, amount_limit, liter_limit) in [
+        (QuotaWindow::Daily, &limits.daily_amount_limit, &limits.daily_liter_limit),
+        (QuotaWindow::Monthly, &limits.monthly_amount_limit, &limits.monthly_liter_limit),
+    ] {
+        if amount_limit.is_none() && liter_limit.is_none() {
+            continue;
+        }
+
+        // Transaksi yang dibatalkan tidak dihitung
+        let (spent, liters): (String, String) = sqlx::query_as(&format!(
+            r#"
+            SELECT COALESCE(SUM(COALESCE(final_price, total_price)), 0)::text,
+                   COALESCE(SUM(COALESCE(dispensed_quantity, quantity)), 0)::text
+            FROM transactions
+            WHERE organization_id = $1
+              AND {} = $2
+              AND status <> 'cancelled'
+              AND created_at > NOW() - $3::interval
+            "#,
+            scope.column()
+        ))
+        .bind(organization_id)
+        .bind(scope.id())
+        .bind(window.interval())
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if let Some(limit) = amount_limit {
+            let usage = QuotaUsage::new(limit.clone(), parse_decimal(&spent)?);
+            if usage.used.clone() + amount.clone() > usage.limit {
+                return Err(AppError::BadRequest(format!(
+                    "{} spending limit exceeded for {}: requested Rp {}, remaining Rp {} of Rp {}",
+                    window, scope, amount, usage.remaining, usage.limit
+                )));
+            }
+        }
+        if let Some(limit) = liter_limit {
+            let usage = QuotaUsage::new(limit.clone(), parse_decimal(&liters)?);
+            if usage.used.clone() + quantity.clone() > usage.limit {
+                return Err(AppError::BadRequest(format!(
+                    "{} liter limit exceeded for {}: requested {} liters, remaining {} of {} liters",
+                    window, scope, quantity, usage.remaining, usage.limit
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Cek jenis BBM, brand, dan limit driver/kendaraan untuk transaksi organisasi.
+///
+/// Harus dipanggil di dalam transaksi database yang sama dengan INSERT transaksi:
+/// baris anggota dan kendaraan armada dikunci (FOR UPDATE) sampai commit, sehingga
+/// pembelian paralel tidak bisa sama-sama lolos dari sisa limit yang sama.
+pub async fn enforce_spending_limits(
+    conn: &mut PgConnection,
+    organization: &Organization,
+    purchase: OrganizationPurchase<'_>,
+) -> Result<()> {
+    if !organization.allows_fuel_type(purchase.fuel_type) {
+        return Err(AppError::BadRequest(format!(
+            "{} is not allowed for organization {}",
+            purchase.fuel_type, organization.name
+        )));
+    }
+    if organization.allowed_brand_ids.is_some() {
+        let brand_id: Option<Uuid> = sqlx::query_scalar("SELECT brand_id FROM spbu WHERE id = $1")
+            .bind(purchase.spbu_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .flatten();
+        if !organization.allows_brand(brand_id) {
+            return Err(AppError::BadRequest(format!(
+                "This SPBU brand is not allowed for organization {}",
+                organization.name
+            )));
+        }
+    }
+
+    let member_limits = sqlx::query(
+        r#"
+        SELECT
+            daily_amount_limit::text AS daily_amount_limit,
+            monthly_amount_limit::text AS monthly_amount_limit,
+            daily_liter_limit::text AS daily_liter_limit,
+            monthly_liter_limit::text AS monthly_liter_limit
+        FROM organization_members
+        WHERE organization_id = $1 AND user_id = $2 AND is_active
+        FOR UPDATE
+        "#,
+    )
+    .bind(organization.id)
+    .bind(purchase.user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Organization membership is no longer active".to_string()))?;
+    let member_limits = SpendingLimits::from_row(&member_limits)?;
+
+    check_limits(
+        conn,
+        organization.id,
+        LimitScope::Driver(purchase.user_id),
+        &member_limits,
+        purchase.quantity,
+        purchase.amount,
+    )
+    .await?;
+
+    if let Some(vehicle) = purchase.vehicle {
+        let vehicle_limits = sqlx::query(
+            r#"
+            SELECT
+                daily_amount_limit::text AS daily_amount_limit,
+                monthly_amount_limit::text AS monthly_amount_limit,
+                daily_liter_limit::text AS daily_liter_limit,
+                monthly_liter_limit::text AS monthly_liter_limit
+            FROM organization_vehicles
+            WHERE organization_id = $1 AND vehicle_id = $2 AND is_active
+            FOR UPDATE
+            "#,
+        )
+        .bind(organization.id)
+        .bind(vehicle.id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Vehicle is not registered to this organization".to_string()))?;
+        let vehicle_limits = SpendingLimits::from_row(&vehicle_limits)?;
+
+        check_limits(
+            conn,
+            organization.id,
+            LimitScope::Vehicle(vehicle.id, &vehicle.plate_number),
+            &vehicle_limits,
+            purchase.quantity,
+            purchase.amount,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// POST /organizations (pembuat otomatis menjadi admin)
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<Organization>)> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name is required".to_string()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let organization = sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        INSERT INTO organizations (name, billing_email, tax_id, allowed_fuel_types, allowed_brand_ids, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING {}
+        "#,
+        ORGANIZATION_COLUMNS
+    ))
+    .bind(name)
+    .bind(payload.billing_email)
+    .bind(payload.tax_id)
+    .bind(normalize_fuel_types(payload.allowed_fuel_types).filter(|f| !f.is_empty()))
+    .bind(payload.allowed_brand_ids.filter(|b| !b.is_empty()))
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)")
+        .bind(organization.id)
+        .bind(user_id)
+        .bind(OrganizationRole::Admin)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+// GET /me/organizations
+pub async fn list_my_organizations(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<MyOrganization>>> {
+    let organizations = sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        SELECT {} FROM organizations
+        WHERE id IN (SELECT organization_id FROM organization_members WHERE user_id = $1 AND is_active)
+        ORDER BY name
+        "#,
+        ORGANIZATION_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut result = Vec::with_capacity(organizations.len());
+    for organization in organizations {
+        let role = find_member_role(&state.db, organization.id, user_id).await?;
+        result.push(MyOrganization { organization, role });
+    }
+    Ok(Json(result))
+}
+
+// GET /organizations/:id
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Organization>> {
+    find_member_role(&state.db, organization_id, user_id).await?;
+
+    let organization = sqlx::query_as::<_, Organization>(&format!(
+        "SELECT {} FROM organizations WHERE id = $1",
+        ORGANIZATION_COLUMNS
+    ))
+    .bind(organization_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(organization))
+}
+
+// PUT /organizations/:id
+pub async fn update_organization(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let name = payload.name.map(|n| n.trim().to_string());
+    if name.as_deref().is_some_and(str::is_empty) {
+        return Err(AppError::BadRequest("name must not be empty".to_string()));
+    }
+    let update_fuel_types = payload.allowed_fuel_types.is_some();
+    let update_brands = payload.allowed_brand_ids.is_some();
+
+    let organization = sqlx::query_as::<_, Organization>(&format!(
+        r#"
+        UPDATE organizations
+        SET
+            name = COALESCE($1, name),
+            billing_email = COALESCE($2, billing_email),
+            tax_id = COALESCE($3, tax_id),
+            allowed_fuel_types = CASE WHEN $4 THEN $5 ELSE allowed_fuel_types END,
+            allowed_brand_ids = CASE WHEN $6 THEN $7 ELSE allowed_brand_ids END,
+            is_active = COALESCE($8, is_active)
+        WHERE id = $9
+        RETURNING {}
+        "#,
+        ORGANIZATION_COLUMNS
+    ))
+    .bind(name)
+    .bind(payload.billing_email)
+    .bind(payload.tax_id)
+    .bind(update_fuel_types)
+    .bind(normalize_fuel_types(payload.allowed_fuel_types).filter(|f| !f.is_empty()))
+    .bind(update_brands)
+    .bind(payload.allowed_brand_ids.filter(|b| !b.is_empty()))
+    .bind(payload.is_active)
+    .bind(organization_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(organization))
+}
+
+async fn fetch_member<'e, E: PgExecutor<'e>>(
+    executor: E,
+    organization_id: Uuid,
+    member_user_id: Uuid,
+) -> Result<OrganizationMember> {
+    sqlx::query_as::<_, OrganizationMember>(&format!(
+        r#"
+        SELECT {} FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1 AND m.user_id = $2
+        "#,
+        MEMBER_COLUMNS
+    ))
+    .bind(organization_id)
+    .bind(member_user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Member not found".to_string()))
+}
+
+/// Kunci organisasi selama perubahan anggota, lalu pastikan masih ada admin aktif
+async fn lock_organization(conn: &mut PgConnection, organization_id: Uuid) -> Result<()> {
+    sqlx::query("SELECT id FROM organizations WHERE id = $1 FOR UPDATE")
+        .bind(organization_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn ensure_active_admin(conn: &mut PgConnection, organization_id: Uuid) -> Result<()> {
+    let has_admin: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM organization_members
+            WHERE organization_id = $1 AND role = 'admin' AND is_active
+        )
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !has_admin {
+        return Err(AppError::BadRequest(
+            "Organization must keep at least one active admin".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// GET /organizations/:id/members
+pub async fn list_members(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationMember>>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let members = sqlx::query_as::<_, OrganizationMember>(&format!(
+        r#"
+        SELECT {} FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+        ORDER BY m.role, u.email
+        "#,
+        MEMBER_COLUMNS
+    ))
+    .bind(organization_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(members))
+}
+
+// POST /organizations/:id/members
+pub async fn add_member(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<AddMemberRequest>,
+) -> Result<(StatusCode, Json<OrganizationMember>)> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    validate_limits(&payload.limits)?;
+
+    let member_user_id: Uuid = sqlx::query_scalar("SELECT id FROM users WHERE LOWER(email) = LOWER($1)")
+        .bind(payload.email.trim())
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let [daily_amount, monthly_amount, daily_liter, monthly_liter] = limit_params(&payload.limits);
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (
+            organization_id, user_id, role,
+            daily_amount_limit, monthly_amount_limit, daily_liter_limit, monthly_liter_limit
+        )
+        VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::numeric, $7::numeric)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(member_user_id)
+    .bind(payload.role.unwrap_or(OrganizationRole::Driver))
+    .bind(daily_amount)
+    .bind(monthly_amount)
+    .bind(daily_liter)
+    .bind(monthly_liter)
+    .execute(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("User is already a member of this organization".to_string())
+        }
+        e => e.into(),
+    })?;
+
+    let member = fetch_member(&state.db, organization_id, member_user_id).await?;
+    Ok((StatusCode::CREATED, Json(member)))
+}
+
+// PUT /organizations/:id/members/:user_id
+pub async fn update_member(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, member_user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateMemberRequest>,
+) -> Result<Json<OrganizationMember>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    validate_limits(&payload.limits)?;
+
+    let mut tx = state.db.begin().await?;
+    lock_organization(&mut tx, organization_id).await?;
+
+    let [daily_amount, monthly_amount, daily_liter, monthly_liter] = limit_params(&payload.limits);
+    let result = sqlx::query(
+        r#"
+        UPDATE organization_members
+        SET
+            role = COALESCE($1, role),
+            is_active = COALESCE($2, is_active),
+            daily_amount_limit = $3::numeric,
+            monthly_amount_limit = $4::numeric,
+            daily_liter_limit = $5::numeric,
+            monthly_liter_limit = $6::numeric
+        WHERE organization_id = $7 AND user_id = $8
+        "#,
+    )
+    .bind(payload.role)
+    .bind(payload.is_active)
+    .bind(daily_amount)
+    .bind(monthly_amount)
+    .bind(daily_liter)
+    .bind(monthly_liter)
+    .bind(organization_id)
+    .bind(member_user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Member not found".to_string()));
+    }
+    ensure_active_admin(&mut tx, organization_id).await?;
+
+    let member = fetch_member(&mut *tx, organization_id, member_user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(member))
+}
+
+// DELETE /organizations/:id/members/:user_id
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, member_user_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+    lock_organization(&mut tx, organization_id).await?;
+
+    let result = sqlx::query("DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2")
+        .bind(organization_id)
+        .bind(member_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Member not found".to_string()));
+    }
+    ensure_active_admin(&mut tx, organization_id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn fetch_organization_vehicle<'e, E: PgExecutor<'e>>(
+    executor: E,
+    organization_id: Uuid,
+    vehicle_id: Uuid,
+) -> Result<OrganizationVehicle> {
+    sqlx::query_as::<_, OrganizationVehicle>(&format!(
+        r#"
+        SELECT {} FROM organization_vehicles ov
+        JOIN vehicles v ON v.id = ov.vehicle_id
+        WHERE ov.organization_id = $1 AND ov.vehicle_id = $2
+        "#,
+        ORGANIZATION_VEHICLE_COLUMNS
+    ))
+    .bind(organization_id)
+    .bind(vehicle_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Vehicle is not registered to this organization".to_string()))
+}
+
+// GET /organizations/:id/vehicles
+pub async fn list_organization_vehicles(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationVehicle>>> {
+    find_member_role(&state.db, organization_id, user_id).await?;
+
+    let vehicles = sqlx::query_as::<_, OrganizationVehicle>(&format!(
+        r#"
+        SELECT {} FROM organization_vehicles ov
+        JOIN vehicles v ON v.id = ov.vehicle_id
+        WHERE ov.organization_id = $1
+        ORDER BY v.plate_number
+        "#,
+        ORGANIZATION_VEHICLE_COLUMNS
+    ))
+    .bind(organization_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(vehicles))
+}
+
+// POST /organizations/:id/vehicles (kendaraan milik salah satu anggota organisasi)
+pub async fn add_organization_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<AddOrganizationVehicleRequest>,
+) -> Result<(StatusCode, Json<OrganizationVehicle>)> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    validate_limits(&payload.limits)?;
+
+    let owned_by_member: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM vehicles v
+            JOIN organization_members m ON m.user_id = v.user_id
+            WHERE v.id = $1 AND m.organization_id = $2 AND m.is_active
+        )
+        "#,
+    )
+    .bind(payload.vehicle_id)
+    .bind(organization_id)
+    .fetch_one(&state.db)
+    .await?;
+    if !owned_by_member {
+        return Err(AppError::BadRequest(
+            "Vehicle not found or not owned by a member of this organization".to_string(),
+        ));
+    }
+
+    let [daily_amount, monthly_amount, daily_liter, monthly_liter] = limit_params(&payload.limits);
+    sqlx::query(
+        r#"
+        INSERT INTO organization_vehicles (
+            organization_id, vehicle_id,
+            daily_amount_limit, monthly_amount_limit, daily_liter_limit, monthly_liter_limit
+        )
+        VALUES ($1, $2, $3::numeric, $4::numeric, $5::numeric, $6::numeric)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(payload.vehicle_id)
+    .bind(daily_amount)
+    .bind(monthly_amount)
+    .bind(daily_liter)
+    .bind(monthly_liter)
+    .execute(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("Vehicle is already registered to an organization".to_string())
+        }
+        e => e.into(),
+    })?;
+
+    let vehicle = fetch_organization_vehicle(&state.db, organization_id, payload.vehicle_id).await?;
+    Ok((StatusCode::CREATED, Json(vehicle)))
+}
+
+// PUT /organizations/:id/vehicles/:vehicle_id
+pub async fn update_organization_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, vehicle_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateOrganizationVehicleRequest>,
+) -> Result<Json<OrganizationVehicle>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    validate_limits(&payload.limits)?;
+
+    let [daily_amount, monthly_amount, daily_liter, monthly_liter] = limit_params(&payload.limits);
+    let result = sqlx::query(
+        r#"
+        UPDATE organization_vehicles
+        SET
+            is_active = COALESCE($1, is_active),
+            daily_amount_limit = $2::numeric,
+            monthly_amount_limit = $3::numeric,
+            daily_liter_limit = $4::numeric,
+            monthly_liter_limit = $5::numeric
+        WHERE organization_id = $6 AND vehicle_id = $7
+        "#,
+    )
+    .bind(payload.is_active)
+    .bind(daily_amount)
+    .bind(monthly_amount)
+    .bind(daily_liter)
+    .bind(monthly_liter)
+    .bind(organization_id)
+    .bind(vehicle_id)
+    .execute(&state.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Vehicle is not registered to this organization".to_string()));
+    }
+
+    let vehicle = fetch_organization_vehicle(&state.db, organization_id, vehicle_id).await?;
+    Ok(Json(vehicle))
+}
+
+// DELETE /organizations/:id/vehicles/:vehicle_id
+pub async fn remove_organization_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, vehicle_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let result = sqlx::query("DELETE FROM organization_vehicles WHERE organization_id = $1 AND vehicle_id = $2")
+        .bind(organization_id)
+        .bind(vehicle_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Vehicle is not registered to this organization".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /organizations/:id/transactions
+pub async fn list_organization_transactions(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<TransactionResponse>>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let transactions = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE organization_id = $1 ORDER BY created_at DESC",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(organization_id)
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(TransactionResponse::from)
+    .collect();
+
+    Ok(Json(transactions))
+}
+
+// GET /organizations/:id/invoices
+pub async fn list_invoices(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationInvoice>>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let invoices = sqlx::query_as::<_, OrganizationInvoice>(&format!(
+        "SELECT {} FROM organization_invoices WHERE organization_id = $1 ORDER BY period_start DESC",
+        INVOICE_COLUMNS
+    ))
+    .bind(organization_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(invoices))
+}
+
+// POST /organizations/:id/invoices
+// Tagihan bulanan untuk semua transaksi organisasi pada periode yang sudah selesai
+pub async fn create_invoice(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<CreateInvoiceRequest>,
+) -> Result<(StatusCode, Json<OrganizationInvoice>)> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let (period_start, period_end) = parse_period(&payload.period)?;
+    if !period_has_ended(period_end) {
+        return Err(AppError::BadRequest(
+            "Invoices can only be issued after the period has ended".to_string(),
+        ));
+    }
+    let due_date = period_end + Days::new(INVOICE_DUE_DAYS);
+
+    let mut tx = state.db.begin().await?;
+    let invoice_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO organization_invoices (organization_id, period_start, period_end, transaction_count, total_amount, due_date)
+        VALUES ($1, $2, $3, 0, 0, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(organization_id)
+    .bind(period_start)
+    .bind(period_end)
+    .bind(due_date)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict(format!("Invoice for {} has already been issued", payload.period.trim()))
+        }
+        e => e.into(),
+    })?;
+
+    let invoiced = sqlx::query(
+        r#"
+        UPDATE transactions
+        SET invoice_id = $1, updated_at = NOW()
+        WHERE organization_id = $2
+          AND invoice_id IS NULL
+          AND status = 'completed'
+          AND created_at >= $3::date::timestamp AT TIME ZONE $5
+          AND created_at < $4::date::timestamp AT TIME ZONE $5
+        "#,
+    )
+    .bind(invoice_id)
+    .bind(organization_id)
+    .bind(period_start)
+    .bind(period_end)
+    .bind(BILLING_TIMEZONE)
+    .execute(&mut *tx)
+    .await?;
+
+    if invoiced.rows_affected() == 0 {
+        return Err(AppError::BadRequest(format!(
+            "No transactions to invoice for {}",
+            payload.period.trim()
+        )));
+    }
+
+    let invoice = sqlx::query_as::<_, OrganizationInvoice>(&format!(
+        r#"
+        UPDATE organization_invoices
+        SET
+            transaction_count = (SELECT COUNT(*) FROM transactions WHERE invoice_id = $1),
+            total_amount = (
+                SELECT COALESCE(SUM(COALESCE(final_price, total_price)), 0) FROM transactions WHERE invoice_id = $1
+            )
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        INVOICE_COLUMNS
+    ))
+    .bind(invoice_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(invoice)))
+}
+
+async fn fetch_invoice_transactions(state: &AppState, invoice_id: Uuid) -> Result<Vec<TransactionResponse>> {
+    let transactions = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE invoice_id = $1 ORDER BY created_at",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(invoice_id)
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(TransactionResponse::from)
+    .collect();
+
+    Ok(transactions)
+}
+
+// GET /organizations/:id/invoices/:invoice_id
+pub async fn get_invoice(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, invoice_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<InvoiceDetail>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let invoice = sqlx::query_as::<_, OrganizationInvoice>(&format!(
+        "SELECT {} FROM organization_invoices WHERE id = $1 AND organization_id = $2",
+        INVOICE_COLUMNS
+    ))
+    .bind(invoice_id)
+    .bind(organization_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Invoice not found".to_string()))?;
+
+    let transactions = fetch_invoice_transactions(&state, invoice.id).await?;
+    Ok(Json(InvoiceDetail { invoice, transactions }))
+}
+
+// POST /organizations/:id/invoices/:invoice_id/pay
+pub async fn pay_invoice(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, invoice_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<PayInvoiceRequest>,
+) -> Result<Json<InvoiceDetail>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+
+    let payment_method = payload.payment_method.trim().to_lowercase();
+    if payment_method.is_empty() {
+        return Err(AppError::BadRequest("payment_method is required".to_string()));
+    }
+    if payment_method == PAYMENT_METHOD_WALLET {
+        return Err(AppError::BadRequest(
+            "Organization invoices cannot be paid from a personal wallet".to_string(),
+        ));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let invoice = sqlx::query_as::<_, OrganizationInvoice>(&format!(
+        "SELECT {} FROM organization_invoices WHERE id = $1 AND organization_id = $2 FOR UPDATE",
+        INVOICE_COLUMNS
+    ))
+    .bind(invoice_id)
+    .bind(organization_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Invoice not found".to_string()))?;
+
+    if invoice.status == InvoiceStatus::Paid {
+        return Err(AppError::BadRequest("Invoice has already been paid".to_string()));
+    }
+
+    if invoice.total_amount.0 > BigDecimal::from(0) {
+        if let PaymentOutcome::Failed(reason) = payment::charge(&payment_method, &invoice.total_amount).await {
+            return Err(AppError::BadRequest(format!("Payment failed: {}", reason)));
+        }
+    }
+
+    let invoice = sqlx::query_as::<_, OrganizationInvoice>(&format!(
+        "UPDATE organization_invoices SET status = $1, paid_at = NOW() WHERE id = $2 RETURNING {}",
+        INVOICE_COLUMNS
+    ))
+    .bind(InvoiceStatus::Paid)
+    .bind(invoice.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE transactions SET payment_status = 'paid', paid_at = NOW(), updated_at = NOW() WHERE invoice_id = $1",
+    )
+    .bind(invoice.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let transactions = fetch_invoice_transactions(&state, invoice.id).await?;
+    Ok(Json(InvoiceDetail { invoice, transactions }))
+}
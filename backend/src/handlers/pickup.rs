@@ -0,0 +1,284 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use sqlx::PgConnection;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::operator::require_staff;
+use crate::handlers::transaction::{finish_transaction, TRANSACTION_COLUMNS};
+use crate::models::pickup::{CompletePickupRequest, RedeemPickupRequest};
+use crate::models::{Transaction, TransactionResponse, TransactionStatus};
+use crate::pickup::{self, PickupCode};
+use crate::types::Decimal;
+use crate::AppState;
+
+/// Simpan nonce baru untuk transaksi lalu tandatangani kode QR-nya.
+/// Kode yang dibuat sebelumnya otomatis tidak berlaku lagi.
+pub async fn issue_pickup_code(conn: &mut PgConnection, transaction_id: Uuid) -> Result<PickupCode> {
+    let nonce: Uuid = sqlx::query_scalar(
+        "UPDATE transactions SET pickup_nonce = gen_random_uuid() WHERE id = $1 RETURNING pickup_nonce",
+    )
+    .bind(transaction_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(pickup::sign(transaction_id, nonce))
+}
+
+// Kelebihan liter yang masih diterima dibanding pesanan (persen)
+const DISPENSE_TOLERANCE_PERCENT: i32 = 10;
+
+// Liter aktual boleh berbeda dari pesanan; selisihnya direkonsiliasi saat transaksi selesai.
+// Batas atasnya pesanan + toleransi, dan kapasitas tangki jika pesanan untuk kendaraan tertentu.
+fn validate_dispensed(
+    dispensed_liters: &Decimal,
+    ordered_liters: &Decimal,
+    tank_capacity: Option<&Decimal>,
+) -> Result<()> {
+    let liters = &dispensed_liters.0;
+    if *liters <= BigDecimal::from(0) {
+        return Err(AppError::BadRequest("dispensed_liters must be greater than 0".to_string()));
+    }
+    // Kolom dispensed_quantity bertipe DECIMAL(10, 2)
+    if liters.with_scale(2) != *liters {
+        return Err(AppError::BadRequest(
+            "dispensed_liters must have at most 2 decimal places".to_string(),
+        ));
+    }
+    if *liters >= BigDecimal::from(100_000_000) {
+        return Err(AppError::BadRequest("dispensed_liters is too large".to_string()));
+    }
+
+    let max_liters = (&ordered_liters.0 * BigDecimal::from(100 + DISPENSE_TOLERANCE_PERCENT)
+        / BigDecimal::from(100))
+    .with_scale(2);
+    if *liters > max_liters {
+        return Err(AppError::BadRequest(format!(
+            "dispensed_liters exceeds the ordered quantity by more than {}% (max {} liters)",
+            DISPENSE_TOLERANCE_PERCENT,
+            Decimal(max_liters)
+        )));
+    }
+    if let Some(tank_capacity) = tank_capacity {
+        if *liters > tank_capacity.0 {
+            return Err(AppError::BadRequest(format!(
+                "dispensed_liters exceeds the vehicle tank capacity (max {} liters)",
+                tank_capacity
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn check_dispensed(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+    dispensed_liters: &Decimal,
+) -> Result<()> {
+    let tank_capacity: Option<String> = match transaction.vehicle_id {
+        Some(vehicle_id) => {
+            sqlx::query_scalar("SELECT tank_capacity::text FROM vehicles WHERE id = $1")
+                .bind(vehicle_id)
+                .fetch_optional(&mut *conn)
+                .await?
+        }
+        None => None,
+    };
+    let tank_capacity = tank_capacity
+        .map(|value| {
+            Decimal::from_str(&value).map_err(|_| {
+                AppError::InternalServerError("Invalid decimal format in database".to_string())
+            })
+        })
+        .transpose()?;
+    validate_dispensed(dispensed_liters, &transaction.quantity, tank_capacity.as_ref())
+}
+
+// GET /transactions/:id/pickup-code
+// Buat ulang kode QR, misalnya karena kode sebelumnya sudah kedaluwarsa
+pub async fn get_pickup_code(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<Json<PickupCode>> {
+    let mut tx = state.db.begin().await?;
+
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(transaction_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))?;
+
+    if transaction.pickup_redeemed_at.is_some() {
+        return Err(AppError::Conflict("Pickup code has already been redeemed".to_string()));
+    }
+    if transaction.status != TransactionStatus::Processing {
+        return Err(AppError::BadRequest(
+            "Pickup codes are only available for paid transactions".to_string(),
+        ));
+    }
+
+    let code = issue_pickup_code(&mut tx, transaction_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(code))
+}
+
+// POST /operator/redeem
+// Dipanggil petugas setelah memindai QR pelanggan
+pub async fn redeem_pickup(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<RedeemPickupRequest>,
+) -> Result<Json<TransactionResponse>> {
+    let (transaction_id, nonce) =
+        pickup::verify(&payload.code).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let mut tx = state.db.begin().await?;
+
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE id = $1 FOR UPDATE",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(transaction_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))?;
+
+    // Kode hanya bisa ditukar di SPBU tempat pesanan dibuat
+    require_staff(&mut *tx, transaction.spbu_id, user_id).await?;
+
+    if transaction.pickup_redeemed_at.is_some() {
+        return Err(AppError::Conflict("Pickup code has already been redeemed".to_string()));
+    }
+    if transaction.status != TransactionStatus::Processing {
+        return Err(AppError::BadRequest(
+            "Only paid transactions can be redeemed".to_string(),
+        ));
+    }
+
+    let current_nonce: Option<Uuid> =
+        sqlx::query_scalar("SELECT pickup_nonce FROM transactions WHERE id = $1")
+            .bind(transaction_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    if current_nonce != Some(nonce) {
+        return Err(AppError::BadRequest(
+            "Pickup code has been replaced by a newer one".to_string(),
+        ));
+    }
+
+    if let Some(dispensed_liters) = &payload.dispensed_liters {
+        check_dispensed(&mut tx, &transaction, dispensed_liters).await?;
+    }
+
+    let redeemed = sqlx::query_as::<_, Transaction>(&format!(
+        r#"
+        UPDATE transactions
+        SET pickup_redeemed_at = NOW(), pickup_redeemed_by = $1, pickup_nonce = NULL, updated_at = NOW()
+        WHERE id = $2
+        RETURNING {}
+        "#,
+        TRANSACTION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(transaction_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let response = match &payload.dispensed_liters {
+        Some(dispensed_liters) => finish_transaction(&mut tx, &redeemed, Some(dispensed_liters)).await?,
+        None => redeemed.into(),
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+// POST /operator/spbu/:id/transactions/:transaction_id/complete
+// Catat liter yang dikeluarkan untuk pesanan yang kodenya sudah ditukar
+pub async fn complete_pickup(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, transaction_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<CompletePickupRequest>,
+) -> Result<Json<TransactionResponse>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE id = $1 AND spbu_id = $2 FOR UPDATE",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(transaction_id)
+    .bind(spbu_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))?;
+
+    if transaction.pickup_redeemed_at.is_none() {
+        return Err(AppError::BadRequest(
+            "Transaction has not been redeemed at the pump".to_string(),
+        ));
+    }
+    check_dispensed(&mut tx, &transaction, &payload.dispensed_liters).await?;
+
+    let response = finish_transaction(&mut tx, &transaction, Some(&payload.dispensed_liters)).await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn liters(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn dispensed_must_be_positive_with_two_decimals() {
+        let ordered = liters("20");
+        assert!(validate_dispensed(&liters("0"), &ordered, None).is_err());
+        assert!(validate_dispensed(&liters("-1"), &ordered, None).is_err());
+        assert!(validate_dispensed(&liters("9.999"), &ordered, None).is_err());
+        assert!(validate_dispensed(&liters("9.99"), &ordered, None).is_ok());
+        // Nol di belakang tidak menambah presisi
+        assert!(validate_dispensed(&liters("9.500"), &ordered, None).is_ok());
+        // Angka JSON dibaca sebagai f64 tapi tidak boleh jadi ekspansi binernya
+        let from_json: Decimal = serde_json::from_str("3.31").unwrap();
+        assert!(validate_dispensed(&from_json, &ordered, None).is_ok());
+    }
+
+    #[test]
+    fn dispensed_rejects_values_outside_column_range() {
+        let ordered = liters("99999999.99");
+        assert!(validate_dispensed(&liters("100000000"), &ordered, None).is_err());
+        assert!(validate_dispensed(&liters("99999999.99"), &ordered, None).is_ok());
+    }
+
+    #[test]
+    fn dispensed_is_capped_by_order_tolerance_and_tank() {
+        let ordered = liters("20");
+        // 20 liter + 10%
+        assert!(validate_dispensed(&liters("22"), &ordered, None).is_ok());
+        assert!(validate_dispensed(&liters("22.01"), &ordered, None).is_err());
+        // Kurang dari pesanan selalu boleh
+        assert!(validate_dispensed(&liters("5"), &ordered, None).is_ok());
+
+        let tank = liters("21");
+        assert!(validate_dispensed(&liters("21"), &ordered, Some(&tank)).is_ok());
+        assert!(validate_dispensed(&liters("21.5"), &ordered, Some(&tank)).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::require_platform_admin;
+use crate::error::{AppError, Result};
+use crate::models::promotion::{
+    CreatePromotionRequest, DiscountType, Promotion, UpdatePromotionRequest, ValidatePromotionRequest,
+    ValidatePromotionResponse,
+};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Kolom promotions; kolom numeric di-cast ke text karena di-parse manual ke Decimal
+pub const PROMOTION_COLUMNS: &str = r#"
+    id, code, description, discount_type,
+    discount_value::text AS discount_value,
+    max_discount::text AS max_discount,
+    min_purchase::text AS min_purchase,
+    brand_id, spbu_id, fuel_type, first_time_only,
+    starts_at, ends_at, max_uses, max_uses_per_user, is_active,
+    created_at, updated_at
+"#;
+
+fn invalid_code(code: &str, reason: &str) -> AppError {
+    AppError::BadRequest(format!("Promo code {} {}", code, reason))
+}
+
+/// Validasi kode promo untuk pembelian dan hitung potongannya.
+///
+/// Baris promo dikunci (FOR UPDATE) sampai transaksi database selesai, sehingga
+/// pengecekan batas pemakaian dan pencatatan redemption oleh request paralel
+/// berjalan bergantian dan batas tidak bisa terlampaui.
+pub async fn apply_promotion(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    code: &str,
+    spbu_id: Uuid,
+    fuel_type: &str,
+    amount: &Decimal,
+) -> Result<(Promotion, Decimal)> {
+    let code = code.trim().to_uppercase();
+    let promotion = sqlx::query_as::<_, Promotion>(&format!(
+        "SELECT {} FROM promotions WHERE code = $1 FOR UPDATE",
+        PROMOTION_COLUMNS
+    ))
+    .bind(&code)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Promo code {} not found", code)))?;
+
+    if !promotion.is_active {
+        return Err(invalid_code(&code, "is no longer active"));
+    }
+    let now = Utc::now();
+    if promotion.starts_at.is_some_and(|starts_at| now < starts_at) {
+        return Err(invalid_code(&code, "is not valid yet"));
+    }
+    if promotion.ends_at.is_some_and(|ends_at| now >= ends_at) {
+        return Err(invalid_code(&code, "has expired"));
+    }
+    if *amount < promotion.min_purchase {
+        return Err(invalid_code(
+            &code,
+            &format!("requires a minimum purchase of {}", promotion.min_purchase),
+        ));
+    }
+    if promotion
+        .fuel_type
+        .as_deref()
+        .is_some_and(|f| !f.eq_ignore_ascii_case(fuel_type))
+    {
+        return Err(invalid_code(&code, "is not valid for this fuel type"));
+    }
+    if promotion.spbu_id.is_some_and(|id| id != spbu_id) {
+        return Err(invalid_code(&code, "is not valid at this SPBU"));
+    }
+    if let Some(brand_id) = promotion.brand_id {
+        let spbu_brand: Option<Uuid> = sqlx::query_scalar("SELECT brand_id FROM spbu WHERE id = $1")
+            .bind(spbu_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .flatten();
+        if spbu_brand != Some(brand_id) {
+            return Err(invalid_code(&code, "is not valid for this brand"));
+        }
+    }
+    if promotion.first_time_only {
+        let has_transactions: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM transactions WHERE user_id = $1 AND status <> 'cancelled')",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+        if has_transactions {
+            return Err(invalid_code(&code, "is only valid for a first transaction"));
+        }
+    }
+
+    if promotion.max_uses.is_some() || promotion.max_uses_per_user.is_some() {
+        // Pemakaian pada transaksi yang dibatalkan tidak dihitung
+        let (total_uses, user_uses): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE r.user_id = $2)
+            FROM promotion_redemptions r
+            JOIN transactions t ON t.id = r.transaction_id
+            WHERE r.promotion_id = $1 AND t.status <> 'cancelled'
+            "#,
+        )
+        .bind(promotion.id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if promotion.max_uses.is_some_and(|max| total_uses >= max as i64) {
+            return Err(invalid_code(&code, "has reached its usage limit"));
+        }
+        if promotion.max_uses_per_user.is_some_and(|max| user_uses >= max as i64) {
+            return Err(invalid_code(&code, "has already been used the maximum number of times"));
+        }
+    }
+
+    let discount = promotion.discount_for(amount);
+    Ok((promotion, discount))
+}
+
+/// Catat pemakaian promo untuk transaksi (dipanggil dalam transaksi database yang sama)
+pub async fn record_redemption(
+    conn: &mut PgConnection,
+    promotion_id: Uuid,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    discount: &Decimal,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO promotion_redemptions (promotion_id, user_id, transaction_id, discount_amount)
+        VALUES ($1, $2, $3, $4::numeric)
+        "#,
+    )
+    .bind(promotion_id)
+    .bind(user_id)
+    .bind(transaction_id)
+    .bind(discount.to_string())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+// POST /promotions/validate
+pub async fn validate_promotion(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<ValidatePromotionRequest>,
+) -> Result<Json<ValidatePromotionResponse>> {
+    // Transaksi hanya untuk lock; tidak ada yang disimpan
+    let mut tx = state.db.begin().await?;
+    let (promotion, discount) = apply_promotion(
+        &mut tx,
+        user_id,
+        &payload.code,
+        payload.spbu_id,
+        &payload.fuel_type,
+        &payload.amount,
+    )
+    .await?;
+    tx.rollback().await?;
+
+    Ok(Json(ValidatePromotionResponse {
+        code: promotion.code,
+        total_after_discount: payload.amount - discount.clone(),
+        discount,
+    }))
+}
+
+fn validate_promotion_values(payload: &CreatePromotionRequest) -> Result<()> {
+    let zero = BigDecimal::from(0);
+    if payload.code.trim().is_empty() {
+        return Err(AppError::BadRequest("code is required".to_string()));
+    }
+    if payload.discount_value.0 <= zero {
+        return Err(AppError::BadRequest("discount_value must be greater than 0".to_string()));
+    }
+    if payload.discount_type == DiscountType::Percentage && payload.discount_value.0 > BigDecimal::from(100) {
+        return Err(AppError::BadRequest("Percentage discount must not exceed 100".to_string()));
+    }
+    if let (Some(starts_at), Some(ends_at)) = (payload.starts_at, payload.ends_at) {
+        if starts_at >= ends_at {
+            return Err(AppError::BadRequest("starts_at must be before ends_at".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn map_promotion_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("Promo code already exists".to_string())
+        }
+        sqlx::Error::Database(db) if db.message().contains("foreign key") => {
+            AppError::BadRequest("Brand or SPBU not found".to_string())
+        }
+        sqlx::Error::Database(db) if db.message().contains("check constraint") => {
+            AppError::BadRequest(format!("Invalid promotion: {}", db.message()))
+        }
+        e => e.into(),
+    }
+}
+
+// GET /promotions, hanya admin (berisi semua kode termasuk yang tidak dipublikasikan)
+pub async fn list_promotions(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<Promotion>>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let promotions = sqlx::query_as::<_, Promotion>(&format!(
+        "SELECT {} FROM promotions ORDER BY created_at DESC",
+        PROMOTION_COLUMNS
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(promotions))
+}
+
+// POST /promotions, hanya admin
+pub async fn create_promotion(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreatePromotionRequest>,
+) -> Result<(StatusCode, Json<Promotion>)> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    validate_promotion_values(&payload)?;
+
+    let promotion = sqlx::query_as::<_, Promotion>(&format!(
+        r#"
+        INSERT INTO promotions (
+            code, description, discount_type, discount_value, max_discount, min_purchase,
+            brand_id, spbu_id, fuel_type, first_time_only, starts_at, ends_at,
+            max_uses, max_uses_per_user
+        )
+        VALUES ($1, $2, $3, $4::numeric, $5::numeric, COALESCE($6::numeric, 0), $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING {}
+        "#,
+        PROMOTION_COLUMNS
+    ))
+    .bind(payload.code.trim().to_uppercase())
+    .bind(payload.description)
+    .bind(payload.discount_type)
+    .bind(payload.discount_value.to_string())
+    .bind(payload.max_discount.map(|m| m.to_string()))
+    .bind(payload.min_purchase.map(|m| m.to_string()))
+    .bind(payload.brand_id)
+    .bind(payload.spbu_id)
+    .bind(payload.fuel_type.map(|f| f.trim().to_uppercase()).filter(|f| !f.is_empty()))
+    .bind(payload.first_time_only)
+    .bind(payload.starts_at)
+    .bind(payload.ends_at)
+    .bind(payload.max_uses)
+    .bind(payload.max_uses_per_user)
+    .fetch_one(&state.db)
+    .await
+    .map_err(map_promotion_error)?;
+
+    Ok((StatusCode::CREATED, Json(promotion)))
+}
+
+// PUT /promotions/:id, hanya admin
+pub async fn update_promotion(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(promotion_id): Path<Uuid>,
+    Json(payload): Json<UpdatePromotionRequest>,
+) -> Result<Json<Promotion>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let promotion = sqlx::query_as::<_, Promotion>(&format!(
+        r#"
+        UPDATE promotions
+        SET
+            description = COALESCE($1, description),
+            max_discount = COALESCE($2::numeric, max_discount),
+            min_purchase = COALESCE($3::numeric, min_purchase),
+            starts_at = COALESCE($4, starts_at),
+            ends_at = COALESCE($5, ends_at),
+            max_uses = COALESCE($6, max_uses),
+            max_uses_per_user = COALESCE($7, max_uses_per_user),
+            is_active = COALESCE($8, is_active)
+        WHERE id = $9
+        RETURNING {}
+        "#,
+        PROMOTION_COLUMNS
+    ))
+    .bind(payload.description)
+    .bind(payload.max_discount.map(|m| m.to_string()))
+    .bind(payload.min_purchase.map(|m| m.to_string()))
+    .bind(payload.starts_at)
+    .bind(payload.ends_at)
+    .bind(payload.max_uses)
+    .bind(payload.max_uses_per_user)
+    .bind(payload.is_active)
+    .bind(promotion_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(map_promotion_error)?
+    .ok_or_else(|| AppError::NotFound("Promotion not found".to_string()))?;
+
+    Ok(Json(promotion))
+}
+
+// DELETE /promotions/:id, hanya admin (hanya untuk promo yang belum pernah dipakai)
+pub async fn delete_promotion(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(promotion_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let used: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM promotion_redemptions WHERE promotion_id = $1)")
+        .bind(promotion_id)
+        .fetch_one(&state.db)
+        .await?;
+    if used {
+        return Err(AppError::Conflict(
+            "Promotion has been used; deactivate it instead (is_active = false)".to_string(),
+        ));
+    }
+
+    let result = sqlx::query("DELETE FROM promotions WHERE id = $1")
+        .bind(promotion_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Promotion not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,308 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::operator::{normalize_fuel_type, require_staff, require_station_manager};
+use crate::handlers::transaction::TRANSACTION_COLUMNS;
+use crate::models::pump::{
+    AssignPumpRequest, CreatePumpRequest, Pump, PumpStatus, UpdatePumpRequest, UpdatePumpStatusRequest,
+    WaitTimeEstimate, WaitTimeQuery,
+};
+use crate::models::{Transaction, TransactionResponse, TransactionStatus};
+use crate::AppState;
+
+const PUMP_COLUMNS: &str = "id, spbu_id, pump_number, fuel_types, status, created_at, updated_at";
+
+// Rata-rata waktu melayani satu kendaraan di satu pompa
+const MINUTES_PER_VEHICLE: i64 = 3;
+
+/// Buat pompa 1..count untuk SPBU baru; pompa melayani semua jenis BBM
+pub async fn create_default_pumps(conn: &mut PgConnection, spbu_id: Uuid, count: i32) -> Result<()> {
+    sqlx::query("INSERT INTO pumps (spbu_id, pump_number) SELECT $1, n FROM generate_series(1, $2) AS n")
+        .bind(spbu_id)
+        .bind(count)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+// Normalisasi dan pastikan semua jenis BBM dijual di SPBU
+async fn validate_fuel_types(conn: &mut PgConnection, spbu_id: Uuid, fuel_types: &[String]) -> Result<Vec<String>> {
+    let mut normalized = Vec::with_capacity(fuel_types.len());
+    for fuel_type in fuel_types {
+        let fuel_type = normalize_fuel_type(fuel_type)?;
+        if !normalized.contains(&fuel_type) {
+            normalized.push(fuel_type);
+        }
+    }
+
+    let sold: Vec<String> = sqlx::query_scalar("SELECT fuel_type FROM fuel_prices WHERE spbu_id = $1")
+        .bind(spbu_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    if let Some(missing) = normalized.iter().find(|f| !sold.contains(f)) {
+        return Err(AppError::BadRequest(format!("{} is not sold at this SPBU", missing)));
+    }
+
+    Ok(normalized)
+}
+
+async fn fetch_pump<'e, E: PgExecutor<'e>>(executor: E, spbu_id: Uuid, pump_id: Uuid) -> Result<Pump> {
+    sqlx::query_as::<_, Pump>(&format!(
+        "SELECT {} FROM pumps WHERE id = $1 AND spbu_id = $2",
+        PUMP_COLUMNS
+    ))
+    .bind(pump_id)
+    .bind(spbu_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Pump not found".to_string()))
+}
+
+fn duplicate_pump_number(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("Pump number already exists at this SPBU".to_string())
+        }
+        e => e.into(),
+    }
+}
+
+// GET /spbu/:spbu_id/pumps
+pub async fn list_pumps(State(state): State<AppState>, Path(spbu_id): Path<Uuid>) -> Result<Json<Vec<Pump>>> {
+    let pumps = sqlx::query_as::<_, Pump>(&format!(
+        "SELECT {} FROM pumps WHERE spbu_id = $1 ORDER BY pump_number",
+        PUMP_COLUMNS
+    ))
+    .bind(spbu_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(pumps))
+}
+
+// POST /operator/spbu/:id/pumps
+pub async fn create_pump(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<CreatePumpRequest>,
+) -> Result<(StatusCode, Json<Pump>)> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    if payload.pump_number <= 0 {
+        return Err(AppError::BadRequest("pump_number must be greater than 0".to_string()));
+    }
+
+    let mut conn = state.db.acquire().await?;
+    let fuel_types = validate_fuel_types(&mut conn, spbu_id, &payload.fuel_types.unwrap_or_default()).await?;
+
+    let pump = sqlx::query_as::<_, Pump>(&format!(
+        "INSERT INTO pumps (spbu_id, pump_number, fuel_types, status) VALUES ($1, $2, $3, $4) RETURNING {}",
+        PUMP_COLUMNS
+    ))
+    .bind(spbu_id)
+    .bind(payload.pump_number)
+    .bind(&fuel_types)
+    .bind(payload.status.unwrap_or(PumpStatus::Active))
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(duplicate_pump_number)?;
+
+    Ok((StatusCode::CREATED, Json(pump)))
+}
+
+// PUT /operator/spbu/:id/pumps/:pump_id
+pub async fn update_pump(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, pump_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdatePumpRequest>,
+) -> Result<Json<Pump>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    let mut conn = state.db.acquire().await?;
+    let fuel_types = match &payload.fuel_types {
+        Some(fuel_types) => Some(validate_fuel_types(&mut conn, spbu_id, fuel_types).await?),
+        None => None,
+    };
+
+    let pump = sqlx::query_as::<_, Pump>(&format!(
+        r#"
+        UPDATE pumps
+        SET fuel_types = COALESCE($1, fuel_types), status = COALESCE($2, status)
+        WHERE id = $3 AND spbu_id = $4
+        RETURNING {}
+        "#,
+        PUMP_COLUMNS
+    ))
+    .bind(fuel_types)
+    .bind(payload.status)
+    .bind(pump_id)
+    .bind(spbu_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Pump not found".to_string()))?;
+
+    Ok(Json(pump))
+}
+
+// PUT /operator/spbu/:id/pumps/:pump_id/status
+// Boleh dilakukan semua staf, termasuk kasir
+pub async fn update_pump_status(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, pump_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdatePumpStatusRequest>,
+) -> Result<Json<Pump>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    let pump = sqlx::query_as::<_, Pump>(&format!(
+        "UPDATE pumps SET status = $1 WHERE id = $2 AND spbu_id = $3 RETURNING {}",
+        PUMP_COLUMNS
+    ))
+    .bind(payload.status)
+    .bind(pump_id)
+    .bind(spbu_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Pump not found".to_string()))?;
+
+    Ok(Json(pump))
+}
+
+// DELETE /operator/spbu/:id/pumps/:pump_id
+pub async fn delete_pump(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, pump_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    let result = sqlx::query("DELETE FROM pumps WHERE id = $1 AND spbu_id = $2")
+        .bind(pump_id)
+        .bind(spbu_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Pump not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /operator/spbu/:id/transactions/:transaction_id/pump
+pub async fn assign_pump(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, transaction_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<AssignPumpRequest>,
+) -> Result<Json<TransactionResponse>> {
+    require_staff(&state.db, spbu_id, user_id).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE id = $1 AND spbu_id = $2 FOR UPDATE",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(transaction_id)
+    .bind(spbu_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))?;
+
+    if matches!(transaction.status, TransactionStatus::Completed | TransactionStatus::Cancelled) {
+        return Err(AppError::BadRequest(
+            "Pumps can only be assigned to open orders".to_string(),
+        ));
+    }
+
+    if let Some(pump_id) = payload.pump_id {
+        let pump = fetch_pump(&mut *tx, spbu_id, pump_id).await?;
+        if pump.status != PumpStatus::Active {
+            return Err(AppError::BadRequest(format!(
+                "Pump {} is under maintenance",
+                pump.pump_number
+            )));
+        }
+        if !pump.serves(&transaction.fuel_type) {
+            return Err(AppError::BadRequest(format!(
+                "Pump {} does not serve {}",
+                pump.pump_number, transaction.fuel_type
+            )));
+        }
+    }
+
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "UPDATE transactions SET pump_id = $1 WHERE id = $2 RETURNING {}",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(payload.pump_id)
+    .bind(transaction_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(transaction.into()))
+}
+
+// GET /spbu/:spbu_id/wait-time
+// Perkiraan kasar: antrian dibagi rata ke pompa aktif yang melayani jenis BBM
+pub async fn get_wait_time(
+    State(state): State<AppState>,
+    Path(spbu_id): Path<Uuid>,
+    Query(query): Query<WaitTimeQuery>,
+) -> Result<Json<WaitTimeEstimate>> {
+    let fuel_type = query.fuel_type.as_deref().map(normalize_fuel_type).transpose()?;
+
+    let row: Option<(i64, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM pumps WHERE spbu_id = s.id),
+            (
+                SELECT COUNT(*) FROM pumps p
+                WHERE p.spbu_id = s.id
+                  AND p.status = 'active'
+                  AND (
+                      $2::text IS NULL
+                      OR (
+                          EXISTS (SELECT 1 FROM fuel_prices fp WHERE fp.spbu_id = s.id AND fp.fuel_type = $2)
+                          AND (cardinality(p.fuel_types) = 0 OR $2 = ANY(p.fuel_types))
+                      )
+                  )
+            ),
+            COALESCE(s.jumlah_antrian, 0) + (
+                SELECT COUNT(*) FROM transactions t
+                WHERE t.spbu_id = s.id AND t.status IN ('paid', 'processing')
+            )
+        FROM spbu s
+        WHERE s.id = $1
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(&fuel_type)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let (total_pumps, active_pumps, queue_length) =
+        row.ok_or_else(|| AppError::NotFound("SPBU not found".to_string()))?;
+
+    let estimated_wait_minutes = (active_pumps > 0)
+        .then(|| (queue_length * MINUTES_PER_VEHICLE + active_pumps - 1) / active_pumps);
+
+    Ok(Json(WaitTimeEstimate {
+        spbu_id,
+        fuel_type,
+        total_pumps,
+        active_pumps,
+        queue_length,
+        estimated_wait_minutes,
+    }))
+}
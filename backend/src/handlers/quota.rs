@@ -0,0 +1,239 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use sqlx::{PgConnection, Row};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::auth::require_platform_admin;
+use crate::error::{AppError, Result};
+use crate::models::quota::{QuotaPolicy, QuotaUsage, QuotaWindow, UpsertQuotaPolicyRequest, VehicleQuota};
+use crate::models::vehicle::{Vehicle, VehicleType};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Kolom fuel_quota_policies; limit di-cast ke text karena di-parse manual ke Decimal
+pub const QUOTA_POLICY_COLUMNS: &str = r#"
+    id, fuel_type, vehicle_type,
+    daily_limit::text AS daily_limit,
+    monthly_limit::text AS monthly_limit,
+    created_at, updated_at
+"#;
+
+// Total liter yang sudah dibeli (transaksi yang tidak dibatalkan) dalam jendela kuota
+const USAGE_SQL: &str = r#"
+    SELECT COALESCE(SUM(COALESCE(dispensed_quantity, quantity)), 0)::text
+    FROM transactions
+    WHERE plate_number = $1
+      AND fuel_type = $2
+      AND status <> 'cancelled'
+      AND created_at > NOW() - $3::interval
+"#;
+
+fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .map_err(|_| AppError::InternalServerError("Invalid decimal format in database".to_string()))
+}
+
+async fn usage_since(conn: &mut PgConnection, plate_number: &str, fuel_type: &str, window: QuotaWindow) -> Result<Decimal> {
+    let used: String = sqlx::query_scalar(USAGE_SQL)
+        .bind(plate_number)
+        .bind(fuel_type)
+        .bind(window.interval())
+        .fetch_one(&mut *conn)
+        .await?;
+    parse_decimal(&used)
+}
+
+/// Cek kuota BBM bersubsidi sebelum transaksi disimpan.
+///
+/// Harus dipanggil di dalam transaksi database yang sama dengan INSERT transaksi:
+/// advisory lock per nomor polisi + jenis BBM menahan pembelian paralel sampai commit,
+/// sehingga dua request bersamaan tidak bisa sama-sama lolos dari sisa kuota yang sama.
+/// BBM tanpa kebijakan kuota dianggap tidak bersubsidi dan selalu lolos.
+pub async fn enforce_quota(
+    conn: &mut PgConnection,
+    vehicle: Option<&Vehicle>,
+    fuel_type: &str,
+    quantity: &Decimal,
+) -> Result<()> {
+    let policies = sqlx::query_as::<_, QuotaPolicy>(&format!(
+        "SELECT {} FROM fuel_quota_policies WHERE fuel_type = UPPER($1)",
+        QUOTA_POLICY_COLUMNS
+    ))
+    .bind(fuel_type)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if policies.is_empty() {
+        return Ok(());
+    }
+
+    let vehicle = vehicle.ok_or_else(|| {
+        AppError::BadRequest(format!("{} is subsidized and requires a registered vehicle (vehicle_id)", fuel_type))
+    })?;
+
+    let policy = policies
+        .into_iter()
+        .find(|p| p.vehicle_type == vehicle.vehicle_type)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Vehicle {} is not eligible for subsidized {}",
+                vehicle.plate_number, fuel_type
+            ))
+        })?;
+
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("quota:{}:{}", vehicle.plate_number, fuel_type))
+        .execute(&mut *conn)
+        .await?;
+
+    for (window, limit) in [
+        (QuotaWindow::Daily, policy.daily_limit),
+        (QuotaWindow::Monthly, policy.monthly_limit),
+    ] {
+        let Some(limit) = limit else { continue };
+        let used = usage_since(conn, &vehicle.plate_number, fuel_type, window).await?;
+        let usage = QuotaUsage::new(limit, used);
+
+        if usage.used.clone() + quantity.clone() > usage.limit {
+            return Err(AppError::BadRequest(format!(
+                "{} {} quota exceeded for vehicle {}: requested {} liters, remaining {} of {} liters",
+                window, fuel_type, vehicle.plate_number, quantity, usage.remaining, usage.limit
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// GET /me/quota
+pub async fn get_my_quota(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<VehicleQuota>>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            v.id AS vehicle_id, v.plate_number, v.vehicle_type, p.fuel_type,
+            p.daily_limit::text AS daily_limit,
+            p.monthly_limit::text AS monthly_limit,
+            usage.daily_used, usage.monthly_used
+        FROM vehicles v
+        JOIN fuel_quota_policies p ON p.vehicle_type = v.vehicle_type
+        CROSS JOIN LATERAL (
+            SELECT
+                COALESCE(SUM(COALESCE(t.dispensed_quantity, t.quantity)) FILTER (WHERE t.created_at > NOW() - $2::interval), 0)::text AS daily_used,
+                COALESCE(SUM(COALESCE(t.dispensed_quantity, t.quantity)) FILTER (WHERE t.created_at > NOW() - $3::interval), 0)::text AS monthly_used
+            FROM transactions t
+            WHERE t.plate_number = v.plate_number
+              AND t.fuel_type = p.fuel_type
+              AND t.status <> 'cancelled'
+        ) usage
+        WHERE v.user_id = $1
+        ORDER BY v.created_at, p.fuel_type
+        "#
+    )
+    .bind(user_id)
+    .bind(QuotaWindow::Daily.interval())
+    .bind(QuotaWindow::Monthly.interval())
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut quotas = Vec::with_capacity(rows.len());
+    for row in rows {
+        let usage = |limit: &str, used: &str| -> Result<Option<QuotaUsage>> {
+            match row.try_get::<Option<String>, _>(limit)? {
+                Some(limit) => {
+                    let used: String = row.try_get(used)?;
+                    Ok(Some(QuotaUsage::new(parse_decimal(&limit)?, parse_decimal(&used)?)))
+                }
+                None => Ok(None),
+            }
+        };
+
+        quotas.push(VehicleQuota {
+            daily: usage("daily_limit", "daily_used")?,
+            monthly: usage("monthly_limit", "monthly_used")?,
+            vehicle_id: row.try_get("vehicle_id")?,
+            plate_number: row.try_get("plate_number")?,
+            vehicle_type: row.try_get::<VehicleType, _>("vehicle_type")?,
+            fuel_type: row.try_get("fuel_type")?,
+        });
+    }
+
+    Ok(Json(quotas))
+}
+
+// GET /quota-policies
+pub async fn list_quota_policies(State(state): State<AppState>) -> Result<Json<Vec<QuotaPolicy>>> {
+    let policies = sqlx::query_as::<_, QuotaPolicy>(&format!(
+        "SELECT {} FROM fuel_quota_policies ORDER BY fuel_type, vehicle_type",
+        QUOTA_POLICY_COLUMNS
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(policies))
+}
+
+// PUT /quota-policies (insert atau update berdasarkan fuel_type + vehicle_type), hanya admin
+pub async fn upsert_quota_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<UpsertQuotaPolicyRequest>,
+) -> Result<Json<QuotaPolicy>> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let fuel_type = payload.fuel_type.trim().to_uppercase();
+    if fuel_type.is_empty() {
+        return Err(AppError::BadRequest("fuel_type is required".to_string()));
+    }
+    for limit in [&payload.daily_limit, &payload.monthly_limit].into_iter().flatten() {
+        if limit.0 <= BigDecimal::from(0) {
+            return Err(AppError::BadRequest("Quota limits must be greater than 0".to_string()));
+        }
+    }
+
+    let policy = sqlx::query_as::<_, QuotaPolicy>(&format!(
+        r#"
+        INSERT INTO fuel_quota_policies (fuel_type, vehicle_type, daily_limit, monthly_limit)
+        VALUES ($1, $2, $3::numeric, $4::numeric)
+        ON CONFLICT (fuel_type, vehicle_type)
+        DO UPDATE SET daily_limit = EXCLUDED.daily_limit, monthly_limit = EXCLUDED.monthly_limit
+        RETURNING {}
+        "#,
+        QUOTA_POLICY_COLUMNS
+    ))
+    .bind(fuel_type)
+    .bind(payload.vehicle_type)
+    .bind(payload.daily_limit.map(|l| l.to_string()))
+    .bind(payload.monthly_limit.map(|l| l.to_string()))
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(policy))
+}
+
+// DELETE /quota-policies/:id, hanya admin
+pub async fn delete_quota_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(policy_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_platform_admin(&state.db, user_id).await?;
+
+    let result = sqlx::query("DELETE FROM fuel_quota_policies WHERE id = $1")
+        .bind(policy_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Quota policy not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
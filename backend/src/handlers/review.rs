@@ -1,15 +1,44 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use serde::Serialize;
+use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::models::review::{CreateReviewRequest, Review, ReviewResponse, SpbuRatingResponse, UpdateReviewRequest, RatingCount};
+use crate::handlers::media::{delete_owner_media, to_response};
+use crate::handlers::review_reply::load_replies;
+use crate::models::media::{MediaObject, MediaResponse};
+use crate::models::review::{
+    CreateReviewRequest, HelpfulVoteResponse, Review, ReviewList, ReviewQuery, ReviewReply, ReviewResponse,
+    ReviewSort, SpbuRatingResponse, UpdateReviewRequest, RatingCount,
+};
 use crate::AppState;
 
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+// $1 = user yang sedang melihat (boleh NULL), untuk kolom has_voted
+const REVIEW_SELECT: &str = r#"
+    SELECT
+        r.id,
+        r.user_id,
+        u.nama_lengkap AS user_name,
+        r.spbu_id,
+        s.nama AS spbu_name,
+        r.rating,
+        r.comment,
+        (SELECT COUNT(*) FROM review_votes v WHERE v.review_id = r.id) AS helpful_count,
+        EXISTS(SELECT 1 FROM review_votes v WHERE v.review_id = r.id AND v.user_id = $1) AS has_voted,
+        r.created_at,
+        r.updated_at
+    FROM reviews r
+    JOIN users u ON r.user_id = u.id
+    JOIN spbu s ON r.spbu_id = s.id
+"#;
+
 // Helper functions for response
 fn success<T: Serialize>(data: T) -> Json<serde_json::Value> {
     Json(serde_json::json!({
@@ -82,7 +111,7 @@ pub async fn create_review(
     })?;
 
     // Dapatkan nama user dan SPBU untuk response
-    let response = get_review_with_details(&state, review.id).await?;
+    let response = get_review_with_details(&state, review.id, Some(user_id)).await?;
 
     Ok((StatusCode::CREATED, success(response)))
 }
@@ -90,9 +119,10 @@ pub async fn create_review(
 // Dapatkan detail review
 pub async fn get_review(
     State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
     Path(review_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    let review = get_review_with_details(&state, review_id).await?;
+    let review = get_review_with_details(&state, review_id, Some(user_id)).await?;
     Ok(success(review))
 }
 
@@ -142,7 +172,7 @@ pub async fn update_review(
     .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let review = review.ok_or_else(|| error(StatusCode::NOT_FOUND, "Review not found"))?;
-    let response = get_review_with_details(&state, review.id).await?;
+    let response = get_review_with_details(&state, review.id, Some(user_id)).await?;
 
     Ok(success(response))
 }
@@ -174,14 +204,28 @@ pub async fn delete_review(
         return Err(error(StatusCode::NOT_FOUND, "Review not found"));
     }
 
+    // Foto review ikut dihapus dari storage
+    delete_owner_media(&state, "review", review_id).await?;
+
     Ok((StatusCode::NO_CONTENT, ()))
 }
 
-// Dapatkan semua review untuk SPBU tertentu
+// Dapatkan review untuk SPBU tertentu
+// GET /spbu/:spbu_id/reviews?sort=newest|highest|lowest|most_helpful&rating=5&limit=20&offset=0
+// Tanpa login; jika token dikirim, has_voted diisi untuk user tersebut
 pub async fn get_spbu_reviews(
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
     Path(spbu_id): Path<Uuid>,
+    Query(query): Query<ReviewQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(rating) = query.rating {
+        if !(1..=5).contains(&rating) {
+            return Err(error(StatusCode::BAD_REQUEST, "rating filter must be between 1 and 5"));
+        }
+    }
+    let viewer = crate::middleware::auth::get_user_id_from_headers(&headers).ok();
+
     // Cek apakah SPBU ada
     let spbu_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM spbu WHERE id = $1)")
         .bind(spbu_id)
@@ -193,33 +237,170 @@ pub async fn get_spbu_reviews(
         return Err(error(StatusCode::NOT_FOUND, "SPBU not found"));
     }
 
-    // Dapatkan semua review untuk SPBU ini
-    let reviews = sqlx::query_as!(
-        ReviewResponse,
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM reviews r WHERE r.spbu_id = $1 AND ($2::int IS NULL OR FLOOR(r.rating)::int = $2)",
+    )
+    .bind(spbu_id)
+    .bind(query.rating)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let order_by = match query.sort {
+        ReviewSort::Newest => "r.created_at DESC",
+        ReviewSort::Highest => "r.rating DESC, r.created_at DESC",
+        ReviewSort::Lowest => "r.rating ASC, r.created_at DESC",
+        ReviewSort::MostHelpful => "helpful_count DESC, r.created_at DESC",
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let mut reviews = sqlx::query_as::<_, ReviewResponse>(&format!(
         r#"
-        SELECT 
-            r.id,
-            r.user_id,
-            u.nama_lengkap as user_name,  -- Menggunakan nama_lengkap dari tabel users
-            r.spbu_id,
-            s.nama as spbu_name,
-            r.rating,
-            r.comment,
-            r.created_at,
-            r.updated_at
-        FROM reviews r
-        JOIN users u ON r.user_id = u.id
-        JOIN spbu s ON r.spbu_id = s.id
-        WHERE r.spbu_id = $1
-        ORDER BY r.created_at DESC
+        {}
+        WHERE r.spbu_id = $2 AND ($3::int IS NULL OR FLOOR(r.rating)::int = $3)
+        ORDER BY {}, r.id
+        LIMIT $4 OFFSET $5
+        "#,
+        REVIEW_SELECT, order_by
+    ))
+    .bind(viewer)
+    .bind(spbu_id)
+    .bind(query.rating)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    attach_details(&state, &mut reviews).await?;
+
+    Ok(success(ReviewList {
+        total,
+        limit,
+        offset,
+        reviews,
+    }))
+}
+
+// Vote "membantu" untuk review orang lain; vote ulang tidak dihitung dua kali
+// POST /reviews/:review_id/helpful
+pub async fn vote_helpful(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(review_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let author: Option<Uuid> = sqlx::query_scalar("SELECT user_id FROM reviews WHERE id = $1")
+        .bind(review_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    match author {
+        None => return Err(error(StatusCode::NOT_FOUND, "Review not found")),
+        Some(author) if author == user_id => {
+            return Err(error(StatusCode::FORBIDDEN, "You cannot vote on your own review"));
+        }
+        Some(_) => {}
+    }
+
+    sqlx::query("INSERT INTO review_votes (review_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(review_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(success(helpful_votes(&state, review_id, user_id).await?))
+}
+
+// DELETE /reviews/:review_id/helpful
+pub async fn remove_helpful_vote(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(review_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let review_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM reviews WHERE id = $1)")
+        .bind(review_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !review_exists {
+        return Err(error(StatusCode::NOT_FOUND, "Review not found"));
+    }
+
+    sqlx::query("DELETE FROM review_votes WHERE review_id = $1 AND user_id = $2")
+        .bind(review_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(success(helpful_votes(&state, review_id, user_id).await?))
+}
+
+async fn helpful_votes(
+    state: &AppState,
+    review_id: Uuid,
+    user_id: Uuid,
+) -> Result<HelpfulVoteResponse, (StatusCode, Json<serde_json::Value>)> {
+    let (helpful_count, has_voted): (i64, bool) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*),
+            COALESCE(BOOL_OR(user_id = $2), FALSE)
+        FROM review_votes
+        WHERE review_id = $1
+        "#,
+    )
+    .bind(review_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(HelpfulVoteResponse {
+        review_id,
+        helpful_count,
+        has_voted,
+    })
+}
+
+// Isi foto dan balasan operator untuk setiap review
+async fn attach_details(
+    state: &AppState,
+    reviews: &mut [ReviewResponse],
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let review_ids: Vec<Uuid> = reviews.iter().map(|r| r.id).collect();
+    let photos = sqlx::query_as::<_, MediaObject>(
+        r#"
+        SELECT * FROM media_objects
+        WHERE owner_type = 'review' AND owner_id = ANY($1) AND kind = 'photo'
+        ORDER BY sort_order, created_at
         "#,
-        spbu_id
     )
+    .bind(&review_ids)
     .fetch_all(&state.db)
     .await
     .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    Ok(success(reviews))
+    let mut by_review: HashMap<Uuid, Vec<MediaResponse>> = HashMap::new();
+    for photo in photos {
+        by_review.entry(photo.owner_id).or_default().push(to_response(state, photo));
+    }
+    let mut replies: HashMap<Uuid, ReviewReply> = load_replies(&state.db, &review_ids)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|reply| (reply.review_id, reply))
+        .collect();
+
+    for review in reviews {
+        review.photos = by_review.remove(&review.id).unwrap_or_default();
+        review.reply = replies.remove(&review.id);
+    }
+    Ok(())
 }
 
 // Dapatkan rating summary untuk SPBU
@@ -290,30 +471,16 @@ pub async fn get_spbu_rating(
 async fn get_review_with_details(
     state: &AppState,
     review_id: Uuid,
+    viewer: Option<Uuid>,
 ) -> Result<ReviewResponse, (StatusCode, Json<serde_json::Value>)> {
-    let review = sqlx::query_as!(
-        ReviewResponse,
-        r#"
-        SELECT 
-            r.id,
-            r.user_id,
-            u.nama_lengkap as user_name,  -- Menggunakan nama_lengkap dari tabel users
-            r.spbu_id,
-            s.nama as spbu_name,
-            r.rating,
-            r.comment,
-            r.created_at,
-            r.updated_at
-        FROM reviews r
-        JOIN users u ON r.user_id = u.id
-        JOIN spbu s ON r.spbu_id = s.id
-        WHERE r.id = $1
-        "#,
-        review_id
-    )
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let review = sqlx::query_as::<_, ReviewResponse>(&format!("{} WHERE r.id = $2", REVIEW_SELECT))
+        .bind(viewer)
+        .bind(review_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    review.ok_or_else(|| error(StatusCode::NOT_FOUND, "Review not found"))
+    let mut review = review.ok_or_else(|| error(StatusCode::NOT_FOUND, "Review not found"))?;
+    attach_details(state, std::slice::from_mut(&mut review)).await?;
+    Ok(review)
 }
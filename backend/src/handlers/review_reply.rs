@@ -0,0 +1,247 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde_json::json;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::operator::require_staff;
+use crate::models::notification::NotificationEvent;
+use crate::models::review::{ReviewReply, ReviewReplyEdit, ReviewReplyRequest, SpbuReviewStats};
+use crate::notifications::notify;
+use crate::AppState;
+
+const MAX_REPLY_LENGTH: usize = 2000;
+
+// Panjang cuplikan balasan di notifikasi
+const EXCERPT_LENGTH: usize = 100;
+
+// Dipakai dengan FROM review_replies rr
+const REPLY_COLUMNS: &str = r#"
+    rr.id, rr.review_id, rr.body,
+    EXISTS(SELECT 1 FROM review_reply_edits e WHERE e.reply_id = rr.id) AS edited,
+    rr.created_at, rr.updated_at
+"#;
+
+/// Balasan operator untuk sekumpulan review
+pub async fn load_replies<'e, E: PgExecutor<'e>>(executor: E, review_ids: &[Uuid]) -> Result<Vec<ReviewReply>> {
+    let replies = sqlx::query_as::<_, ReviewReply>(&format!(
+        "SELECT {} FROM review_replies rr WHERE rr.review_id = ANY($1)",
+        REPLY_COLUMNS
+    ))
+    .bind(review_ids)
+    .fetch_all(executor)
+    .await?;
+    Ok(replies)
+}
+
+fn validate_body(body: &str) -> Result<String> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(AppError::BadRequest("body is required".to_string()));
+    }
+    if body.chars().count() > MAX_REPLY_LENGTH {
+        return Err(AppError::BadRequest(format!(
+            "body must be at most {} characters",
+            MAX_REPLY_LENGTH
+        )));
+    }
+    Ok(body.to_string())
+}
+
+fn excerpt(body: &str) -> String {
+    if body.chars().count() <= EXCERPT_LENGTH {
+        return body.to_string();
+    }
+    let cut: String = body.chars().take(EXCERPT_LENGTH).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[derive(sqlx::FromRow)]
+struct ReviewTarget {
+    user_id: Uuid,
+    spbu_id: Uuid,
+    spbu_name: String,
+}
+
+// Review yang dibalas beserta SPBU-nya; hanya staf SPBU tersebut yang boleh membalas
+async fn review_for_staff(
+    conn: &mut sqlx::PgConnection,
+    review_id: Uuid,
+    user_id: Uuid,
+) -> Result<ReviewTarget> {
+    let review = sqlx::query_as::<_, ReviewTarget>(
+        r#"
+        SELECT r.user_id, r.spbu_id, s.nama AS spbu_name
+        FROM reviews r
+        JOIN spbu s ON s.id = r.spbu_id
+        WHERE r.id = $1
+        "#,
+    )
+    .bind(review_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+    require_staff(&mut *conn, review.spbu_id, user_id).await?;
+    Ok(review)
+}
+
+async fn fetch_reply<'e, E: PgExecutor<'e>>(executor: E, review_id: Uuid) -> Result<ReviewReply> {
+    sqlx::query_as::<_, ReviewReply>(&format!(
+        "SELECT {} FROM review_replies rr WHERE rr.review_id = $1",
+        REPLY_COLUMNS
+    ))
+    .bind(review_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Reply not found".to_string()))
+}
+
+// POST /operator/reviews/:review_id/reply
+// Penulis review mendapat notifikasi
+pub async fn create_reply(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(review_id): Path<Uuid>,
+    Json(payload): Json<ReviewReplyRequest>,
+) -> Result<(StatusCode, Json<ReviewReply>)> {
+    let body = validate_body(&payload.body)?;
+
+    let mut tx = state.db.begin().await?;
+    let review = review_for_staff(&mut tx, review_id, user_id).await?;
+
+    sqlx::query("INSERT INTO review_replies (review_id, author_id, body) VALUES ($1, $2, $3)")
+        .bind(review_id)
+        .bind(user_id)
+        .bind(&body)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+                AppError::Conflict("This review already has a reply".to_string())
+            }
+            e => e.into(),
+        })?;
+
+    if review.user_id != user_id {
+        let data = json!({
+            "review_id": review_id,
+            "spbu_id": review.spbu_id,
+            "spbu_name": review.spbu_name,
+            "excerpt": excerpt(&body),
+        });
+        notify(&mut tx, review.user_id, NotificationEvent::ReviewReplied, data).await?;
+    }
+
+    let reply = fetch_reply(&mut *tx, review_id).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(reply)))
+}
+
+// PUT /operator/reviews/:review_id/reply
+// Isi lama disimpan ke riwayat; reviewer tidak dinotifikasi ulang
+pub async fn update_reply(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(review_id): Path<Uuid>,
+    Json(payload): Json<ReviewReplyRequest>,
+) -> Result<Json<ReviewReply>> {
+    let body = validate_body(&payload.body)?;
+
+    let mut tx = state.db.begin().await?;
+    review_for_staff(&mut tx, review_id, user_id).await?;
+
+    let (reply_id, current): (Uuid, String) =
+        sqlx::query_as("SELECT id, body FROM review_replies WHERE review_id = $1 FOR UPDATE")
+            .bind(review_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Reply not found".to_string()))?;
+
+    if current != body {
+        sqlx::query("INSERT INTO review_reply_edits (reply_id, body, edited_by) VALUES ($1, $2, $3)")
+            .bind(reply_id)
+            .bind(&current)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE review_replies SET body = $2 WHERE id = $1")
+            .bind(reply_id)
+            .bind(&body)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let reply = fetch_reply(&mut *tx, review_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(reply))
+}
+
+// GET /reviews/:review_id/reply/history
+// Isi balasan sebelum setiap perubahan, terlama di atas
+pub async fn get_reply_history(
+    State(state): State<AppState>,
+    Path(review_id): Path<Uuid>,
+) -> Result<Json<Vec<ReviewReplyEdit>>> {
+    let reply = fetch_reply(&state.db, review_id).await?;
+
+    let edits = sqlx::query_as::<_, ReviewReplyEdit>(
+        "SELECT body, edited_at FROM review_reply_edits WHERE reply_id = $1 ORDER BY edited_at",
+    )
+    .bind(reply.id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(edits))
+}
+
+// GET /spbu/:spbu_id/review-stats
+pub async fn get_spbu_review_stats(
+    State(state): State<AppState>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<Json<SpbuReviewStats>> {
+    let spbu_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM spbu WHERE id = $1)")
+        .bind(spbu_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    if !spbu_exists {
+        return Err(AppError::NotFound("SPBU not found".to_string()));
+    }
+
+    let (total_reviews, replied_reviews, average_response_hours): (i64, i64, Option<f64>) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(r.id),
+            COUNT(rr.id),
+            (AVG(EXTRACT(EPOCH FROM (rr.created_at - r.created_at))) / 3600)::float8
+        FROM reviews r
+        LEFT JOIN review_replies rr ON rr.review_id = r.id
+        WHERE r.spbu_id = $1
+        "#,
+    )
+    .bind(spbu_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let response_rate = if total_reviews > 0 {
+        replied_reviews as f64 / total_reviews as f64
+    } else {
+        0.0
+    };
+
+    Ok(Json(SpbuReviewStats {
+        spbu_id,
+        total_reviews,
+        replied_reviews,
+        response_rate,
+        average_response_hours,
+    }))
+}
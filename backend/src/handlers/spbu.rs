@@ -1,8 +1,110 @@
-use axum::{extract::State, Json};
-use crate::models::spbu::Spbu;
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use crate::auth::require_platform_admin;
+use crate::geocoding::{self, route::{self, Route}, Coordinates, GeocodeError};
+use crate::handlers::operator::{require_owner, require_station_manager};
+use crate::handlers::pump::create_default_pumps;
+use crate::jobs::{queue, EvaluateWishlistAlerts};
+use crate::models::fuel_price::FuelPrice;
+use crate::models::spbu::{AlongRouteRequest, AlongRouteResponse, Spbu, SpbuAlongRoute};
+use crate::types::Decimal;
 use crate::AppState;
+use std::collections::HashMap;
+use std::str::FromStr;
 use uuid::Uuid;
 
+// Batas input pencarian di sepanjang rute
+const DEFAULT_MAX_DETOUR_KM: f64 = 2.0;
+const MAX_DETOUR_LIMIT_KM: f64 = 25.0;
+const MAX_ROUTE_POINTS: usize = 10_000;
+
+// Alamat dan koordinat dianggap tidak cocok jika terpaut lebih dari ini
+const MAX_ADDRESS_MISMATCH_KM: f64 = 30.0;
+
+fn geocoder_unavailable(e: GeocodeError) -> (StatusCode, String) {
+    tracing::warn!("Geocoder error: {}", e);
+    (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+}
+
+// Lengkapi koordinat dari alamat (atau sebaliknya) dan tandai lokasi yang mencurigakan
+async fn resolve_location(state: &AppState, payload: &mut Spbu) -> Result<(), (StatusCode, String)> {
+    let address = payload
+        .alamat
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string);
+
+    let coordinates = match (payload.latitude, payload.longitude) {
+        (Some(latitude), Some(longitude)) => Some(Coordinates { latitude, longitude }),
+        (None, None) => None,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "latitude and longitude must be provided together".to_string(),
+            ))
+        }
+    };
+
+    payload.location_flag = None;
+
+    let coordinates = match (coordinates, address) {
+        (None, None) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "alamat or latitude/longitude is required".to_string(),
+            ))
+        }
+        (None, Some(address)) => {
+            let found = state
+                .geocoder
+                .geocode(&address)
+                .await
+                .map_err(geocoder_unavailable)?
+                .ok_or_else(|| {
+                    (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "Could not determine coordinates from alamat".to_string(),
+                    )
+                })?;
+            payload.latitude = Some(found.latitude);
+            payload.longitude = Some(found.longitude);
+            found
+        }
+        (Some(coordinates), None) => {
+            let found = state
+                .geocoder
+                .reverse_geocode(coordinates)
+                .await
+                .map_err(geocoder_unavailable)?
+                .ok_or_else(|| {
+                    (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "Could not determine alamat from coordinates".to_string(),
+                    )
+                })?;
+            payload.alamat = Some(found);
+            coordinates
+        }
+        (Some(coordinates), Some(address)) => {
+            // Cek konsistensi; jika geocoder tidak tersedia, lewati saja
+            match state.geocoder.geocode(&address).await {
+                Ok(Some(found)) if geocoding::haversine_km(found, coordinates) > MAX_ADDRESS_MISMATCH_KM => {
+                    payload.location_flag = Some("address_mismatch".to_string());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Skipping address consistency check: {}", e),
+            }
+            coordinates
+        }
+    };
+
+    if !geocoding::is_within_indonesia(coordinates) {
+        payload.location_flag = Some("outside_indonesia".to_string());
+    }
+
+    Ok(())
+}
+
 // GET all SPBU
 pub async fn get_all_spbu(
     State(state): State<AppState>,
@@ -28,12 +130,21 @@ pub async fn get_spbu_by_id(
 }
 
 // POST SPBU
+// Pembuat SPBU otomatis menjadi owner
 pub async fn create_spbu(
     State(state): State<AppState>,
-    Json(payload): Json<Spbu>,
+    Extension(user_id): Extension<Uuid>,
+    Json(mut payload): Json<Spbu>,
 ) -> Result<Json<Spbu>, (axum::http::StatusCode, String)> {
+    // Hanya admin yang mendaftarkan SPBU; owner ditetapkan terpisah lewat POST /admin/spbu/:id/owner
+    require_platform_admin(&state.db, user_id).await?;
+    resolve_location(&state, &mut payload).await?;
+
+    let mut tx = state.db.begin().await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
     let rec = sqlx::query_as::<_, Spbu>(
-        "INSERT INTO spbu (id, nama, alamat, latitude, longitude, brand_id, rating, jumlah_pompa, jumlah_antrian, foto, created_at, updated_at) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12) RETURNING *"
+        "INSERT INTO spbu (id, nama, alamat, latitude, longitude, brand_id, rating, jumlah_pompa, jumlah_antrian, foto, created_at, updated_at, location_flag) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13) RETURNING *"
     )
     .bind(payload.id)
     .bind(payload.nama)
@@ -47,20 +158,40 @@ pub async fn create_spbu(
     .bind(payload.foto)
     .bind(payload.created_at)
     .bind(payload.updated_at)
-    .fetch_one(&state.db)
+    .bind(payload.location_flag)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // jumlah_pompa diisi ulang oleh trigger dari tabel pumps
+    let rec = match rec.jumlah_pompa {
+        Some(count) if count > 0 => {
+            create_default_pumps(&mut tx, rec.id, count).await?;
+            sqlx::query_as::<_, Spbu>("SELECT * FROM spbu WHERE id = $1")
+                .bind(rec.id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        _ => rec,
+    };
+    tx.commit().await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     Ok(Json(rec))
 }
 
 // PUT SPBU
 pub async fn update_spbu(
     State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
     axum::extract::Path(id): axum::extract::Path<Uuid>,
-    Json(payload): Json<Spbu>,
+    Json(mut payload): Json<Spbu>,
 ) -> Result<Json<Spbu>, (axum::http::StatusCode, String)> {
+    require_station_manager(&state.db, id, user_id).await?;
+    resolve_location(&state, &mut payload).await?;
+
     let rec = sqlx::query_as::<_, Spbu>(
-        "UPDATE spbu SET nama = $1, alamat = $2, latitude = $3, longitude = $4, brand_id = $5, rating = $6, jumlah_pompa = $7, jumlah_antrian = $8, foto = $9, updated_at = $10 WHERE id = $11 RETURNING *"
+        "UPDATE spbu SET nama = $1, alamat = $2, latitude = $3, longitude = $4, brand_id = $5, rating = $6, jumlah_antrian = $7, foto = $8, updated_at = $9, location_flag = $10 WHERE id = $11 RETURNING *"
     )
     .bind(&payload.nama)
     .bind(&payload.alamat)
@@ -68,22 +199,27 @@ pub async fn update_spbu(
     .bind(&payload.longitude)
     .bind(&payload.brand_id)
     .bind(&payload.rating)
-    .bind(&payload.jumlah_pompa)
     .bind(&payload.jumlah_antrian)
     .bind(&payload.foto)
     .bind(&payload.updated_at)
+    .bind(&payload.location_flag)
     .bind(id)
     .fetch_one(&state.db)
     .await
     .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    // jumlah_antrian bisa berubah
+    queue::enqueue(&state.db, &EvaluateWishlistAlerts { spbu_id: id }).await?;
     Ok(Json(rec))
 }
 
 // DELETE SPBU
 pub async fn delete_spbu(
     State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
     axum::extract::Path(id): axum::extract::Path<Uuid>,
 ) -> Result<Json<Spbu>, (axum::http::StatusCode, String)> {
+    require_owner(&state.db, id, user_id).await?;
+
     let rec = sqlx::query_as::<_, Spbu>(
         "DELETE FROM spbu WHERE id = $1 RETURNING *"
     )
@@ -93,3 +229,129 @@ pub async fn delete_spbu(
     .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     Ok(Json(rec))
 }
+
+// Ambil harga BBM untuk sekumpulan SPBU, dikelompokkan per SPBU
+pub async fn fetch_fuel_prices(
+    state: &AppState,
+    spbu_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<FuelPrice>>, sqlx::Error> {
+    let rows: Vec<(Uuid, String, String)> = sqlx::query_as(
+        r#"
+        SELECT spbu_id, fuel_type, price::text
+        FROM fuel_prices
+        WHERE spbu_id = ANY($1)
+        ORDER BY fuel_type
+        "#
+    )
+    .bind(spbu_ids)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut prices: HashMap<Uuid, Vec<FuelPrice>> = HashMap::new();
+    for (spbu_id, fuel_type, price) in rows {
+        prices.entry(spbu_id).or_default().push(FuelPrice {
+            spbu_id,
+            fuel_type,
+            price: Decimal::from_str(&price).unwrap_or_default(),
+        });
+    }
+    Ok(prices)
+}
+
+// POST /spbu/along-route
+// Cari SPBU dalam koridor rute, diurutkan dari yang paling dekat titik awal
+pub async fn find_spbu_along_route(
+    State(state): State<AppState>,
+    Json(payload): Json<AlongRouteRequest>,
+) -> Result<Json<AlongRouteResponse>, (StatusCode, String)> {
+    let points = match (&payload.polyline, payload.waypoints) {
+        (Some(polyline), None) => route::decode_polyline(polyline)
+            .ok_or_else(|| (StatusCode::BAD_REQUEST, "Invalid encoded polyline".to_string()))?,
+        (None, Some(waypoints)) => waypoints,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Provide either polyline or waypoints".to_string(),
+            ))
+        }
+    };
+    if points.is_empty() || points.len() > MAX_ROUTE_POINTS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Route must have between 1 and {} points", MAX_ROUTE_POINTS),
+        ));
+    }
+
+    let max_detour_km = payload.max_detour_km.unwrap_or(DEFAULT_MAX_DETOUR_KM);
+    if !(max_detour_km > 0.0 && max_detour_km <= MAX_DETOUR_LIMIT_KM) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("max_detour_km must be between 0 and {}", MAX_DETOUR_LIMIT_KM),
+        ));
+    }
+
+    let route = Route::new(points);
+    let (min, max) = route.bounding_box(max_detour_km);
+
+    // Saring kasar dengan bounding box di database, lalu hitung jarak ke rute di sini
+    let candidates = sqlx::query_as::<_, Spbu>(
+        r#"
+        SELECT s.* FROM spbu s
+        WHERE s.latitude BETWEEN $1 AND $2
+          AND s.longitude BETWEEN $3 AND $4
+          AND ($5::uuid IS NULL OR s.brand_id = $5)
+          AND ($6::text IS NULL OR EXISTS (
+              SELECT 1 FROM fuel_prices fp
+              WHERE fp.spbu_id = s.id AND UPPER(fp.fuel_type) = UPPER($6)
+          ))
+        "#
+    )
+    .bind(min.latitude)
+    .bind(max.latitude)
+    .bind(min.longitude)
+    .bind(max.longitude)
+    .bind(payload.brand_id)
+    .bind(&payload.fuel_type)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut matches: Vec<(Spbu, route::RoutePosition)> = candidates
+        .into_iter()
+        .filter_map(|spbu| {
+            let point = Coordinates {
+                latitude: spbu.latitude?,
+                longitude: spbu.longitude?,
+            };
+            let position = route.locate(point)?;
+            (position.distance_from_route_km <= max_detour_km).then_some((spbu, position))
+        })
+        .collect();
+    matches.sort_by(|a, b| a.1.distance_along_km.total_cmp(&b.1.distance_along_km));
+
+    let spbu_ids: Vec<Uuid> = matches.iter().map(|(spbu, _)| spbu.id).collect();
+    let mut prices = fetch_fuel_prices(&state, &spbu_ids)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let spbus = matches
+        .into_iter()
+        .map(|(spbu, position)| {
+            let mut fuel_prices = prices.remove(&spbu.id).unwrap_or_default();
+            if let Some(fuel_type) = &payload.fuel_type {
+                fuel_prices.retain(|p| p.fuel_type.eq_ignore_ascii_case(fuel_type));
+            }
+            SpbuAlongRoute {
+                spbu,
+                distance_along_route_km: position.distance_along_km,
+                distance_from_route_km: position.distance_from_route_km,
+                fuel_prices,
+            }
+        })
+        .collect();
+
+    Ok(Json(AlongRouteResponse {
+        route_length_km: route.length_km(),
+        spbus,
+    }))
+}
@@ -2,12 +2,14 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::AppState;
+use crate::error::AppError;
+use crate::handlers::operator::require_station_manager;
 use crate::models::service::Service;
 use crate::models::spbu::Spbu;
 
@@ -32,22 +34,22 @@ fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<se
     (status, Json(error_response))
 }
 
+fn app_error(err: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let (status, message): (StatusCode, String) = err.into();
+    error(status, message)
+}
+
 // Menambahkan service ke SPBU
 pub async fn add_service_to_spbu(
     State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
     Path(spbu_id): Path<Uuid>,
     Json(payload): Json<AddServiceToSpbuRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    // Cek apakah SPBU ada
-    let spbu: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM spbu WHERE id = $1")
-        .bind(spbu_id)
-        .fetch_optional(&state.db)
+    // Cek apakah SPBU ada dan user adalah owner/manager-nya
+    require_station_manager(&state.db, spbu_id, user_id)
         .await
-        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    if spbu.is_none() {
-        return Err(error(StatusCode::NOT_FOUND, "SPBU not found"));
-    }
+        .map_err(app_error)?;
 
     // Cek apakah service ada
     let service: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM services WHERE id = $1")
@@ -85,8 +87,13 @@ pub async fn add_service_to_spbu(
 // Menghapus service dari SPBU
 pub async fn remove_service_from_spbu(
     State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
     Path((spbu_id, service_id)): Path<(Uuid, Uuid)>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    require_station_manager(&state.db, spbu_id, user_id)
+        .await
+        .map_err(app_error)?;
+
     let exists: Option<(i64,)> = sqlx::query_as(
         "SELECT 1 FROM spbu_services WHERE spbu_id = $1 AND service_id = $2"
     )
@@ -0,0 +1,298 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Days, FixedOffset, Utc};
+use sqlx::PgConnection;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::operator::require_station_manager;
+use crate::handlers::organization::{BILLING_TIMEZONE, BILLING_UTC_OFFSET_SECS};
+use crate::models::spbu_stats::{
+    PaymentMethodShare, ReviewBucket, ReviewStats, SalesBucket, SpbuStats, SpbuStatsQuery, StatsGranularity,
+    StatsSummary, WishlistStats,
+};
+use crate::types::Decimal;
+use crate::AppState;
+
+const DEFAULT_RANGE_DAYS: u64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+const MAX_HOURLY_RANGE_DAYS: i64 = 31;
+
+const SOURCE_TRANSACTIONS: &str = "transactions";
+const SOURCE_REVIEWS: &str = "reviews";
+
+/// Hitung ulang bucket rollup yang ditandai kotor oleh trigger untuk satu SPBU.
+/// Harus dipanggil di dalam transaksi database.
+pub async fn refresh_spbu_stats(conn: &mut PgConnection, spbu_id: Uuid) -> Result<()> {
+    // Satu refresh per SPBU dalam satu waktu
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('spbu_stats:' || $1::text))")
+        .bind(spbu_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let dirty: Vec<(String, DateTime<Utc>)> =
+        sqlx::query_as("DELETE FROM spbu_stats_dirty WHERE spbu_id = $1 RETURNING source, bucket_start")
+            .bind(spbu_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let mut transaction_buckets = Vec::new();
+    let mut review_buckets = Vec::new();
+    for (source, bucket_start) in dirty {
+        match source.as_str() {
+            SOURCE_TRANSACTIONS => transaction_buckets.push(bucket_start),
+            SOURCE_REVIEWS => review_buckets.push(bucket_start),
+            _ => {}
+        }
+    }
+
+    if !transaction_buckets.is_empty() {
+        sqlx::query("DELETE FROM spbu_hourly_sales WHERE spbu_id = $1 AND bucket_start = ANY($2)")
+            .bind(spbu_id)
+            .bind(&transaction_buckets)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO spbu_hourly_sales (
+                spbu_id, bucket_start, fuel_type, payment_method,
+                transaction_count, completed_count, cancelled_count, liters_sold, revenue
+            )
+            SELECT
+                t.spbu_id, b.bucket_start, t.fuel_type, t.payment_method,
+                COUNT(*),
+                COUNT(*) FILTER (WHERE t.status = 'completed'),
+                COUNT(*) FILTER (WHERE t.status = 'cancelled'),
+                COALESCE(SUM(COALESCE(t.dispensed_quantity, t.quantity)) FILTER (WHERE t.status = 'completed'), 0),
+                COALESCE(SUM(COALESCE(t.final_price, t.total_price)) FILTER (WHERE t.status = 'completed'), 0)
+            FROM unnest($2::timestamptz[]) AS b(bucket_start)
+            JOIN transactions t
+              ON t.spbu_id = $1
+             AND t.created_at >= b.bucket_start
+             AND t.created_at < b.bucket_start + INTERVAL '1 hour'
+            GROUP BY t.spbu_id, b.bucket_start, t.fuel_type, t.payment_method
+            "#,
+        )
+        .bind(spbu_id)
+        .bind(&transaction_buckets)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    if !review_buckets.is_empty() {
+        sqlx::query("DELETE FROM spbu_hourly_reviews WHERE spbu_id = $1 AND bucket_start = ANY($2)")
+            .bind(spbu_id)
+            .bind(&review_buckets)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO spbu_hourly_reviews (spbu_id, bucket_start, review_count, rating_sum)
+            SELECT r.spbu_id, b.bucket_start, COUNT(*), SUM(r.rating)
+            FROM unnest($2::timestamptz[]) AS b(bucket_start)
+            JOIN reviews r
+              ON r.spbu_id = $1
+             AND r.created_at >= b.bucket_start
+             AND r.created_at < b.bucket_start + INTERVAL '1 hour'
+            GROUP BY r.spbu_id, b.bucket_start
+            "#,
+        )
+        .bind(spbu_id)
+        .bind(&review_buckets)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// GET /operator/spbu/:id/stats
+pub async fn get_spbu_stats(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Query(query): Query<SpbuStatsQuery>,
+) -> Result<Json<SpbuStats>> {
+    require_station_manager(&state.db, spbu_id, user_id).await?;
+
+    let granularity = query.granularity.unwrap_or_default();
+    let offset = FixedOffset::east_opt(BILLING_UTC_OFFSET_SECS).expect("valid UTC offset");
+    let today = Utc::now().with_timezone(&offset).date_naive();
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Days::new(DEFAULT_RANGE_DAYS - 1));
+
+    if from > to {
+        return Err(AppError::BadRequest("from must not be after to".to_string()));
+    }
+    let range_days = (to - from).num_days() + 1;
+    let max_days = if granularity == StatsGranularity::Hour {
+        MAX_HOURLY_RANGE_DAYS
+    } else {
+        MAX_RANGE_DAYS
+    };
+    if range_days > max_days {
+        return Err(AppError::BadRequest(format!(
+            "Range is limited to {} days for {} granularity",
+            max_days,
+            granularity.as_str()
+        )));
+    }
+
+    let mut tx = state.db.begin().await?;
+    refresh_spbu_stats(&mut tx, spbu_id).await?;
+    tx.commit().await?;
+    let refreshed_at = Utc::now();
+
+    // $2/$3 = tanggal WIB inklusif, $4 = zona waktu
+    let summary = sqlx::query_as::<_, StatsSummary>(
+        r#"
+        SELECT
+            COALESCE(SUM(transaction_count), 0)::bigint AS transaction_count,
+            COALESCE(SUM(completed_count), 0)::bigint AS completed_count,
+            COALESCE(SUM(cancelled_count), 0)::bigint AS cancelled_count,
+            COALESCE(SUM(liters_sold), 0)::text AS liters_sold,
+            COALESCE(SUM(revenue), 0)::text AS revenue,
+            CASE WHEN SUM(completed_count) > 0
+                 THEN ROUND(SUM(revenue) / SUM(completed_count), 2)::text
+            END AS average_ticket
+        FROM spbu_hourly_sales
+        WHERE spbu_id = $1
+          AND bucket_start >= $2::date::timestamp AT TIME ZONE $4
+          AND bucket_start < ($3::date + 1)::timestamp AT TIME ZONE $4
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(from)
+    .bind(to)
+    .bind(BILLING_TIMEZONE)
+    .fetch_one(&state.db)
+    .await?;
+
+    let sales = sqlx::query_as::<_, SalesBucket>(
+        r#"
+        SELECT
+            date_trunc($5, bucket_start AT TIME ZONE $4) AS bucket_start,
+            fuel_type,
+            SUM(completed_count)::bigint AS transaction_count,
+            SUM(liters_sold)::text AS liters_sold,
+            SUM(revenue)::text AS revenue
+        FROM spbu_hourly_sales
+        WHERE spbu_id = $1
+          AND bucket_start >= $2::date::timestamp AT TIME ZONE $4
+          AND bucket_start < ($3::date + 1)::timestamp AT TIME ZONE $4
+        GROUP BY 1, 2
+        HAVING SUM(completed_count) > 0
+        ORDER BY 1, 2
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(from)
+    .bind(to)
+    .bind(BILLING_TIMEZONE)
+    .bind(granularity.as_str())
+    .fetch_all(&state.db)
+    .await?;
+
+    let payment_rows: Vec<(String, i64, String)> = sqlx::query_as(
+        r#"
+        SELECT payment_method, SUM(completed_count)::bigint, SUM(revenue)::text
+        FROM spbu_hourly_sales
+        WHERE spbu_id = $1
+          AND bucket_start >= $2::date::timestamp AT TIME ZONE $4
+          AND bucket_start < ($3::date + 1)::timestamp AT TIME ZONE $4
+        GROUP BY payment_method
+        HAVING SUM(completed_count) > 0
+        ORDER BY 2 DESC, 1
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(from)
+    .bind(to)
+    .bind(BILLING_TIMEZONE)
+    .fetch_all(&state.db)
+    .await?;
+
+    let payment_methods = payment_rows
+        .into_iter()
+        .map(|(payment_method, transaction_count, revenue)| {
+            Ok(PaymentMethodShare {
+                payment_method,
+                transaction_count,
+                revenue: Decimal::from_str(&revenue).map_err(|e| AppError::InternalServerError(e.to_string()))?,
+                share: transaction_count as f64 / summary.completed_count as f64,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let trend = sqlx::query_as::<_, ReviewBucket>(
+        r#"
+        SELECT
+            date_trunc($5, bucket_start AT TIME ZONE $4) AS bucket_start,
+            SUM(review_count)::bigint AS review_count,
+            SUM(rating_sum) / SUM(review_count) AS average_rating
+        FROM spbu_hourly_reviews
+        WHERE spbu_id = $1
+          AND bucket_start >= $2::date::timestamp AT TIME ZONE $4
+          AND bucket_start < ($3::date + 1)::timestamp AT TIME ZONE $4
+        GROUP BY 1
+        HAVING SUM(review_count) > 0
+        ORDER BY 1
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(from)
+    .bind(to)
+    .bind(BILLING_TIMEZONE)
+    .bind(granularity.as_str())
+    .fetch_all(&state.db)
+    .await?;
+
+    let review_count: i64 = trend.iter().map(|b| b.review_count).sum();
+    let average_rating = (review_count > 0).then(|| {
+        trend.iter().map(|b| b.average_rating * b.review_count as f64).sum::<f64>() / review_count as f64
+    });
+
+    let (total, added): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*),
+            COUNT(*) FILTER (
+                WHERE created_at >= $2::date::timestamp AT TIME ZONE $4
+                  AND created_at < ($3::date + 1)::timestamp AT TIME ZONE $4
+            )
+        FROM wishlists
+        WHERE spbu_id = $1
+        "#,
+    )
+    .bind(spbu_id)
+    .bind(from)
+    .bind(to)
+    .bind(BILLING_TIMEZONE)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(SpbuStats {
+        spbu_id,
+        granularity,
+        from,
+        to,
+        timezone: BILLING_TIMEZONE,
+        summary,
+        sales,
+        payment_methods,
+        reviews: ReviewStats {
+            review_count,
+            average_rating,
+            trend,
+        },
+        wishlists: WishlistStats { total, added },
+        refreshed_at,
+    }))
+}
@@ -0,0 +1,431 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{FixedOffset, NaiveDate};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::organization::{
+    parse_period, period_has_ended, require_admin, BILLING_TIMEZONE, BILLING_UTC_OFFSET_SECS,
+};
+use crate::models::statement::{
+    split_tax, Statement, StatementDocument, StatementLine, StatementQuery, StatementSummary,
+};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Tarif PPN (persen); harga BBM sudah termasuk PPN
+const PPN_RATE: i32 = 11;
+
+const STATEMENT_COLUMNS: &str = r#"
+    id, statement_number, user_id, organization_id,
+    tax_rate::text AS tax_rate, issued_at
+"#;
+
+const STATEMENT_SUMMARY_COLUMNS: &str = r#"
+    id, statement_number, period_start, transaction_count,
+    total_amount::text AS total_amount, issued_at
+"#;
+
+const STATEMENT_LINE_COLUMNS: &str = r#"
+    line_number, transaction_id, transaction_date, user_id, spbu_id, spbu_name,
+    vehicle_id, plate_number, fuel_type,
+    quantity::text AS quantity,
+    price_per_liter::text AS price_per_liter,
+    discount::text AS discount,
+    amount::text AS amount,
+    subtotal::text AS subtotal,
+    tax_amount::text AS tax_amount
+"#;
+
+/// Pemilik statement: transaksi pribadi user atau seluruh transaksi organisasi
+#[derive(Clone, Copy)]
+enum StatementAccount {
+    User(Uuid),
+    Organization(Uuid),
+}
+
+impl StatementAccount {
+    fn column(&self) -> &'static str {
+        match self {
+            StatementAccount::User(_) => "user_id",
+            StatementAccount::Organization(_) => "organization_id",
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        match self {
+            StatementAccount::User(id) | StatementAccount::Organization(id) => *id,
+        }
+    }
+
+    // Transaksi atas tagihan organisasi masuk ke statement organisasi, bukan statement pribadi
+    fn transaction_filter(&self) -> &'static str {
+        match self {
+            StatementAccount::User(_) => "t.user_id = $1 AND t.organization_id IS NULL",
+            StatementAccount::Organization(_) => "t.organization_id = $1",
+        }
+    }
+
+    fn number_prefix(&self) -> &'static str {
+        match self {
+            StatementAccount::User(_) => "ST",
+            StatementAccount::Organization(_) => "STO",
+        }
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .map_err(|_| AppError::InternalServerError("Invalid decimal format in database".to_string()))
+}
+
+async fn account_name(state: &AppState, account: StatementAccount) -> Result<String> {
+    let sql = match account {
+        StatementAccount::User(_) => "SELECT COALESCE(nama_lengkap, email) FROM users WHERE id = $1",
+        StatementAccount::Organization(_) => "SELECT name FROM organizations WHERE id = $1",
+    };
+    let name: String = sqlx::query_scalar(sql)
+        .bind(account.id())
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Account not found".to_string()))?;
+    Ok(name)
+}
+
+/// Transaksi selesai dalam periode (tanggal WIB) sebagai baris statement
+async fn collect_lines(
+    state: &AppState,
+    account: StatementAccount,
+    (period_start, period_end): (NaiveDate, NaiveDate),
+    tax_rate: &Decimal,
+) -> Result<Vec<StatementLine>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT
+            t.id, t.created_at, t.user_id, t.spbu_id, COALESCE(s.nama, '-') AS spbu_name,
+            t.vehicle_id, t.plate_number, t.fuel_type,
+            COALESCE(t.dispensed_quantity, t.quantity)::text AS quantity,
+            t.price_per_liter::text AS price_per_liter,
+            (t.points_discount + t.promo_discount)::text AS discount,
+            COALESCE(t.final_price, t.total_price)::text AS amount
+        FROM transactions t
+        LEFT JOIN spbu s ON s.id = t.spbu_id
+        WHERE {}
+          AND t.status = 'completed'
+          AND t.created_at >= $2::date::timestamp AT TIME ZONE $4
+          AND t.created_at < $3::date::timestamp AT TIME ZONE $4
+        ORDER BY t.created_at, t.id
+        "#,
+        account.transaction_filter()
+    ))
+    .bind(account.id())
+    .bind(period_start)
+    .bind(period_end)
+    .bind(BILLING_TIMEZONE)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut lines = Vec::with_capacity(rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        let amount = parse_decimal(row.try_get("amount")?)?;
+        let (subtotal, tax_amount) = split_tax(&amount, tax_rate);
+        lines.push(StatementLine {
+            line_number: i as i32 + 1,
+            transaction_id: row.try_get("id")?,
+            transaction_date: row.try_get("created_at")?,
+            user_id: row.try_get("user_id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            spbu_name: row.try_get("spbu_name")?,
+            vehicle_id: row.try_get("vehicle_id")?,
+            plate_number: row.try_get("plate_number")?,
+            fuel_type: row.try_get("fuel_type")?,
+            quantity: parse_decimal(row.try_get("quantity")?)?,
+            price_per_liter: parse_decimal(row.try_get("price_per_liter")?)?,
+            discount: parse_decimal(row.try_get("discount")?)?,
+            amount,
+            subtotal,
+            tax_amount,
+        });
+    }
+    Ok(lines)
+}
+
+async fn find_issued<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    account: StatementAccount,
+    period_start: NaiveDate,
+) -> Result<Option<Statement>> {
+    let statement = sqlx::query_as::<_, Statement>(&format!(
+        "SELECT {} FROM statements WHERE {} = $1 AND period_start = $2",
+        STATEMENT_COLUMNS,
+        account.column()
+    ))
+    .bind(account.id())
+    .bind(period_start)
+    .fetch_optional(executor)
+    .await?;
+    Ok(statement)
+}
+
+async fn fetch_issued_lines(state: &AppState, statement_id: Uuid) -> Result<Vec<StatementLine>> {
+    let lines = sqlx::query_as::<_, StatementLine>(&format!(
+        "SELECT {} FROM statement_lines WHERE statement_id = $1 ORDER BY line_number",
+        STATEMENT_LINE_COLUMNS
+    ))
+    .bind(statement_id)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(lines)
+}
+
+/// Ambil statement periode: snapshot jika sudah terbit, selain itu preview yang dihitung dari transaksi.
+/// Tidak pernah menerbitkan statement; penerbitan lewat `issue_statement`.
+async fn load_statement(state: &AppState, account: StatementAccount, period: &str) -> Result<StatementDocument> {
+    let period = parse_period(period)?;
+    let name = account_name(state, account).await?;
+
+    if let Some(header) = find_issued(&state.db, account, period.0).await? {
+        let lines = fetch_issued_lines(state, header.id).await?;
+        let tax_rate = header.tax_rate.clone();
+        return Ok(StatementDocument::new(Some(&header), name, period, tax_rate, lines));
+    }
+
+    let tax_rate = Decimal::from(PPN_RATE);
+    let lines = collect_lines(state, account, period, &tax_rate).await?;
+    Ok(StatementDocument::new(None, name, period, tax_rate, lines))
+}
+
+/// Terbitkan statement untuk periode yang sudah selesai. Statement yang sudah terbit adalah
+/// snapshot dan tidak dihitung ulang; `false` berarti statement sudah terbit sebelumnya.
+async fn issue_statement(
+    state: &AppState,
+    account: StatementAccount,
+    period: &str,
+) -> Result<(bool, StatementDocument)> {
+    let period_label = period.trim().to_string();
+    let period = parse_period(period)?;
+    if !period_has_ended(period.1) {
+        return Err(AppError::BadRequest(
+            "Statements can only be issued after the period has ended".to_string(),
+        ));
+    }
+    let name = account_name(state, account).await?;
+
+    let mut tx = state.db.begin().await?;
+    // Request paralel untuk periode yang sama menunggu di sini; yang kedua memakai hasil yang pertama
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("statement:{}:{}", account.id(), period.0))
+        .execute(&mut *tx)
+        .await?;
+    if let Some(header) = find_issued(&mut *tx, account, period.0).await? {
+        tx.commit().await?;
+        let lines = fetch_issued_lines(state, header.id).await?;
+        let tax_rate = header.tax_rate.clone();
+        return Ok((false, StatementDocument::new(Some(&header), name, period, tax_rate, lines)));
+    }
+
+    let tax_rate = Decimal::from(PPN_RATE);
+    let preview = StatementDocument::new(
+        None,
+        name.clone(),
+        period,
+        tax_rate.clone(),
+        collect_lines(state, account, period, &tax_rate).await?,
+    );
+    if preview.lines.is_empty() {
+        return Err(AppError::BadRequest(format!("No transactions to include for {}", period_label)));
+    }
+
+    let sequence: i64 = sqlx::query_scalar("SELECT nextval('statement_number_seq')")
+        .fetch_one(&mut *tx)
+        .await?;
+    let statement_number = format!(
+        "{}/{}/{:06}",
+        account.number_prefix(),
+        period.0.format("%Y/%m"),
+        sequence
+    );
+
+    let header = sqlx::query_as::<_, Statement>(&format!(
+        r#"
+        INSERT INTO statements (
+            statement_number, user_id, organization_id, period_start, period_end,
+            transaction_count, subtotal, tax_rate, tax_amount, total_amount
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9::numeric, $10::numeric)
+        RETURNING {}
+        "#,
+        STATEMENT_COLUMNS
+    ))
+    .bind(&statement_number)
+    .bind(match account {
+        StatementAccount::User(id) => Some(id),
+        StatementAccount::Organization(_) => None,
+    })
+    .bind(match account {
+        StatementAccount::Organization(id) => Some(id),
+        StatementAccount::User(_) => None,
+    })
+    .bind(period.0)
+    .bind(period.1)
+    .bind(preview.transaction_count as i32)
+    .bind(preview.subtotal.to_string())
+    .bind(tax_rate.to_string())
+    .bind(preview.tax_amount.to_string())
+    .bind(preview.total_amount.to_string())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for line in &preview.lines {
+        sqlx::query(
+            r#"
+            INSERT INTO statement_lines (
+                statement_id, line_number, transaction_id, transaction_date, user_id, spbu_id, spbu_name,
+                vehicle_id, plate_number, fuel_type, quantity, price_per_liter, discount, amount,
+                subtotal, tax_amount
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11::numeric, $12::numeric, $13::numeric,
+                    $14::numeric, $15::numeric, $16::numeric)
+            "#,
+        )
+        .bind(header.id)
+        .bind(line.line_number)
+        .bind(line.transaction_id)
+        .bind(line.transaction_date)
+        .bind(line.user_id)
+        .bind(line.spbu_id)
+        .bind(&line.spbu_name)
+        .bind(line.vehicle_id)
+        .bind(&line.plate_number)
+        .bind(&line.fuel_type)
+        .bind(line.quantity.to_string())
+        .bind(line.price_per_liter.to_string())
+        .bind(line.discount.to_string())
+        .bind(line.amount.to_string())
+        .bind(line.subtotal.to_string())
+        .bind(line.tax_amount.to_string())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok((true, StatementDocument::new(Some(&header), name, period, tax_rate, preview.lines)))
+}
+
+// Tanggal di CSV/PDF ditulis dalam WIB, sama dengan batas periode
+fn local_offset() -> FixedOffset {
+    FixedOffset::east_opt(BILLING_UTC_OFFSET_SECS).expect("valid UTC offset")
+}
+
+fn render(document: StatementDocument, format: Option<&str>) -> Result<Response> {
+    let format = format.unwrap_or("json").trim().to_lowercase();
+    match format.as_str() {
+        "json" => Ok(Json(document).into_response()),
+        "csv" => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", document.file_name("csv")),
+                ),
+            ],
+            document.to_csv(&local_offset()),
+        )
+            .into_response()),
+        "pdf" => Ok((
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", document.file_name("pdf")),
+                ),
+            ],
+            document.to_pdf(&local_offset()),
+        )
+            .into_response()),
+        _ => Err(AppError::BadRequest("format must be json, csv, or pdf".to_string())),
+    }
+}
+
+async fn list_issued(state: &AppState, account: StatementAccount) -> Result<Vec<StatementSummary>> {
+    let statements = sqlx::query_as::<_, StatementSummary>(&format!(
+        "SELECT {} FROM statements WHERE {} = $1 ORDER BY period_start DESC",
+        STATEMENT_SUMMARY_COLUMNS,
+        account.column()
+    ))
+    .bind(account.id())
+    .fetch_all(&state.db)
+    .await?;
+    Ok(statements)
+}
+
+// GET /me/statements
+pub async fn list_my_statements(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<StatementSummary>>> {
+    Ok(Json(list_issued(&state, StatementAccount::User(user_id)).await?))
+}
+
+// GET /me/statements/:period?format=json|csv|pdf
+pub async fn get_my_statement(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(period): Path<String>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Response> {
+    let document = load_statement(&state, StatementAccount::User(user_id), &period).await?;
+    render(document, query.format.as_deref())
+}
+
+// POST /me/statements/:period
+// 201 jika baru diterbitkan, 200 jika sudah terbit sebelumnya
+pub async fn issue_my_statement(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(period): Path<String>,
+) -> Result<(StatusCode, Json<StatementDocument>)> {
+    let (issued, document) = issue_statement(&state, StatementAccount::User(user_id), &period).await?;
+    Ok((if issued { StatusCode::CREATED } else { StatusCode::OK }, Json(document)))
+}
+
+// GET /organizations/:id/statements
+pub async fn list_organization_statements(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<StatementSummary>>> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    Ok(Json(list_issued(&state, StatementAccount::Organization(organization_id)).await?))
+}
+
+// GET /organizations/:id/statements/:period?format=json|csv|pdf
+pub async fn get_organization_statement(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, period)): Path<(Uuid, String)>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Response> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    let document = load_statement(&state, StatementAccount::Organization(organization_id), &period).await?;
+    render(document, query.format.as_deref())
+}
+
+// POST /organizations/:id/statements/:period
+pub async fn issue_organization_statement(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((organization_id, period)): Path<(Uuid, String)>,
+) -> Result<(StatusCode, Json<StatementDocument>)> {
+    require_admin(&state.db, organization_id, user_id).await?;
+    let (issued, document) =
+        issue_statement(&state, StatementAccount::Organization(organization_id), &period).await?;
+    Ok((if issued { StatusCode::CREATED } else { StatusCode::OK }, Json(document)))
+}
@@ -1,77 +1,51 @@
 use axum::{
     extract::{Path, State, Extension},
-    http::StatusCode,
-    response::IntoResponse,
     Json,
 };
-use axum::extract::FromRef;
-use bigdecimal::{BigDecimal, FromPrimitive};
-use serde_json::json;
+use bigdecimal::{BigDecimal, RoundingMode};
+use sqlx::PgConnection;
 use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::error::{AppError, Result};
+use crate::handlers::fill_up::{record_fill_up, NewFillUp};
+use crate::handlers::organization::{
+    enforce_spending_limits, find_billing_organization, find_organization_vehicle, OrganizationPurchase,
+};
+use crate::handlers::loyalty::{award_points, points_balance, points_discount, redeem_points, reverse_transaction_points};
+use crate::handlers::pickup::issue_pickup_code;
+use crate::handlers::promotion::{apply_promotion, record_redemption};
+use crate::handlers::quota::enforce_quota;
+use crate::handlers::vehicle::find_user_vehicle;
+use crate::handlers::wallet::{debit_wallet, refund_to_wallet};
+use crate::models::notification::NotificationEvent;
+use crate::notifications::notify_transaction;
+use crate::payment::{self, PaymentOutcome, PAYMENT_METHOD_INVOICE, PAYMENT_METHOD_WALLET};
+use crate::models::vehicle::FuelCompatibility;
 use crate::types::Decimal;
 use crate::{
-    models::{CreateTransactionRequest, Transaction, TransactionResponse, TransactionStatus, PaymentStatus},
+    models::{AdjustmentStatus, CreateTransactionRequest, Transaction, TransactionResponse, TransactionStatus, PaymentStatus},
     AppState,
 };
-use sqlx::Row;
-
-#[derive(Debug)]
-pub enum AppError {
-    BadRequest(String),
-    NotFound(String),
-    InternalServerError(String),
-    DatabaseError(String),
-}
 
-impl From<sqlx::Error> for AppError {
-    fn from(err: sqlx::Error) -> Self {
-        AppError::DatabaseError(err.to_string())
-    }
-}
-
-impl From<(StatusCode, String)> for AppError {
-    fn from((status, message): (StatusCode, String)) -> Self {
-        match status {
-            StatusCode::BAD_REQUEST => AppError::BadRequest(message),
-            StatusCode::NOT_FOUND => AppError::NotFound(message),
-            _ => AppError::InternalServerError(message),
-        }
-    }
-}
-
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let (status, error_message) = match self {
-            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
-            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
-            AppError::InternalServerError(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
-            AppError::DatabaseError(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
-        };
-
-        let body = Json(json!({
-            "error": error_message,
-        }));
-
-        (status, body).into_response()
-    }
-}
-
-impl std::fmt::Display for AppError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            AppError::BadRequest(msg) => write!(f, "Bad Request: {}", msg),
-            AppError::NotFound(msg) => write!(f, "Not Found: {}", msg),
-            AppError::InternalServerError(msg) => write!(f, "Internal Server Error: {}", msg),
-            AppError::DatabaseError(msg) => write!(f, "Database Error: {}", msg),
-        }
-    }
-}
-
-impl std::error::Error for AppError {}
-
-type Result<T> = std::result::Result<T, AppError>;
+// Kolom transactions; kolom numeric di-cast ke text karena di-parse manual ke Decimal
+pub const TRANSACTION_COLUMNS: &str = r#"
+    id, user_id, spbu_id, vehicle_id, plate_number, fuel_type,
+    quantity::text AS quantity,
+    price_per_liter::text AS price_per_liter,
+    total_price::text AS total_price,
+    points_redeemed,
+    points_discount::text AS points_discount,
+    promo_code,
+    promo_discount::text AS promo_discount,
+    organization_id, invoice_id, pump_id,
+    dispensed_quantity::text AS dispensed_quantity,
+    final_price::text AS final_price,
+    adjustment_amount::text AS adjustment_amount,
+    adjustment_status, pickup_redeemed_at,
+    status, payment_method, payment_status,
+    created_at, updated_at, paid_at
+"#;
 
 #[axum::debug_handler]
 pub async fn create_transaction(
@@ -134,11 +108,128 @@ pub async fn create_transaction(
     // Parse the price from the database
     let price_per_liter = Decimal::from_str(&fuel_price.to_string())
         .map_err(|_| AppError::InternalServerError("Invalid price format in database".to_string()))?;
+
+    // Cek kecocokan BBM dan kapasitas tangki jika transaksi untuk kendaraan tertentu
+    if payload.odometer_km.is_some() && payload.vehicle_id.is_none() {
+        return Err(AppError::BadRequest("odometer_km requires vehicle_id".to_string()));
+    }
+
+    // Transaksi akun organisasi memakai kendaraan armada dan dibayar lewat tagihan bulanan
+    let organization = match payload.organization_id {
+        Some(organization_id) => Some(find_billing_organization(&state.db, organization_id, user_id).await?),
+        None => None,
+    };
+    // Metode invoice hanya untuk akun organisasi; transaksi pribadi tidak pernah masuk tagihan
+    if organization.is_none() && payload.payment_method.trim().eq_ignore_ascii_case(PAYMENT_METHOD_INVOICE) {
+        return Err(AppError::BadRequest(
+            "payment_method invoice requires an organization_id".to_string(),
+        ));
+    }
+    let vehicle = match (payload.vehicle_id, &organization) {
+        (Some(vehicle_id), Some(organization)) => {
+            Some(find_organization_vehicle(&state.db, organization.id, vehicle_id).await?)
+        }
+        (Some(vehicle_id), None) => Some(find_user_vehicle(&state.db, user_id, vehicle_id).await?),
+        (None, _) => None,
+    };
+    let mut warnings = Vec::new();
+    if let Some(vehicle) = &vehicle {
+        match vehicle.fuel_compatibility(&payload.fuel_type) {
+            FuelCompatibility::Recommended => {}
+            FuelCompatibility::NotRecommended => warnings.push(format!(
+                "{} is not a recommended fuel for vehicle {} (recommended: {})",
+                payload.fuel_type,
+                vehicle.plate_number,
+                vehicle.recommended_fuel_types.join(", ")
+            )),
+            FuelCompatibility::Incompatible => {
+                return Err(AppError::BadRequest(format!(
+                    "{} is not compatible with vehicle {} (recommended: {})",
+                    payload.fuel_type,
+                    vehicle.plate_number,
+                    vehicle.recommended_fuel_types.join(", ")
+                )));
+            }
+        }
+
+        if payload.quantity.0 > vehicle.tank_capacity.0 {
+            return Err(AppError::BadRequest(format!(
+                "Quantity exceeds tank capacity of vehicle {} (max {} liters)",
+                vehicle.plate_number, vehicle.tank_capacity
+            )));
+        }
+    }
     
     // Clone the BigDecimal values before using them in multiplication
     let price_per_liter_bd = price_per_liter.0.clone();
     let quantity_bd = payload.quantity.0.clone();
-    let total_price = Decimal(price_per_liter_bd * quantity_bd);
+    let gross_total = Decimal(price_per_liter_bd * quantity_bd);
+
+    // Poin yang ditukar menjadi potongan harga
+    let points_redeemed = payload.redeem_points.unwrap_or(0);
+    if points_redeemed > 0 && organization.is_some() {
+        return Err(AppError::BadRequest(
+            "Points cannot be redeemed on organization transactions".to_string(),
+        ));
+    }
+    let points_discount = points_discount(points_redeemed)?;
+    // Cek awal; saldo dicek ulang dengan akun terkunci saat poin ditukar
+    if points_redeemed > 0 {
+        let balance = points_balance(&state.db, user_id).await?;
+        if balance < points_redeemed {
+            return Err(AppError::BadRequest(format!(
+                "Insufficient points: requested {}, available {}",
+                points_redeemed, balance
+            )));
+        }
+    }
+
+    // Kuota BBM bersubsidi dan promo dicek lalu transaksi disimpan dalam satu transaksi database
+    let mut tx = state.db.begin().await?;
+    enforce_quota(&mut tx, vehicle.as_ref(), &payload.fuel_type, &payload.quantity).await?;
+
+    // Promo dihitung dari harga kotor, poin dipakai untuk sisa setelah promo
+    let promotion = match payload.promo_code.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(code) => Some(
+            apply_promotion(&mut tx, user_id, code, payload.spbu_id, &payload.fuel_type, &gross_total).await?,
+        ),
+        None => None,
+    };
+    let promo_discount = promotion
+        .as_ref()
+        .map(|(_, discount)| discount.clone())
+        .unwrap_or_default();
+    let after_promo = gross_total - promo_discount.clone();
+
+    if points_discount > after_promo {
+        return Err(AppError::BadRequest(format!(
+            "Points discount ({}) exceeds transaction total ({})",
+            points_discount, after_promo
+        )));
+    }
+    let total_price = after_promo - points_discount.clone();
+
+    if let Some(organization) = &organization {
+        enforce_spending_limits(
+            &mut tx,
+            organization,
+            OrganizationPurchase {
+                user_id,
+                vehicle: vehicle.as_ref(),
+                spbu_id: payload.spbu_id,
+                fuel_type: &payload.fuel_type,
+                quantity: &payload.quantity,
+                amount: &total_price,
+            },
+        )
+        .await?;
+    }
+
+    // Transaksi organisasi langsung diproses; pembayarannya menunggu tagihan bulanan
+    let (status, payment_method) = match &organization {
+        Some(_) => (TransactionStatus::Processing, PAYMENT_METHOD_INVOICE),
+        None => (TransactionStatus::Pending, payload.payment_method.as_str()),
+    };
     
     // Convert to string for database storage
     let quantity_str = payload.quantity.0.to_string();
@@ -151,6 +242,8 @@ pub async fn create_transaction(
         INSERT INTO transactions (
             user_id, 
             spbu_id, 
+            vehicle_id,
+            plate_number,
             fuel_type, 
             quantity, 
             price_per_liter, 
@@ -158,35 +251,75 @@ pub async fn create_transaction(
             status, 
             payment_method, 
             payment_status,
+            points_redeemed,
+            points_discount,
+            promo_code,
+            promo_discount,
+            organization_id,
             created_at,
             updated_at
-        ) VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::numeric, $7::transaction_status, $8, $9::payment_status, NOW(), NOW())
+        ) VALUES ($1, $2, $3, $4, $5, $6::numeric, $7::numeric, $8::numeric, $9::transaction_status, $10, $11::payment_status, $12, $13::numeric, $14, $15::numeric, $16, NOW(), NOW())
         RETURNING id
         "#
     )
     .bind(user_id)
     .bind(payload.spbu_id)
+    .bind(payload.vehicle_id)
+    .bind(vehicle.as_ref().map(|v| v.plate_number.as_str()))
     .bind(&payload.fuel_type)
     .bind(quantity_str)
     .bind(price_per_liter_str)
     .bind(total_price_str)
-    .bind(TransactionStatus::Pending)
-    .bind(&payload.payment_method)
+    .bind(status)
+    .bind(payment_method)
     .bind(PaymentStatus::Pending)
-    .fetch_one(&state.db)
+    .bind(points_redeemed)
+    .bind(points_discount.to_string())
+    .bind(promotion.as_ref().map(|(p, _)| p.code.as_str()))
+    .bind(promo_discount.to_string())
+    .bind(organization.as_ref().map(|o| o.id))
+    .fetch_one(&mut *tx)
     .await?;
+
+    if let Some((promotion, discount)) = &promotion {
+        record_redemption(&mut tx, promotion.id, user_id, transaction_id, discount).await?;
+    }
+    if points_redeemed > 0 {
+        redeem_points(&mut tx, user_id, transaction_id, points_redeemed).await?;
+    }
     
     // Get the full transaction details
-    let transaction = sqlx::query_as::<_, Transaction>(
-        r#"
-        SELECT * FROM transactions WHERE id = $1
-        "#
-    )
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
+        "SELECT {} FROM transactions WHERE id = $1",
+        TRANSACTION_COLUMNS
+    ))
     .bind(transaction_id)
-    .fetch_one(&state.db)
+    .fetch_one(&mut *tx)
     .await?;
-    
-    Ok(Json(transaction.into()))
+
+    if let (Some(vehicle), Some(odometer_km)) = (&vehicle, payload.odometer_km) {
+        record_fill_up(
+            &mut tx,
+            vehicle,
+            NewFillUp {
+                transaction_id: Some(transaction.id),
+                spbu_id: Some(transaction.spbu_id),
+                station_name: None,
+                fuel_type: Some(&transaction.fuel_type),
+                liters: &transaction.quantity,
+                odometer_km,
+                is_full_tank: payload.is_full_tank.unwrap_or(true),
+                filled_at: transaction.created_at.unwrap_or_else(chrono::Utc::now),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    let mut response: TransactionResponse = transaction.into();
+    response.warnings = warnings;
+    Ok(Json(response))
 }
 
 #[axum::debug_handler]
@@ -196,46 +329,16 @@ pub async fn get_transaction(
     Path(transaction_id): Path<Uuid>,
 ) -> Result<Json<TransactionResponse>> {
     // Get transaction by ID and user ID
-    let transaction = sqlx::query(
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
         r#"
-        SELECT 
-            id, 
-            user_id, 
-            spbu_id, 
-            fuel_type, 
-            quantity::text, 
-            price_per_liter::text, 
-            total_price::text, 
-            status, 
-            payment_method, 
-            payment_status, 
-            created_at, 
-            updated_at, 
-            paid_at 
+        SELECT {}
         FROM transactions 
         WHERE id = $1 AND user_id = $2
-        "#
-    )
+        "#,
+        TRANSACTION_COLUMNS
+    ))
     .bind(transaction_id)
     .bind(user_id)
-    .map(|row: sqlx::postgres::PgRow| {
-        // Manual mapping to handle Decimal fields properly
-        Transaction {
-            id: row.get("id"),
-            user_id: row.get("user_id"),
-            spbu_id: row.get("spbu_id"),
-            fuel_type: row.get("fuel_type"),
-            quantity: Decimal(BigDecimal::from_str(&row.get::<String, _>("quantity")).unwrap_or_default()),
-            price_per_liter: Decimal(BigDecimal::from_str(&row.get::<String, _>("price_per_liter")).unwrap_or_default()),
-            total_price: Decimal(BigDecimal::from_str(&row.get::<String, _>("total_price")).unwrap_or_default()),
-            status: row.get("status"),
-            payment_method: row.get("payment_method"),
-            payment_status: row.get("payment_status"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            paid_at: row.get("paid_at"),
-        }
-    })
     .fetch_optional(&state.db)
     .await
     .map_err(|e| AppError::InternalServerError(e.to_string()))?;
@@ -252,46 +355,16 @@ pub async fn list_transactions(
     Extension(user_id): Extension<Uuid>,
 ) -> Result<Json<Vec<TransactionResponse>>> {
     // Get all transactions for the user, ordered by creation date (newest first)
-    let transactions = sqlx::query(
+    let transactions = sqlx::query_as::<_, Transaction>(&format!(
         r#"
-        SELECT 
-            id, 
-            user_id, 
-            spbu_id, 
-            fuel_type, 
-            quantity::text, 
-            price_per_liter::text, 
-            total_price::text, 
-            status, 
-            payment_method, 
-            payment_status, 
-            created_at, 
-            updated_at, 
-            paid_at 
+        SELECT {}
         FROM transactions 
         WHERE user_id = $1
         ORDER BY created_at DESC
-        "#
-    )
+        "#,
+        TRANSACTION_COLUMNS
+    ))
     .bind(user_id)
-    .map(|row: sqlx::postgres::PgRow| {
-        // Manual mapping to handle Decimal fields properly
-        Transaction {
-            id: row.get("id"),
-            user_id: row.get("user_id"),
-            spbu_id: row.get("spbu_id"),
-            fuel_type: row.get("fuel_type"),
-            quantity: Decimal::from_str(&row.get::<String, _>("quantity")).unwrap_or_default(),
-            price_per_liter: Decimal::from_str(&row.get::<String, _>("price_per_liter")).unwrap_or_default(),
-            total_price: Decimal::from_str(&row.get::<String, _>("total_price")).unwrap_or_default(),
-            status: row.get("status"),
-            payment_method: row.get("payment_method"),
-            payment_status: row.get("payment_status"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            paid_at: row.get("paid_at"),
-        }
-    })
     .fetch_all(&state.db)
     .await?
     .into_iter()
@@ -312,13 +385,14 @@ pub async fn cancel_transaction(
         .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
     // Get the transaction with row lock to prevent race conditions
-    let transaction = sqlx::query_as::<_, Transaction>(
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
         r#"
-        SELECT * FROM transactions 
+        SELECT {} FROM transactions 
         WHERE id = $1 AND user_id = $2
         FOR UPDATE
-        "#
-    )
+        "#,
+        TRANSACTION_COLUMNS
+    ))
     .bind(transaction_id)
     .bind(user_id)
     .fetch_optional(&mut *tx)
@@ -328,59 +402,69 @@ pub async fn cancel_transaction(
     match transaction {
         Some(transaction) => {
             // Check if the transaction can be cancelled
-            if transaction.status != TransactionStatus::Pending {
+            if !matches!(transaction.status, TransactionStatus::Pending | TransactionStatus::Processing) {
+                return Err(AppError::BadRequest(
+                    "Only pending or processing transactions can be cancelled".to_string(),
+                ));
+            }
+
+            if transaction.invoice_id.is_some() {
                 return Err(AppError::BadRequest(
-                    "Only pending transactions can be cancelled".to_string(),
+                    "Transactions included in an organization invoice cannot be cancelled".to_string(),
                 ));
             }
 
+            // BBM sudah mulai diisi setelah kode QR dipindai petugas
+            if transaction.pickup_redeemed_at.is_some() {
+                return Err(AppError::BadRequest(
+                    "Transactions already redeemed at the pump cannot be cancelled".to_string(),
+                ));
+            }
+
+            // Transaksi yang sudah dibayar dikembalikan ke dompet
+            let payment_status = if transaction.payment_status == PaymentStatus::Paid {
+                if transaction.total_price.0 > BigDecimal::from(0) {
+                    refund_to_wallet(
+                        &mut tx,
+                        user_id,
+                        transaction_id,
+                        &transaction.total_price,
+                        "Refund for cancelled transaction",
+                    )
+                    .await?;
+                }
+                PaymentStatus::Refunded
+            } else {
+                transaction.payment_status
+            };
+
             // Update the transaction status to cancelled
-            let updated_transaction = sqlx::query(
+            let updated_transaction = sqlx::query_as::<_, Transaction>(&format!(
                 r#"
                 UPDATE transactions
-                SET status = $1, updated_at = NOW()
+                SET status = $1, payment_status = $4, updated_at = NOW()
                 WHERE id = $2 AND user_id = $3
-                RETURNING 
-                    id, 
-                    user_id, 
-                    spbu_id, 
-                    fuel_type, 
-                    quantity::text, 
-                    price_per_liter::text, 
-                    total_price::text, 
-                    status, 
-                    payment_method, 
-                    payment_status, 
-                    created_at, 
-                    updated_at, 
-                    paid_at
-                "#
-            )
-            .bind(TransactionStatus::Cancelled.to_string())
+                RETURNING {}
+                "#,
+                TRANSACTION_COLUMNS
+            ))
+            .bind(TransactionStatus::Cancelled)
             .bind(transaction_id)
             .bind(user_id)
-            .map(|row: sqlx::postgres::PgRow| {
-                // Manual mapping to handle Decimal fields properly
-                Transaction {
-                    id: row.get("id"),
-                    user_id: row.get("user_id"),
-                    spbu_id: row.get("spbu_id"),
-                    fuel_type: row.get("fuel_type"),
-                    quantity: Decimal::from_str(&row.get::<String, _>("quantity")).unwrap_or_default(),
-                    price_per_liter: Decimal::from_str(&row.get::<String, _>("price_per_liter")).unwrap_or_default(),
-                    total_price: Decimal::from_str(&row.get::<String, _>("total_price")).unwrap_or_default(),
-                    status: row.get("status"),
-                    payment_method: row.get("payment_method"),
-                    payment_status: row.get("payment_status"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                    paid_at: row.get("paid_at"),
-                }
-            })
+            .bind(payment_status)
             .fetch_one(&mut *tx)
             .await
             .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
+            release_cancelled_transaction(&mut tx, transaction_id).await?;
+
+            if payment_status == PaymentStatus::Refunded && transaction.total_price.0 > BigDecimal::from(0) {
+                notify_transaction(&mut tx, NotificationEvent::RefundIssued, &updated_transaction, serde_json::json!({
+                    "amount": transaction.total_price.to_string(),
+                }))
+                .await?;
+            }
+
             // Commit the transaction
             tx.commit().await
                 .map_err(|e| AppError::InternalServerError(e.to_string()))?;
@@ -394,6 +478,21 @@ pub async fn cancel_transaction(
     }
 }
 
+/// Lepaskan semua yang masih terikat ke transaksi yang baru dibatalkan.
+/// Pemakaian kuota, promo dan batas belanja organisasi tidak perlu diubah karena
+/// perhitungannya sudah mengabaikan transaksi berstatus cancelled.
+pub async fn release_cancelled_transaction(conn: &mut PgConnection, transaction_id: Uuid) -> Result<()> {
+    // Pengisian yang dibatalkan tidak ikut dihitung dalam statistik efisiensi
+    sqlx::query("DELETE FROM fill_ups WHERE transaction_id = $1")
+        .bind(transaction_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Kembalikan poin yang sudah ditukar
+    reverse_transaction_points(conn, transaction_id).await?;
+    Ok(())
+}
+
 #[axum::debug_handler]
 pub async fn process_payment(
     State(state): State<AppState>,
@@ -405,13 +504,14 @@ pub async fn process_payment(
         .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
     // Get the transaction with row lock to prevent race conditions
-    let transaction = sqlx::query_as::<_, Transaction>(
+    let transaction = sqlx::query_as::<_, Transaction>(&format!(
         r#"
-        SELECT * FROM transactions 
+        SELECT {} FROM transactions 
         WHERE id = $1 AND user_id = $2
         FOR UPDATE
-        "#
-    )
+        "#,
+        TRANSACTION_COLUMNS
+    ))
     .bind(transaction_id)
     .bind(user_id)
     .fetch_optional(&mut *tx)
@@ -423,6 +523,12 @@ pub async fn process_payment(
         None => return Err(AppError::NotFound("Transaction not found".to_string())),
     };
 
+    if transaction.organization_id.is_some() {
+        return Err(AppError::BadRequest(
+            "Organization transactions are settled through the monthly invoice".to_string(),
+        ));
+    }
+
     // Validate transaction status
     if transaction.status != TransactionStatus::Pending {
         return Err(AppError::BadRequest(
@@ -430,13 +536,26 @@ pub async fn process_payment(
         ));
     }
 
-    // Process payment (in a real implementation, this would call a payment gateway)
-    // For demo purposes, we'll assume the payment is always successful
-    let payment_success = true;
+    // Bayar dari saldo dompet (dalam transaksi database yang sama) atau lewat payment gateway.
+    // Transaksi yang seluruhnya tertutup promo/poin tidak perlu ditagih.
+    let payment_success = if transaction.total_price.0 <= BigDecimal::from(0) {
+        true
+    } else if transaction.payment_method.eq_ignore_ascii_case(PAYMENT_METHOD_WALLET) {
+        debit_wallet(&mut tx, user_id, transaction_id, &transaction.total_price).await?;
+        true
+    } else {
+        match payment::charge(&transaction.payment_method, &transaction.total_price).await {
+            PaymentOutcome::Succeeded => true,
+            PaymentOutcome::Failed(reason) => {
+                tracing::warn!("Payment for transaction {} failed: {}", transaction_id, reason);
+                false
+            }
+        }
+    };
 
     let updated_transaction = if payment_success {
         // Update transaction and payment status to paid
-        sqlx::query(
+        sqlx::query_as::<_, Transaction>(&format!(
             r#"
             UPDATE transactions
             SET status = $1::transaction_status, 
@@ -444,105 +563,255 @@ pub async fn process_payment(
                 paid_at = NOW(),
                 updated_at = NOW()
             WHERE id = $3
-            RETURNING 
-                id, 
-                user_id, 
-                spbu_id, 
-                fuel_type, 
-                quantity::text, 
-                price_per_liter::text, 
-                total_price::text, 
-                status, 
-                payment_method, 
-                payment_status, 
-                created_at, 
-                updated_at, 
-                paid_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            TRANSACTION_COLUMNS
+        ))
         .bind(TransactionStatus::Processing)
         .bind(PaymentStatus::Paid)
         .bind(transaction_id)
-        .map(|row: sqlx::postgres::PgRow| {
-            // Manual mapping to handle Decimal fields properly
-            Transaction {
-                id: row.get("id"),
-                user_id: row.get("user_id"),
-                spbu_id: row.get("spbu_id"),
-                fuel_type: row.get("fuel_type"),
-                quantity: Decimal(BigDecimal::from_str(&row.get::<String, _>("quantity")).unwrap_or_default()),
-                price_per_liter: Decimal(BigDecimal::from_str(&row.get::<String, _>("price_per_liter")).unwrap_or_default()),
-                total_price: Decimal(BigDecimal::from_str(&row.get::<String, _>("total_price")).unwrap_or_default()),
-                status: row.get("status"),
-                payment_method: row.get("payment_method"),
-                payment_status: row.get("payment_status"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-                paid_at: row.get("paid_at"),
-            }
-        })
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| AppError::InternalServerError(e.to_string()))?
     } else {
         // If payment failed
-        sqlx::query(
+        sqlx::query_as::<_, Transaction>(&format!(
             r#"
             UPDATE transactions
             SET payment_status = $1::payment_status, 
                 updated_at = NOW()
             WHERE id = $2
-            RETURNING 
-                id, 
-                user_id, 
-                spbu_id, 
-                fuel_type, 
-                quantity::text, 
-                price_per_liter::text, 
-                total_price::text, 
-                status, 
-                payment_method, 
-                payment_status, 
-                created_at, 
-                updated_at, 
-                paid_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            TRANSACTION_COLUMNS
+        ))
         .bind(PaymentStatus::Failed)
         .bind(transaction_id)
-        .map(|row: sqlx::postgres::PgRow| {
-            // Manual mapping to handle Decimal fields properly
-            Transaction {
-                id: row.get("id"),
-                user_id: row.get("user_id"),
-                spbu_id: row.get("spbu_id"),
-                fuel_type: row.get("fuel_type"),
-                quantity: Decimal(BigDecimal::from_str(&row.get::<String, _>("quantity")).unwrap_or_default()),
-                price_per_liter: Decimal(BigDecimal::from_str(&row.get::<String, _>("price_per_liter")).unwrap_or_default()),
-                total_price: Decimal(BigDecimal::from_str(&row.get::<String, _>("total_price")).unwrap_or_default()),
-                status: row.get("status"),
-                payment_method: row.get("payment_method"),
-                payment_status: row.get("payment_status"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-                paid_at: row.get("paid_at"),
-            }
-        })
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| AppError::InternalServerError(e.to_string()))?
     };
 
+    // Kode QR untuk ditunjukkan ke petugas pompa
+    let pickup_code = if payment_success {
+        Some(issue_pickup_code(&mut tx, transaction_id).await?)
+    } else {
+        None
+    };
+
+    let event = if payment_success {
+        NotificationEvent::PaymentSucceeded
+    } else {
+        NotificationEvent::PaymentFailed
+    };
+    notify_transaction(&mut tx, event, &updated_transaction, serde_json::json!({})).await?;
+
     // Commit the transaction
     tx.commit().await
         .map_err(|e| AppError::InternalServerError(e.to_string()))?;
 
-    // Send notification (in a real implementation, this would send an email/notification)
-    if payment_success {
-        println!("Payment successful for transaction: {}", transaction_id);
+    let mut response: TransactionResponse = updated_transaction.into();
+    response.pickup_code = pickup_code;
+    Ok(Json(response))
+}
+
+/// Harga untuk liter aktual dengan potongan promo dan poin yang sama seperti saat pesanan dibuat
+fn reconciled_price(transaction: &Transaction, dispensed_quantity: &Decimal) -> Decimal {
+    let gross = (&dispensed_quantity.0 * &transaction.price_per_liter.0).with_scale_round(2, RoundingMode::HalfUp);
+    let net = gross - &transaction.promo_discount.0 - &transaction.points_discount.0;
+    Decimal(net.max(BigDecimal::from(0)))
+}
+
+/// Cara menyelesaikan selisih harga. Transaksi organisasi belum masuk tagihan saat diselesaikan
+/// (tagihan hanya memuat transaksi `completed`), jadi selisihnya cukup ikut tagihan berikutnya.
+fn adjustment_status(transaction: &Transaction, adjustment: &Decimal) -> Option<AdjustmentStatus> {
+    if adjustment.0 == BigDecimal::from(0) {
+        None
+    } else if transaction.organization_id.is_some() {
+        Some(AdjustmentStatus::PendingInvoice)
+    } else if adjustment.0 < BigDecimal::from(0) {
+        Some(AdjustmentStatus::Refunded)
     } else {
-        println!("Payment failed for transaction: {}", transaction_id);
+        Some(AdjustmentStatus::Underpaid)
+    }
+}
+
+/// Tandai transaksi (yang sudah dikunci pemanggil) selesai dan berikan poin.
+/// Jika `dispensed_quantity` diisi, harga dihitung ulang dari liter aktual: kelebihan bayar
+/// dikembalikan ke dompet, kekurangan ditandai `underpaid`. Nilai pesanan tetap disimpan.
+pub async fn finish_transaction(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+    dispensed_quantity: Option<&Decimal>,
+) -> Result<TransactionResponse> {
+    // Hanya transaksi yang sudah dibayar (processing) yang bisa diselesaikan
+    if transaction.status != TransactionStatus::Processing {
+        return Err(AppError::BadRequest(
+            "Only paid transactions being processed can be completed".to_string(),
+        ));
+    }
+
+    let reconciliation = dispensed_quantity.map(|dispensed| {
+        let final_price = reconciled_price(transaction, dispensed);
+        let adjustment = final_price.clone() - transaction.total_price.clone();
+        let status = adjustment_status(transaction, &adjustment);
+        (dispensed, final_price, adjustment, status)
+    });
+
+    if let Some((_, _, adjustment, Some(AdjustmentStatus::Refunded))) = &reconciliation {
+        refund_to_wallet(
+            &mut *conn,
+            transaction.user_id,
+            transaction.id,
+            &Decimal(-adjustment.0.clone()),
+            "Refund for undispensed fuel",
+        )
+        .await?;
+    }
+
+    let completed = sqlx::query_as::<_, Transaction>(&format!(
+        r#"
+        UPDATE transactions
+        SET status = $1,
+            dispensed_quantity = COALESCE($3::numeric, dispensed_quantity),
+            final_price = COALESCE($4::numeric, final_price),
+            adjustment_amount = COALESCE($5::numeric, adjustment_amount),
+            adjustment_status = COALESCE($6, adjustment_status),
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING {}
+        "#,
+        TRANSACTION_COLUMNS
+    ))
+    .bind(TransactionStatus::Completed)
+    .bind(transaction.id)
+    .bind(reconciliation.as_ref().map(|(dispensed, _, _, _)| dispensed.to_string()))
+    .bind(reconciliation.as_ref().map(|(_, final_price, _, _)| final_price.to_string()))
+    .bind(reconciliation.as_ref().map(|(_, _, adjustment, _)| adjustment.to_string()))
+    .bind(reconciliation.as_ref().and_then(|(_, _, _, status)| *status))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Statistik efisiensi memakai liter yang benar-benar masuk ke tangki
+    if let Some((dispensed, _, _, _)) = &reconciliation {
+        sqlx::query("UPDATE fill_ups SET liters = $1::numeric WHERE transaction_id = $2")
+            .bind(dispensed.to_string())
+            .bind(transaction.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    // Poin hanya untuk pembelian pribadi, bukan atas tagihan organisasi
+    let points_earned = match completed.organization_id {
+        Some(_) => None,
+        None => Some(award_points(&mut *conn, &completed).await?),
+    };
+
+    notify_transaction(&mut *conn, NotificationEvent::TransactionCompleted, &completed, serde_json::json!({})).await?;
+    if let Some((_, _, adjustment, Some(AdjustmentStatus::Refunded))) = &reconciliation {
+        notify_transaction(&mut *conn, NotificationEvent::RefundIssued, &completed, serde_json::json!({
+            "amount": (-adjustment.0.clone()).to_string(),
+        }))
+        .await?;
     }
 
-    Ok(Json(updated_transaction.into()))
+    let mut response: TransactionResponse = completed.into();
+    response.points_earned = points_earned;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(quantity: &str, price_per_liter: &str, promo_discount: &str, points_discount: &str) -> Transaction {
+        let total = quantity.parse::<Decimal>().unwrap() * price_per_liter.parse::<Decimal>().unwrap()
+            - promo_discount.parse().unwrap()
+            - points_discount.parse().unwrap();
+        Transaction {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            spbu_id: Uuid::new_v4(),
+            vehicle_id: None,
+            plate_number: None,
+            fuel_type: "PERTALITE".to_string(),
+            quantity: quantity.parse().unwrap(),
+            price_per_liter: price_per_liter.parse().unwrap(),
+            total_price: total,
+            points_redeemed: 0,
+            points_discount: points_discount.parse().unwrap(),
+            promo_code: None,
+            promo_discount: promo_discount.parse().unwrap(),
+            organization_id: None,
+            invoice_id: None,
+            pump_id: None,
+            dispensed_quantity: None,
+            final_price: None,
+            adjustment_amount: None,
+            adjustment_status: None,
+            pickup_redeemed_at: None,
+            status: TransactionStatus::Processing,
+            payment_method: "wallet".to_string(),
+            payment_status: PaymentStatus::Paid,
+            created_at: None,
+            updated_at: None,
+            paid_at: None,
+        }
+    }
+
+    fn price(value: &str) -> BigDecimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn organization_adjustment_waits_for_next_invoice() {
+        let mut transaction = order("10", "10000", "0", "0");
+        transaction.organization_id = Some(Uuid::new_v4());
+        assert_eq!(adjustment_status(&transaction, &Decimal::from(-5000)), Some(AdjustmentStatus::PendingInvoice));
+        assert_eq!(adjustment_status(&transaction, &Decimal::from(5000)), Some(AdjustmentStatus::PendingInvoice));
+        assert_eq!(adjustment_status(&transaction, &Decimal::from(0)), None);
+    }
+
+    #[test]
+    fn reconciled_price_exact_match_keeps_order_price() {
+        let transaction = order("10", "10000", "2000", "500");
+        let final_price = reconciled_price(&transaction, &"10".parse().unwrap());
+        assert_eq!(final_price.0, price("97500"));
+        let adjustment = final_price - transaction.total_price.clone();
+        assert_eq!(adjustment_status(&transaction, &adjustment), None);
+    }
+
+    #[test]
+    fn reconciled_price_under_dispense_is_refunded() {
+        let transaction = order("10", "10000", "0", "0");
+        let final_price = reconciled_price(&transaction, &"8.5".parse().unwrap());
+        assert_eq!(final_price.0, price("85000"));
+        let adjustment = final_price - transaction.total_price.clone();
+        assert_eq!(adjustment.0, price("-15000"));
+        assert_eq!(adjustment_status(&transaction, &adjustment), Some(AdjustmentStatus::Refunded));
+    }
+
+    #[test]
+    fn reconciled_price_over_dispense_is_underpaid() {
+        let transaction = order("10", "10000", "1000", "0");
+        let final_price = reconciled_price(&transaction, &"10.25".parse().unwrap());
+        assert_eq!(final_price.0, price("101500"));
+        let adjustment = final_price - transaction.total_price.clone();
+        assert_eq!(adjustment.0, price("2500"));
+        assert_eq!(adjustment_status(&transaction, &adjustment), Some(AdjustmentStatus::Underpaid));
+    }
+
+    #[test]
+    fn reconciled_price_keeps_discounts_and_rounds_to_cents() {
+        let transaction = order("10", "12345.67", "5000", "1000");
+        // 3.333 x 12345.67 = 41148.118... -> 41148.12
+        assert_eq!(reconciled_price(&transaction, &"3.333".parse().unwrap()).0, price("35148.12"));
+    }
+
+    #[test]
+    fn reconciled_price_is_never_negative() {
+        // Promo + poin (25000) lebih besar dari harga kotor 1 liter (10000)
+        let transaction = order("10", "10000", "20000", "5000");
+        assert_eq!(reconciled_price(&transaction, &"1".parse().unwrap()).0, price("0"));
+    }
 }
@@ -146,12 +146,6 @@ pub struct UserPayload {
     pub jenis_kelamin: String,
     pub tanggal_lahir: NaiveDate,
     pub foto_profile: String,
-    #[serde(default = "default_role")]
-    pub role: String,
-}
-
-fn default_role() -> String {
-    "user".to_string()
 }
 
 #[derive(Serialize, FromRow)]
@@ -182,6 +176,7 @@ pub async fn register_user(
 
     let now = Utc::now().naive_utc();
 
+    // Role dari request diabaikan; akun baru selalu user biasa
     let row = sqlx::query!(
         r#"
         INSERT INTO users (
@@ -193,7 +188,7 @@ pub async fn register_user(
             role,
             created_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        VALUES ($1, $2, $3, $4, $5, 'user', $6)
         RETURNING id, nama_lengkap, email, password_hash, no_hp, foto_profile, role, created_at
         "#,
         payload.nama_lengkap,
@@ -201,7 +196,6 @@ pub async fn register_user(
         hashed_password,
         payload.no_hp,
         payload.foto_profile,
-        payload.role,
         Some(now)
     )
     .fetch_one(&state.db)
@@ -267,12 +261,11 @@ pub async fn update_user_by_id(
     let now = Utc::now().naive_utc();
     let user = sqlx::query_as!(
         RegisterUserModel,
-        r#"UPDATE users SET nama_lengkap = $1, email = $2, no_hp = $3, foto_profile = $4, role = $5, created_at = $6 WHERE id = $7 RETURNING id, nama_lengkap, email, password_hash, no_hp, foto_profile, role, created_at"#,
+        r#"UPDATE users SET nama_lengkap = $1, email = $2, no_hp = $3, foto_profile = $4, created_at = $5 WHERE id = $6 RETURNING id, nama_lengkap, email, password_hash, no_hp, foto_profile, role, created_at"#,
         payload.nama_lengkap,
         payload.email,
         payload.no_hp,
         Some(payload.foto_profile),
-        payload.role,
         Some(now),
         id
     )
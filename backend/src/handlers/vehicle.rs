@@ -0,0 +1,227 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::vehicle::{normalize_plate_number, CreateVehicleRequest, UpdateVehicleRequest, Vehicle};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Kolom vehicles; tank_capacity di-cast ke text karena di-parse manual ke Decimal
+pub const VEHICLE_COLUMNS: &str = r#"
+    id, user_id, plate_number, vehicle_type, name,
+    tank_capacity::text AS tank_capacity,
+    recommended_fuel_types, avg_consumption_km_per_liter,
+    created_at, updated_at
+"#;
+
+fn normalize_fuel_types(fuel_types: Vec<String>) -> Vec<String> {
+    let mut fuel_types: Vec<String> = fuel_types
+        .into_iter()
+        .map(|f| f.trim().to_uppercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+    fuel_types.sort();
+    fuel_types.dedup();
+    fuel_types
+}
+
+fn validate_plate(plate: &str) -> Result<String> {
+    normalize_plate_number(plate)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid plate number: {}", plate)))
+}
+
+fn validate_tank_capacity(tank_capacity: &Decimal) -> Result<()> {
+    if tank_capacity.0 <= BigDecimal::from(0) {
+        return Err(AppError::BadRequest("tank_capacity must be greater than 0".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_consumption(consumption: Option<f64>) -> Result<()> {
+    if consumption.is_some_and(|c| c <= 0.0) {
+        return Err(AppError::BadRequest(
+            "avg_consumption_km_per_liter must be greater than 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn map_unique_violation(e: sqlx::Error) -> AppError {
+    if e.to_string().contains("duplicate key") {
+        AppError::Conflict("Plate number is already registered".to_string())
+    } else {
+        e.into()
+    }
+}
+
+// Ambil kendaraan milik user; dipakai juga oleh handler transaksi
+pub async fn find_user_vehicle<'e, E>(executor: E, user_id: Uuid, vehicle_id: Uuid) -> Result<Vehicle>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query_as::<_, Vehicle>(&format!(
+        "SELECT {} FROM vehicles WHERE id = $1 AND user_id = $2",
+        VEHICLE_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Vehicle not found".to_string()))
+}
+
+// GET /me/vehicles
+pub async fn list_vehicles(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<Vehicle>>> {
+    let vehicles = sqlx::query_as::<_, Vehicle>(&format!(
+        "SELECT {} FROM vehicles WHERE user_id = $1 ORDER BY created_at",
+        VEHICLE_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(vehicles))
+}
+
+// GET /me/vehicles/:id
+pub async fn get_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+) -> Result<Json<Vehicle>> {
+    let vehicle = find_user_vehicle(&state.db, user_id, vehicle_id).await?;
+    Ok(Json(vehicle))
+}
+
+// POST /me/vehicles
+pub async fn create_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateVehicleRequest>,
+) -> Result<(StatusCode, Json<Vehicle>)> {
+    let plate_number = validate_plate(&payload.plate_number)?;
+    validate_tank_capacity(&payload.tank_capacity)?;
+    validate_consumption(payload.avg_consumption_km_per_liter)?;
+
+    let vehicle = sqlx::query_as::<_, Vehicle>(&format!(
+        r#"
+        INSERT INTO vehicles (
+            user_id, plate_number, vehicle_type, name, tank_capacity,
+            recommended_fuel_types, avg_consumption_km_per_liter
+        )
+        VALUES ($1, $2, $3, $4, $5::numeric, $6, $7)
+        RETURNING {}
+        "#,
+        VEHICLE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(plate_number)
+    .bind(payload.vehicle_type)
+    .bind(payload.name)
+    .bind(payload.tank_capacity.to_string())
+    .bind(normalize_fuel_types(payload.recommended_fuel_types))
+    .bind(payload.avg_consumption_km_per_liter)
+    .fetch_one(&state.db)
+    .await
+    .map_err(map_unique_violation)?;
+
+    Ok((StatusCode::CREATED, Json(vehicle)))
+}
+
+// PUT /me/vehicles/:id
+pub async fn update_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+    Json(payload): Json<UpdateVehicleRequest>,
+) -> Result<Json<Vehicle>> {
+    let plate_number = payload.plate_number.as_deref().map(validate_plate).transpose()?;
+    if let Some(tank_capacity) = &payload.tank_capacity {
+        validate_tank_capacity(tank_capacity)?;
+    }
+    validate_consumption(payload.avg_consumption_km_per_liter)?;
+    let update_name = payload.name.is_some();
+
+    let vehicle = sqlx::query_as::<_, Vehicle>(&format!(
+        r#"
+        UPDATE vehicles
+        SET
+            plate_number = COALESCE($1, plate_number),
+            vehicle_type = COALESCE($2, vehicle_type),
+            name = CASE WHEN $9 THEN $3 ELSE name END,
+            tank_capacity = COALESCE($4::numeric, tank_capacity),
+            recommended_fuel_types = COALESCE($5, recommended_fuel_types),
+            avg_consumption_km_per_liter = COALESCE($6, avg_consumption_km_per_liter)
+        WHERE id = $7 AND user_id = $8
+        RETURNING {}
+        "#,
+        VEHICLE_COLUMNS
+    ))
+    .bind(plate_number)
+    .bind(payload.vehicle_type)
+    .bind(payload.name.flatten())
+    .bind(payload.tank_capacity.map(|t| t.to_string()))
+    .bind(payload.recommended_fuel_types.map(normalize_fuel_types))
+    .bind(payload.avg_consumption_km_per_liter)
+    .bind(vehicle_id)
+    .bind(user_id)
+    .bind(update_name)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(map_unique_violation)?
+    .ok_or_else(|| AppError::NotFound("Vehicle not found".to_string()))?;
+
+    Ok(Json(vehicle))
+}
+
+// DELETE /me/vehicles/:id
+pub async fn delete_vehicle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(vehicle_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let result = sqlx::query("DELETE FROM vehicles WHERE id = $1 AND user_id = $2")
+        .bind(vehicle_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Vehicle not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fuel_types(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_fuel_types_trims_and_uppercases() {
+        assert_eq!(
+            normalize_fuel_types(fuel_types(&[" pertalite ", "Pertamax", ""])),
+            fuel_types(&["PERTALITE", "PERTAMAX"])
+        );
+    }
+
+    #[test]
+    fn normalize_fuel_types_removes_non_adjacent_duplicates() {
+        assert_eq!(
+            normalize_fuel_types(fuel_types(&["pertamax", "PERTALITE", "Pertamax ", "pertalite"])),
+            fuel_types(&["PERTALITE", "PERTAMAX"])
+        );
+    }
+}
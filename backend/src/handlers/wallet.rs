@@ -0,0 +1,306 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use bigdecimal::BigDecimal;
+use sqlx::PgConnection;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::wallet::{
+    CreateTopupRequest, WalletEntry, WalletEntryType, WalletQuery, WalletResponse, WalletTopup,
+};
+use crate::models::PaymentStatus;
+use crate::payment::{self, PaymentOutcome, PAYMENT_METHOD_WALLET};
+use crate::types::Decimal;
+use crate::AppState;
+
+// Batas nominal satu kali top-up
+const MIN_TOPUP_AMOUNT: i32 = 10_000;
+const MAX_TOPUP_AMOUNT: i32 = 10_000_000;
+
+const WALLET_ENTRY_COLUMNS: &str = r#"
+    id, entry_type,
+    amount::text AS amount,
+    balance_after::text AS balance_after,
+    transaction_id, topup_id, description, created_at
+"#;
+
+const WALLET_TOPUP_COLUMNS: &str = r#"
+    id, wallet_id,
+    amount::text AS amount,
+    payment_method, payment_status, created_at, paid_at
+"#;
+
+struct LockedWallet {
+    id: Uuid,
+    balance: Decimal,
+}
+
+/// Ambil dompet user dan kunci barisnya (FOR UPDATE) sampai transaksi database selesai.
+/// Dompet dibuat otomatis saat pertama kali dipakai.
+async fn lock_wallet(conn: &mut PgConnection, user_id: Uuid) -> Result<LockedWallet> {
+    sqlx::query("INSERT INTO wallets (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let (id, balance): (Uuid, String) =
+        sqlx::query_as("SELECT id, balance::text FROM wallets WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    Ok(LockedWallet {
+        id,
+        balance: Decimal::from_str(&balance).map_err(|e| AppError::InternalServerError(e.to_string()))?,
+    })
+}
+
+/// Ubah saldo dompet yang sudah dikunci dan catat mutasinya
+async fn post_entry(
+    conn: &mut PgConnection,
+    wallet: LockedWallet,
+    entry_type: WalletEntryType,
+    amount: Decimal,
+    transaction_id: Option<Uuid>,
+    topup_id: Option<Uuid>,
+    description: &str,
+) -> Result<Decimal> {
+    let balance_after = wallet.balance + amount.clone();
+
+    sqlx::query("UPDATE wallets SET balance = $1::numeric WHERE id = $2")
+        .bind(balance_after.to_string())
+        .bind(wallet.id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO wallet_entries (wallet_id, entry_type, amount, balance_after, transaction_id, topup_id, description)
+        VALUES ($1, $2, $3::numeric, $4::numeric, $5, $6, $7)
+        "#,
+    )
+    .bind(wallet.id)
+    .bind(entry_type)
+    .bind(amount.to_string())
+    .bind(balance_after.to_string())
+    .bind(transaction_id)
+    .bind(topup_id)
+    .bind(description)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(balance_after)
+}
+
+/// Bayar transaksi dari saldo dompet. Saldo dikunci selama transaksi database,
+/// sehingga pembayaran paralel tidak bisa membuat saldo minus.
+pub async fn debit_wallet(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    amount: &Decimal,
+) -> Result<Decimal> {
+    let wallet = lock_wallet(conn, user_id).await?;
+    if wallet.balance < *amount {
+        return Err(AppError::BadRequest(format!(
+            "Insufficient wallet balance: {} available, {} required",
+            wallet.balance, amount
+        )));
+    }
+
+    post_entry(
+        conn,
+        wallet,
+        WalletEntryType::Payment,
+        Decimal(-amount.0.clone()),
+        Some(transaction_id),
+        None,
+        "Payment for transaction",
+    )
+    .await
+}
+
+/// Kembalikan dana transaksi ke saldo dompet (apa pun metode pembayarannya)
+pub async fn refund_to_wallet(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    amount: &Decimal,
+    description: &str,
+) -> Result<Decimal> {
+    let wallet = lock_wallet(conn, user_id).await?;
+    post_entry(
+        conn,
+        wallet,
+        WalletEntryType::Refund,
+        amount.clone(),
+        Some(transaction_id),
+        None,
+        description,
+    )
+    .await
+}
+
+// GET /me/wallet
+pub async fn get_my_wallet(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<WalletQuery>,
+) -> Result<Json<WalletResponse>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let balance: Option<String> = sqlx::query_scalar("SELECT balance::text FROM wallets WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?;
+    let balance = match balance {
+        Some(balance) => Decimal::from_str(&balance).map_err(|e| AppError::InternalServerError(e.to_string()))?,
+        None => Decimal::default(),
+    };
+
+    let entries = sqlx::query_as::<_, WalletEntry>(&format!(
+        r#"
+        SELECT {} FROM wallet_entries
+        WHERE wallet_id = (SELECT id FROM wallets WHERE user_id = $1)
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+        WALLET_ENTRY_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(WalletResponse { balance, entries }))
+}
+
+// GET /me/wallet/topups
+pub async fn list_topups(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<WalletTopup>>> {
+    let topups = sqlx::query_as::<_, WalletTopup>(&format!(
+        r#"
+        SELECT {} FROM wallet_topups
+        WHERE wallet_id = (SELECT id FROM wallets WHERE user_id = $1)
+        ORDER BY created_at DESC
+        "#,
+        WALLET_TOPUP_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(topups))
+}
+
+// POST /me/wallet/topups
+pub async fn create_topup(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateTopupRequest>,
+) -> Result<(StatusCode, Json<WalletTopup>)> {
+    if payload.amount.0 < BigDecimal::from(MIN_TOPUP_AMOUNT) || payload.amount.0 > BigDecimal::from(MAX_TOPUP_AMOUNT)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Top-up amount must be between {} and {}",
+            MIN_TOPUP_AMOUNT, MAX_TOPUP_AMOUNT
+        )));
+    }
+    let payment_method = payload.payment_method.trim().to_lowercase();
+    if payment_method.is_empty() {
+        return Err(AppError::BadRequest("payment_method is required".to_string()));
+    }
+    if payment_method == PAYMENT_METHOD_WALLET {
+        return Err(AppError::BadRequest("Wallet cannot be topped up from itself".to_string()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let wallet = lock_wallet(&mut tx, user_id).await?;
+
+    let topup = sqlx::query_as::<_, WalletTopup>(&format!(
+        r#"
+        INSERT INTO wallet_topups (wallet_id, amount, payment_method)
+        VALUES ($1, $2::numeric, $3)
+        RETURNING {}
+        "#,
+        WALLET_TOPUP_COLUMNS
+    ))
+    .bind(wallet.id)
+    .bind(payload.amount.to_string())
+    .bind(payment_method)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(topup)))
+}
+
+// POST /me/wallet/topups/:id/pay
+pub async fn pay_topup(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(topup_id): Path<Uuid>,
+) -> Result<Json<WalletTopup>> {
+    let mut tx = state.db.begin().await?;
+    let wallet = lock_wallet(&mut tx, user_id).await?;
+
+    let topup = sqlx::query_as::<_, WalletTopup>(&format!(
+        "SELECT {} FROM wallet_topups WHERE id = $1 AND wallet_id = $2 FOR UPDATE",
+        WALLET_TOPUP_COLUMNS
+    ))
+    .bind(topup_id)
+    .bind(wallet.id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Top-up not found".to_string()))?;
+
+    if topup.payment_status != PaymentStatus::Pending {
+        return Err(AppError::BadRequest("Only pending top-ups can be paid".to_string()));
+    }
+
+    let status = match payment::charge(&topup.payment_method, &topup.amount).await {
+        PaymentOutcome::Succeeded => {
+            post_entry(
+                &mut tx,
+                wallet,
+                WalletEntryType::Topup,
+                topup.amount.clone(),
+                None,
+                Some(topup.id),
+                &format!("Top-up via {}", topup.payment_method),
+            )
+            .await?;
+            PaymentStatus::Paid
+        }
+        PaymentOutcome::Failed(reason) => {
+            tracing::warn!("Top-up {} failed: {}", topup.id, reason);
+            PaymentStatus::Failed
+        }
+    };
+
+    let topup = sqlx::query_as::<_, WalletTopup>(&format!(
+        r#"
+        UPDATE wallet_topups
+        SET payment_status = $1,
+            paid_at = CASE WHEN $1 = 'paid'::payment_status THEN NOW() END
+        WHERE id = $2
+        RETURNING {}
+        "#,
+        WALLET_TOPUP_COLUMNS
+    ))
+    .bind(status)
+    .bind(topup.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(topup))
+}
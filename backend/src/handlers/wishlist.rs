@@ -1,13 +1,24 @@
 use axum::{
-    extract::{Path, State, Json},
-    http::{StatusCode, HeaderMap},
-    response::IntoResponse,
+    extract::{Path, Query, State, Json},
+    http::StatusCode,
+    Extension,
 };
-use chrono::Utc;
+use chrono::{FixedOffset, Utc};
 use serde::Serialize;
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection, PgExecutor};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+use crate::error::{AppError, Result};
+use crate::geocoding::{haversine_km, Coordinates};
+use crate::handlers::organization::BILLING_UTC_OFFSET_SECS;
+use crate::handlers::spbu::fetch_fuel_prices;
+use crate::handlers::wishlist_collection::find_collection;
+use crate::models::spbu::is_open_at;
+use crate::models::wishlist::{
+    BulkWishlistRequest, ReorderWishlistRequest, UpdateWishlistItemRequest, WishlistItem, WishlistQuery,
+    WishlistSpbuInfo, WishlistSyncRequest,
+};
 use crate::AppState;
 
 #[derive(Debug, Serialize, FromRow)]
@@ -15,6 +26,9 @@ pub struct WishlistResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub spbu_id: Uuid,
+    pub collection_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub position: i32,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
@@ -22,121 +36,618 @@ pub struct WishlistResponse {
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateWishlistRequest {
     pub spbu_id: Uuid,
+    pub collection_id: Option<Uuid>,
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
-pub struct WishlistWithSpbuResponse {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub spbu_id: Uuid,
-    pub spbu_name: String,
-    pub spbu_address: String,
-    pub created_at: chrono::DateTime<Utc>,
-    pub updated_at: chrono::DateTime<Utc>,
+const WISHLIST_COLUMNS: &str = "id, user_id, spbu_id, collection_id, note, position, created_at, updated_at";
+
+// Batas jumlah SPBU dalam satu permintaan bulk/sync
+const MAX_BULK_ITEMS: usize = 500;
+
+#[derive(Debug, Serialize)]
+pub struct BulkAddWishlistResponse {
+    pub added: Vec<WishlistResponse>,
+    pub already_in_wishlist: Vec<Uuid>,
+    pub not_found: Vec<Uuid>,
 }
 
-// Extract user_id from header
-fn get_user_id_from_headers(headers: &axum::http::HeaderMap) -> Result<Uuid, (StatusCode, String)> {
-    headers
-        .get("X-User-Id")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Invalid or missing X-User-Id header".to_string()))
+#[derive(Debug, Serialize)]
+pub struct BulkRemoveWishlistResponse {
+    pub removed: Vec<Uuid>,
+    pub not_in_wishlist: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WishlistSyncResponse {
+    /// Seluruh wishlist setelah digabung; perangkat cukup mengganti data lokalnya dengan ini
+    pub items: Vec<WishlistResponse>,
+    /// SPBU yang perubahan dari perangkat diabaikan karena data server lebih baru
+    pub server_wins: Vec<Uuid>,
+}
+
+// Catatan kosong disimpan sebagai NULL
+fn normalize_note(note: Option<String>) -> Option<String> {
+    note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
 }
 
 // Tambah ke wishlist
 pub async fn add_to_wishlist(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    Extension(user_id): Extension<Uuid>,
     Json(payload): Json<CreateWishlistRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let user_id = get_user_id_from_headers(&headers)?;
-    
+) -> Result<Json<WishlistResponse>> {
     // First check if the SPBU exists
     let spbu_exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM spbu WHERE id = $1)"
     )
     .bind(payload.spbu_id)
     .fetch_one(&state.db)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    .await?;
 
     if !spbu_exists {
-        return Err((StatusCode::NOT_FOUND, "SPBU not found".to_string()));
+        return Err(AppError::NotFound("SPBU not found".to_string()));
+    }
+
+    if let Some(collection_id) = payload.collection_id {
+        find_collection(&state.db, user_id, collection_id).await?;
     }
 
-    let wishlist = sqlx::query_as::<_, WishlistResponse>(
+    let mut tx = state.db.begin().await?;
+
+    // Item baru ditaruh di urutan terakhir
+    let wishlist = sqlx::query_as::<_, WishlistResponse>(&format!(
         r#"
-        INSERT INTO wishlists (user_id, spbu_id)
-        VALUES ($1, $2)
-        RETURNING id, user_id, spbu_id, created_at, updated_at
-        "#
-    )
+        INSERT INTO wishlists (user_id, spbu_id, collection_id, note, position)
+        VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(position) + 1, 0) FROM wishlists WHERE user_id = $1))
+        RETURNING {}
+        "#,
+        WISHLIST_COLUMNS
+    ))
     .bind(user_id)
     .bind(payload.spbu_id)
-    .fetch_one(&state.db)
+    .bind(payload.collection_id)
+    .bind(normalize_note(payload.note))
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| {
-        if e.to_string().contains("duplicate key") {
-            (StatusCode::CONFLICT, "SPBU already in wishlist".to_string())
-        } else {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("SPBU already in wishlist".to_string())
         }
+        e => e.into(),
     })?;
 
+    clear_deletions(&mut tx, user_id, &[payload.spbu_id]).await?;
+    tx.commit().await?;
+
     Ok(Json(wishlist))
 }
 
 // Hapus dari wishlist
+// DELETE /wishlist/:spbu_id
 pub async fn remove_from_wishlist(
     State(state): State<AppState>,
-    headers: HeaderMap,
-    Path(wishlist_id): Path<Uuid>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let user_id = get_user_id_from_headers(&headers)?;
-    
-    let result = sqlx::query(
-        "DELETE FROM wishlists WHERE id = $1 AND user_id = $2"
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let mut tx = state.db.begin().await?;
+
+    let result = sqlx::query("DELETE FROM wishlists WHERE user_id = $1 AND spbu_id = $2")
+        .bind(user_id)
+        .bind(spbu_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("SPBU is not in your wishlist".to_string()));
+    }
+
+    record_deletions(&mut tx, user_id, &[spbu_id]).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Catat waktu penghapusan agar sinkronisasi dari perangkat lain tidak menghidupkannya lagi
+async fn record_deletions(conn: &mut PgConnection, user_id: Uuid, spbu_ids: &[Uuid]) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO wishlist_deletions (user_id, spbu_id)
+        SELECT $1, UNNEST($2::uuid[])
+        ON CONFLICT (user_id, spbu_id) DO UPDATE SET deleted_at = NOW()
+        "#,
     )
-    .bind(wishlist_id)
     .bind(user_id)
-    .execute(&state.db)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    .bind(spbu_ids)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
 
-    if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "Wishlist item not found".to_string()));
+async fn clear_deletions(conn: &mut PgConnection, user_id: Uuid, spbu_ids: &[Uuid]) -> Result<()> {
+    sqlx::query("DELETE FROM wishlist_deletions WHERE user_id = $1 AND spbu_id = ANY($2)")
+        .bind(user_id)
+        .bind(spbu_ids)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+async fn list_wishlists<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid) -> Result<Vec<WishlistResponse>> {
+    let wishlists = sqlx::query_as::<_, WishlistResponse>(&format!(
+        "SELECT {} FROM wishlists WHERE user_id = $1 ORDER BY position, created_at",
+        WISHLIST_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(executor)
+    .await?;
+    Ok(wishlists)
+}
+
+fn validate_bulk_ids(spbu_ids: &[Uuid]) -> Result<()> {
+    if spbu_ids.is_empty() {
+        return Err(AppError::BadRequest("spbu_ids must not be empty".to_string()));
+    }
+    if spbu_ids.len() > MAX_BULK_ITEMS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} SPBU per request",
+            MAX_BULK_ITEMS
+        )));
     }
+    let unique: HashSet<&Uuid> = spbu_ids.iter().collect();
+    if unique.len() != spbu_ids.len() {
+        return Err(AppError::BadRequest("spbu_ids must not contain duplicates".to_string()));
+    }
+    Ok(())
+}
+
+// POST /wishlist/bulk-add
+// SPBU yang sudah ada di wishlist atau tidak ditemukan dilewati, bukan membatalkan semuanya
+pub async fn bulk_add_to_wishlist(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<BulkWishlistRequest>,
+) -> Result<Json<BulkAddWishlistResponse>> {
+    validate_bulk_ids(&payload.spbu_ids)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let existing: HashSet<Uuid> = sqlx::query_scalar("SELECT id FROM spbu WHERE id = ANY($1)")
+        .bind(&payload.spbu_ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+    // Ditambahkan di akhir wishlist sesuai urutan permintaan
+    let added = sqlx::query_as::<_, WishlistResponse>(&format!(
+        r#"
+        INSERT INTO wishlists (user_id, spbu_id, position)
+        SELECT $1, t.spbu_id,
+               (SELECT COALESCE(MAX(position) + 1, 0) FROM wishlists WHERE user_id = $1) + t.ord::int - 1
+        FROM UNNEST($2::uuid[]) WITH ORDINALITY AS t(spbu_id, ord)
+        JOIN spbu s ON s.id = t.spbu_id
+        ON CONFLICT (user_id, spbu_id) DO NOTHING
+        RETURNING {}
+        "#,
+        WISHLIST_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(&payload.spbu_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let added_ids: Vec<Uuid> = added.iter().map(|w| w.spbu_id).collect();
+    clear_deletions(&mut tx, user_id, &added_ids).await?;
+    tx.commit().await?;
+
+    let mut added = added;
+    added.sort_by_key(|w| w.position);
+
+    let (already_in_wishlist, not_found) = payload
+        .spbu_ids
+        .iter()
+        .filter(|id| !added_ids.contains(id))
+        .partition(|id| existing.contains(id));
 
-    Ok((StatusCode::NO_CONTENT, ()))
+    Ok(Json(BulkAddWishlistResponse {
+        added,
+        already_in_wishlist,
+        not_found,
+    }))
 }
 
-// Lihat daftar wishlist user
-pub async fn get_user_wishlists(
+// POST /wishlist/bulk-remove
+pub async fn bulk_remove_from_wishlist(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<BulkWishlistRequest>,
+) -> Result<Json<BulkRemoveWishlistResponse>> {
+    validate_bulk_ids(&payload.spbu_ids)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let removed: Vec<Uuid> = sqlx::query_scalar(
+        "DELETE FROM wishlists WHERE user_id = $1 AND spbu_id = ANY($2) RETURNING spbu_id",
+    )
+    .bind(user_id)
+    .bind(&payload.spbu_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    record_deletions(&mut tx, user_id, &removed).await?;
+    tx.commit().await?;
+
+    // Ikuti urutan permintaan
+    let (removed, not_in_wishlist) = payload.spbu_ids.iter().partition(|id| removed.contains(id));
+
+    Ok(Json(BulkRemoveWishlistResponse {
+        removed,
+        not_in_wishlist,
+    }))
+}
+
+#[derive(FromRow)]
+struct SyncState {
+    spbu_id: Uuid,
+    id: Option<Uuid>,
+    updated_at: Option<chrono::DateTime<Utc>>,
+    deleted_at: Option<chrono::DateTime<Utc>>,
+    spbu_exists: bool,
+}
+
+// POST /wishlist/sync
+// Gabungkan wishlist offline perangkat dengan server. Untuk tiap SPBU, perubahan yang paling
+// baru (`updated_at` perangkat vs waktu ubah/hapus di server) yang dipakai; jika sama, server menang.
+pub async fn sync_wishlist(
     State(state): State<AppState>,
-    headers: HeaderMap,
-) -> Result<Json<Vec<WishlistWithSpbuResponse>>, (StatusCode, String)> {
-    let user_id = get_user_id_from_headers(&headers)?;
-    
-    let wishlists = sqlx::query_as::<_, WishlistWithSpbuResponse>(
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<WishlistSyncRequest>,
+) -> Result<Json<WishlistSyncResponse>> {
+    let spbu_ids: Vec<Uuid> = payload.items.iter().map(|item| item.spbu_id).collect();
+    if spbu_ids.len() > MAX_BULK_ITEMS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} SPBU per request",
+            MAX_BULK_ITEMS
+        )));
+    }
+    if spbu_ids.iter().collect::<HashSet<_>>().len() != spbu_ids.len() {
+        return Err(AppError::BadRequest("Each spbu_id may appear only once".to_string()));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Kunci baris user agar sinkronisasi paralel dari dua perangkat tidak saling menimpa
+    sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let collection_ids: Vec<Uuid> = payload
+        .items
+        .iter()
+        .filter(|item| !item.deleted)
+        .filter_map(|item| item.collection_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    for collection_id in collection_ids {
+        find_collection(&mut *tx, user_id, collection_id).await.map_err(|e| match e {
+            AppError::NotFound(_) => AppError::BadRequest(format!("Collection {} not found", collection_id)),
+            e => e,
+        })?;
+    }
+
+    let states: HashMap<Uuid, SyncState> = sqlx::query_as::<_, SyncState>(
         r#"
-        SELECT 
-            w.id, 
-            w.user_id, 
-            w.spbu_id, 
-            s.nama as spbu_name,
-            s.alamat as spbu_address,
-            w.created_at, 
-            w.updated_at
+        SELECT t.spbu_id, w.id, w.updated_at, d.deleted_at,
+               EXISTS(SELECT 1 FROM spbu s WHERE s.id = t.spbu_id) AS spbu_exists
+        FROM UNNEST($2::uuid[]) AS t(spbu_id)
+        LEFT JOIN wishlists w ON w.user_id = $1 AND w.spbu_id = t.spbu_id
+        LEFT JOIN wishlist_deletions d ON d.user_id = $1 AND d.spbu_id = t.spbu_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(&spbu_ids)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|state| (state.spbu_id, state))
+    .collect();
+
+    let mut server_wins = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    for item in payload.items {
+        let Some(current) = states.get(&item.spbu_id) else {
+            continue;
+        };
+        match (current.id, current.updated_at) {
+            (Some(_), Some(updated_at)) if item.updated_at <= updated_at => server_wins.push(item.spbu_id),
+            (Some(id), _) if item.deleted => {
+                sqlx::query("DELETE FROM wishlists WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                removed.push(item.spbu_id);
+            }
+            (Some(id), _) => {
+                sqlx::query("UPDATE wishlists SET collection_id = $2, note = $3 WHERE id = $1")
+                    .bind(id)
+                    .bind(item.collection_id)
+                    .bind(normalize_note(item.note))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            // Sudah sama-sama tidak ada di wishlist
+            (None, _) if item.deleted => {}
+            (None, _) if !current.spbu_exists => server_wins.push(item.spbu_id),
+            (None, _) if current.deleted_at.is_some_and(|deleted_at| item.updated_at <= deleted_at) => {
+                server_wins.push(item.spbu_id)
+            }
+            (None, _) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO wishlists (user_id, spbu_id, collection_id, note, position)
+                    VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(position) + 1, 0) FROM wishlists WHERE user_id = $1))
+                    "#,
+                )
+                .bind(user_id)
+                .bind(item.spbu_id)
+                .bind(item.collection_id)
+                .bind(normalize_note(item.note))
+                .execute(&mut *tx)
+                .await?;
+                added.push(item.spbu_id);
+            }
+        }
+    }
+
+    record_deletions(&mut tx, user_id, &removed).await?;
+    clear_deletions(&mut tx, user_id, &added).await?;
+
+    let items = list_wishlists(&mut *tx, user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(WishlistSyncResponse { items, server_wins }))
+}
+
+#[derive(FromRow)]
+struct WishlistRow {
+    id: Uuid,
+    user_id: Uuid,
+    collection_id: Option<Uuid>,
+    note: Option<String>,
+    position: i32,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+    spbu_id: Uuid,
+    spbu_name: String,
+    spbu_address: Option<String>,
+    brand_id: Option<Uuid>,
+    rating: Option<f64>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    jumlah_antrian: Option<i32>,
+    buka_24_jam: Option<bool>,
+    jam_buka: Option<chrono::NaiveTime>,
+    jam_tutup: Option<chrono::NaiveTime>,
+}
+
+/// Lokasi user dari query `lat`/`lng`; keduanya harus diisi bersamaan
+pub fn query_origin(query: &WishlistQuery) -> Result<Option<Coordinates>> {
+    match (query.lat, query.lng) {
+        (None, None) => Ok(None),
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Ok(Some(Coordinates { latitude, longitude }))
+        }
+        (Some(_), Some(_)) => Err(AppError::BadRequest("Invalid lat/lng".to_string())),
+        _ => Err(AppError::BadRequest("lat and lng must be provided together".to_string())),
+    }
+}
+
+/// Item wishlist beserta rating, harga, status buka dan jarak SPBU saat ini,
+/// urut sesuai posisi yang diatur user
+pub async fn load_wishlist_items(
+    state: &AppState,
+    user_id: Option<Uuid>,
+    collection_id: Option<Uuid>,
+    origin: Option<Coordinates>,
+) -> Result<Vec<WishlistItem>> {
+    let rows = sqlx::query_as::<_, WishlistRow>(
+        r#"
+        SELECT
+            w.id, w.user_id, w.collection_id, w.note, w.position, w.created_at, w.updated_at,
+            w.spbu_id,
+            s.nama AS spbu_name,
+            s.alamat AS spbu_address,
+            s.brand_id, s.rating, s.latitude, s.longitude, s.jumlah_antrian,
+            s.buka_24_jam, s.jam_buka, s.jam_tutup
         FROM wishlists w
         JOIN spbu s ON w.spbu_id = s.id
-        WHERE w.user_id = $1
+        WHERE ($1::uuid IS NULL OR w.user_id = $1)
+          AND ($2::uuid IS NULL OR w.collection_id = $2)
+        ORDER BY w.position, w.created_at
         "#
     )
     .bind(user_id)
+    .bind(collection_id)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    .await?;
+
+    let spbu_ids: Vec<Uuid> = rows.iter().map(|row| row.spbu_id).collect();
+    let mut prices = fetch_fuel_prices(state, &spbu_ids).await?;
+
+    // Jam operasional SPBU dalam WIB
+    let offset = FixedOffset::east_opt(BILLING_UTC_OFFSET_SECS).expect("valid UTC offset");
+    let now = Utc::now().with_timezone(&offset).time();
+
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let distance_km = match (origin, row.latitude, row.longitude) {
+                (Some(origin), Some(latitude), Some(longitude)) => {
+                    Some(haversine_km(origin, Coordinates { latitude, longitude }))
+                }
+                _ => None,
+            };
+            WishlistItem {
+                id: row.id,
+                user_id: row.user_id,
+                collection_id: row.collection_id,
+                note: row.note,
+                position: row.position,
+                spbu: WishlistSpbuInfo {
+                    spbu_id: row.spbu_id,
+                    spbu_name: row.spbu_name,
+                    spbu_address: row.spbu_address,
+                    brand_id: row.brand_id,
+                    rating: row.rating,
+                    latitude: row.latitude,
+                    longitude: row.longitude,
+                    jumlah_antrian: row.jumlah_antrian,
+                    is_open: is_open_at(row.buka_24_jam, row.jam_buka, row.jam_tutup, now),
+                    fuel_prices: prices.remove(&row.spbu_id).unwrap_or_default(),
+                    distance_km,
+                },
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            }
+        })
+        .collect();
+
+    Ok(items)
+}
+
+// Lihat daftar wishlist user
+// GET /wishlist?collection_id=...&lat=...&lng=...
+pub async fn get_user_wishlists(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<WishlistQuery>,
+) -> Result<Json<Vec<WishlistItem>>> {
+    let origin = query_origin(&query)?;
+    if let Some(collection_id) = query.collection_id {
+        find_collection(&state.db, user_id, collection_id).await?;
+    }
+
+    let items = load_wishlist_items(&state, Some(user_id), query.collection_id, origin).await?;
+    Ok(Json(items))
+}
+
+async fn fetch_wishlist<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid, spbu_id: Uuid) -> Result<WishlistResponse> {
+    sqlx::query_as::<_, WishlistResponse>(&format!(
+        "SELECT {} FROM wishlists WHERE user_id = $1 AND spbu_id = $2",
+        WISHLIST_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(spbu_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("SPBU is not in your wishlist".to_string()))
+}
+
+// PUT /wishlist/:spbu_id
+// Ubah catatan dan/atau koleksi item
+pub async fn update_wishlist_item(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<UpdateWishlistItemRequest>,
+) -> Result<Json<WishlistResponse>> {
+    let current = fetch_wishlist(&state.db, user_id, spbu_id).await?;
+
+    let collection_id = match payload.collection_id {
+        Some(Some(collection_id)) => {
+            find_collection(&state.db, user_id, collection_id).await?;
+            Some(collection_id)
+        }
+        Some(None) => None,
+        None => current.collection_id,
+    };
+    let note = match payload.note {
+        Some(note) => normalize_note(note),
+        None => current.note,
+    };
+
+    let wishlist = sqlx::query_as::<_, WishlistResponse>(&format!(
+        "UPDATE wishlists SET collection_id = $2, note = $3 WHERE id = $1 RETURNING {}",
+        WISHLIST_COLUMNS
+    ))
+    .bind(current.id)
+    .bind(collection_id)
+    .bind(note)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(wishlist))
+}
+
+// PUT /wishlist/order
+// Posisi SPBU yang dikirim ditukar sesuai urutan permintaan, sehingga urutan di dalam satu
+// koleksi bisa diatur tanpa memindahkan item koleksi lain
+pub async fn reorder_wishlist(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<ReorderWishlistRequest>,
+) -> Result<Json<Vec<WishlistResponse>>> {
+    let mut spbu_ids = payload.spbu_ids.clone();
+    spbu_ids.sort();
+    spbu_ids.dedup();
+    if spbu_ids.len() != payload.spbu_ids.len() {
+        return Err(AppError::BadRequest("spbu_ids must not contain duplicates".to_string()));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Rapikan posisi menjadi 0..n agar tidak ada posisi kembar
+    sqlx::query(
+        r#"
+        UPDATE wishlists w
+        SET position = ordered.rn
+        FROM (
+            SELECT id, (ROW_NUMBER() OVER (ORDER BY position, created_at, id) - 1)::int AS rn
+            FROM wishlists
+            WHERE user_id = $1
+        ) ordered
+        WHERE w.id = ordered.id AND w.position <> ordered.rn
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let slots: Vec<i32> = sqlx::query_scalar(
+        "SELECT position FROM wishlists WHERE user_id = $1 AND spbu_id = ANY($2) ORDER BY position",
+    )
+    .bind(user_id)
+    .bind(&payload.spbu_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if slots.len() != payload.spbu_ids.len() {
+        return Err(AppError::BadRequest("All spbu_ids must be in your wishlist".to_string()));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE wishlists w
+        SET position = target.position
+        FROM UNNEST($2::uuid[], $3::int[]) AS target(spbu_id, position)
+        WHERE w.user_id = $1 AND w.spbu_id = target.spbu_id AND w.position <> target.position
+        "#,
+    )
+    .bind(user_id)
+    .bind(&payload.spbu_ids)
+    .bind(&slots)
+    .execute(&mut *tx)
+    .await?;
+
+    let wishlists = list_wishlists(&mut *tx, user_id).await?;
+    tx.commit().await?;
 
     Ok(Json(wishlists))
 }
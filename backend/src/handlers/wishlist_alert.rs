@@ -0,0 +1,355 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::{PgConnection, Row};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::operator::normalize_fuel_type;
+use crate::jobs::env_u64;
+use crate::models::notification::NotificationEvent;
+use crate::models::wishlist::{
+    CreateWishlistAlertRequest, UpdateWishlistAlertRequest, WishlistAlert, WishlistAlertKind,
+};
+use crate::notifications::{notify, trim_zeros};
+use crate::types::Decimal;
+use crate::AppState;
+
+const WISHLIST_ALERT_COLUMNS: &str = r#"
+    a.id, w.spbu_id, a.kind, a.fuel_type, a.threshold::text AS threshold, a.is_active,
+    a.triggered, a.last_triggered_at, a.created_at, a.updated_at
+"#;
+
+// Batas jumlah aturan per SPBU di wishlist
+const MAX_ALERTS_PER_WISHLIST: i64 = 10;
+
+async fn find_wishlist_id(conn: &mut PgConnection, user_id: Uuid, spbu_id: Uuid) -> Result<Uuid> {
+    sqlx::query_scalar("SELECT id FROM wishlists WHERE user_id = $1 AND spbu_id = $2")
+        .bind(user_id)
+        .bind(spbu_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("SPBU is not in your wishlist".to_string()))
+}
+
+fn validate_threshold(kind: WishlistAlertKind, threshold: Option<&Decimal>) -> Result<()> {
+    match (kind, threshold) {
+        (WishlistAlertKind::BackInStock, Some(_)) => Err(AppError::BadRequest(
+            "threshold is not used for back_in_stock alerts".to_string(),
+        )),
+        (WishlistAlertKind::BackInStock, None) => Ok(()),
+        (_, None) => Err(AppError::BadRequest("threshold is required".to_string())),
+        (_, Some(threshold)) if *threshold <= Decimal::default() => {
+            Err(AppError::BadRequest("threshold must be greater than 0".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// GET /wishlist/:spbu_id/alerts
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+) -> Result<Json<Vec<WishlistAlert>>> {
+    let mut conn = state.db.acquire().await?;
+    let wishlist_id = find_wishlist_id(&mut conn, user_id, spbu_id).await?;
+
+    let alerts = sqlx::query_as::<_, WishlistAlert>(&format!(
+        r#"
+        SELECT {}
+        FROM wishlist_alerts a
+        JOIN wishlists w ON w.id = a.wishlist_id
+        WHERE a.wishlist_id = $1
+        ORDER BY a.created_at
+        "#,
+        WISHLIST_ALERT_COLUMNS
+    ))
+    .bind(wishlist_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(alerts))
+}
+
+// POST /wishlist/:spbu_id/alerts
+pub async fn create_alert(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(spbu_id): Path<Uuid>,
+    Json(payload): Json<CreateWishlistAlertRequest>,
+) -> Result<(StatusCode, Json<WishlistAlert>)> {
+    let fuel_type = match (payload.kind, payload.fuel_type.as_deref()) {
+        (WishlistAlertKind::QueueBelow, Some(_)) => {
+            return Err(AppError::BadRequest(
+                "fuel_type is not used for queue_below alerts".to_string(),
+            ))
+        }
+        (WishlistAlertKind::QueueBelow, None) => None,
+        (_, Some(fuel_type)) => Some(normalize_fuel_type(fuel_type)?),
+        (_, None) => return Err(AppError::BadRequest("fuel_type is required".to_string())),
+    };
+    validate_threshold(payload.kind, payload.threshold.as_ref())?;
+
+    let mut tx = state.db.begin().await?;
+    let wishlist_id = find_wishlist_id(&mut tx, user_id, spbu_id).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM wishlist_alerts WHERE wishlist_id = $1")
+        .bind(wishlist_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if count >= MAX_ALERTS_PER_WISHLIST {
+        return Err(AppError::BadRequest(format!(
+            "An SPBU can have at most {} alerts",
+            MAX_ALERTS_PER_WISHLIST
+        )));
+    }
+
+    let alert_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO wishlist_alerts (wishlist_id, kind, fuel_type, threshold)
+        VALUES ($1, $2, $3, $4::numeric)
+        RETURNING id
+        "#,
+    )
+    .bind(wishlist_id)
+    .bind(payload.kind)
+    .bind(fuel_type)
+    .bind(payload.threshold.map(|t| t.to_string()))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let alert = sync_triggered(&mut tx, alert_id).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(alert)))
+}
+
+// PUT /wishlist/:spbu_id/alerts/:alert_id
+pub async fn update_alert(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, alert_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateWishlistAlertRequest>,
+) -> Result<Json<WishlistAlert>> {
+    let mut tx = state.db.begin().await?;
+    let wishlist_id = find_wishlist_id(&mut tx, user_id, spbu_id).await?;
+
+    let kind: WishlistAlertKind =
+        sqlx::query_scalar("SELECT kind FROM wishlist_alerts WHERE id = $1 AND wishlist_id = $2 FOR UPDATE")
+            .bind(alert_id)
+            .bind(wishlist_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Alert not found".to_string()))?;
+
+    if payload.threshold.is_some() {
+        validate_threshold(kind, payload.threshold.as_ref())?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE wishlist_alerts
+        SET threshold = COALESCE($2::numeric, threshold),
+            is_active = COALESCE($3, is_active)
+        WHERE id = $1
+        "#,
+    )
+    .bind(alert_id)
+    .bind(payload.threshold.map(|t| t.to_string()))
+    .bind(payload.is_active)
+    .execute(&mut *tx)
+    .await?;
+
+    let alert = sync_triggered(&mut tx, alert_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(alert))
+}
+
+// DELETE /wishlist/:spbu_id/alerts/:alert_id
+pub async fn delete_alert(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path((spbu_id, alert_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let mut conn = state.db.acquire().await?;
+    let wishlist_id = find_wishlist_id(&mut conn, user_id, spbu_id).await?;
+
+    let result = sqlx::query("DELETE FROM wishlist_alerts WHERE id = $1 AND wishlist_id = $2")
+        .bind(alert_id)
+        .bind(wishlist_id)
+        .execute(&mut *conn)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Alert not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Aturan beserta data SPBU terkini yang dibutuhkan untuk mengevaluasinya
+struct AlertState {
+    id: Uuid,
+    user_id: Uuid,
+    spbu_id: Uuid,
+    spbu_name: String,
+    kind: WishlistAlertKind,
+    fuel_type: Option<String>,
+    threshold: Option<Decimal>,
+    triggered: bool,
+    last_triggered_at: Option<chrono::DateTime<Utc>>,
+    fuel_sold: bool,
+    price: Option<Decimal>,
+    stock_liters: Option<Decimal>,
+    queue: i32,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AlertState {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> std::result::Result<Self, sqlx::Error> {
+        let decimal = |column: &str| -> std::result::Result<Option<Decimal>, sqlx::Error> {
+            let value: Option<String> = row.try_get(column)?;
+            value
+                .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+                .transpose()
+        };
+        Ok(AlertState {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            spbu_name: row.try_get("spbu_name")?,
+            kind: row.try_get("kind")?,
+            fuel_type: row.try_get("fuel_type")?,
+            threshold: decimal("threshold")?,
+            triggered: row.try_get("triggered")?,
+            last_triggered_at: row.try_get("last_triggered_at")?,
+            fuel_sold: row.try_get("fuel_sold")?,
+            price: decimal("price")?,
+            stock_liters: decimal("stock_liters")?,
+            queue: row.try_get("queue")?,
+        })
+    }
+}
+
+impl AlertState {
+    fn condition_met(&self) -> bool {
+        match (self.kind, &self.threshold) {
+            (WishlistAlertKind::PriceBelow, Some(threshold)) => {
+                self.fuel_sold && self.price.as_ref().is_some_and(|price| price < threshold)
+            }
+            // Stok yang tidak dipantau (NULL) dianggap tersedia
+            (WishlistAlertKind::BackInStock, _) => {
+                self.fuel_sold && self.stock_liters.as_ref().is_none_or(|stock| *stock > Decimal::default())
+            }
+            (WishlistAlertKind::QueueBelow, Some(threshold)) => Decimal::from(self.queue) < *threshold,
+            _ => false,
+        }
+    }
+
+    fn event(&self) -> NotificationEvent {
+        match self.kind {
+            WishlistAlertKind::PriceBelow => NotificationEvent::WishlistPriceDrop,
+            WishlistAlertKind::BackInStock => NotificationEvent::WishlistBackInStock,
+            WishlistAlertKind::QueueBelow => NotificationEvent::WishlistQueueShort,
+        }
+    }
+}
+
+async fn load_alert_states(conn: &mut PgConnection, filter: &str, id: Uuid) -> Result<Vec<AlertState>> {
+    let states = sqlx::query_as::<_, AlertState>(&format!(
+        r#"
+        SELECT a.id, w.user_id, w.spbu_id, s.nama AS spbu_name, a.kind, a.fuel_type,
+               a.threshold::text AS threshold, a.triggered, a.last_triggered_at,
+               fp.id IS NOT NULL AS fuel_sold,
+               fp.price::text AS price,
+               fp.stock_liters::text AS stock_liters,
+               COALESCE(s.jumlah_antrian, 0) AS queue
+        FROM wishlist_alerts a
+        JOIN wishlists w ON w.id = a.wishlist_id
+        JOIN spbu s ON s.id = w.spbu_id
+        LEFT JOIN fuel_prices fp ON fp.spbu_id = w.spbu_id AND fp.fuel_type = a.fuel_type
+        WHERE {}
+        ORDER BY a.id
+        FOR UPDATE OF a
+        "#,
+        filter
+    ))
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(states)
+}
+
+// Setelah aturan dibuat atau diubah, kondisi yang sudah terpenuhi tidak dikirim sebagai
+// notifikasi; notifikasi baru dikirim saat kondisinya berubah setelah ini.
+async fn sync_triggered(conn: &mut PgConnection, alert_id: Uuid) -> Result<WishlistAlert> {
+    if let Some(state) = load_alert_states(conn, "a.id = $1", alert_id).await?.pop() {
+        sqlx::query("UPDATE wishlist_alerts SET triggered = $2 WHERE id = $1 AND triggered <> $2")
+            .bind(alert_id)
+            .bind(state.condition_met())
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let alert = sqlx::query_as::<_, WishlistAlert>(&format!(
+        "SELECT {} FROM wishlist_alerts a JOIN wishlists w ON w.id = a.wishlist_id WHERE a.id = $1",
+        WISHLIST_ALERT_COLUMNS
+    ))
+    .bind(alert_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(alert)
+}
+
+/// Evaluasi semua aturan aktif untuk SPBU setelah harga, stok atau antriannya berubah.
+/// Notifikasi dikirim saat kondisi berubah dari tidak terpenuhi menjadi terpenuhi, dan paling
+/// sering sekali per `WISHLIST_ALERT_COOLDOWN_MINS` (default 60) per aturan supaya nilai yang
+/// naik-turun di sekitar threshold tidak membanjiri user. Mengembalikan jumlah notifikasi.
+pub async fn evaluate_spbu_alerts(conn: &mut PgConnection, spbu_id: Uuid) -> Result<u64> {
+    let cooldown = Duration::minutes(env_u64("WISHLIST_ALERT_COOLDOWN_MINS", 60) as i64);
+    let now = Utc::now();
+    let mut sent = 0;
+
+    for state in load_alert_states(conn, "w.spbu_id = $1 AND a.is_active", spbu_id).await? {
+        let met = state.condition_met();
+        if met == state.triggered {
+            continue;
+        }
+
+        let send = met && state.last_triggered_at.is_none_or(|at| at + cooldown <= now);
+        sqlx::query(
+            r#"
+            UPDATE wishlist_alerts
+            SET triggered = $2,
+                last_triggered_at = CASE WHEN $3 THEN NOW() ELSE last_triggered_at END
+            WHERE id = $1
+            "#,
+        )
+        .bind(state.id)
+        .bind(met)
+        .bind(send)
+        .execute(&mut *conn)
+        .await?;
+
+        if send {
+            let data = json!({
+                "alert_id": state.id,
+                "spbu_id": state.spbu_id,
+                "spbu_name": state.spbu_name,
+                "fuel_type": state.fuel_type,
+                "price": state.price.as_ref().map(|p| p.to_string()),
+                "threshold": state.threshold.as_ref().map(trim_zeros),
+                "queue": state.queue,
+            });
+            notify(&mut *conn, state.user_id, state.event(), data).await?;
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
@@ -0,0 +1,233 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use rand_core::{OsRng, RngCore};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::handlers::wishlist::{load_wishlist_items, query_origin};
+use crate::models::wishlist::{
+    SharedWishlist, SharedWishlistItem, WishlistCollection, WishlistCollectionRequest, WishlistQuery,
+};
+use crate::AppState;
+
+const MAX_NAME_LENGTH: usize = 100;
+
+const COLLECTION_COLUMNS: &str = r#"
+    c.id, c.name, c.share_token,
+    (SELECT COUNT(*) FROM wishlists w WHERE w.collection_id = c.id) AS item_count,
+    c.created_at, c.updated_at
+"#;
+
+/// Pastikan koleksi milik user
+pub async fn find_collection<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid, collection_id: Uuid) -> Result<()> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM wishlist_collections WHERE id = $1 AND user_id = $2)")
+            .bind(collection_id)
+            .bind(user_id)
+            .fetch_one(executor)
+            .await?;
+    if !exists {
+        return Err(AppError::NotFound("Collection not found".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name is required".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::BadRequest(format!(
+            "name must be at most {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn duplicate_name(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("duplicate key") => {
+            AppError::Conflict("A collection with this name already exists".to_string())
+        }
+        e => e.into(),
+    }
+}
+
+async fn fetch_collection<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid, collection_id: Uuid) -> Result<WishlistCollection> {
+    sqlx::query_as::<_, WishlistCollection>(&format!(
+        "SELECT {} FROM wishlist_collections c WHERE c.id = $1 AND c.user_id = $2",
+        COLLECTION_COLUMNS
+    ))
+    .bind(collection_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))
+}
+
+// GET /me/wishlist-collections
+pub async fn list_collections(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Vec<WishlistCollection>>> {
+    let collections = sqlx::query_as::<_, WishlistCollection>(&format!(
+        "SELECT {} FROM wishlist_collections c WHERE c.user_id = $1 ORDER BY c.created_at",
+        COLLECTION_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(collections))
+}
+
+// POST /me/wishlist-collections
+pub async fn create_collection(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<WishlistCollectionRequest>,
+) -> Result<(StatusCode, Json<WishlistCollection>)> {
+    let name = validate_name(&payload.name)?;
+
+    let collection_id: Uuid =
+        sqlx::query_scalar("INSERT INTO wishlist_collections (user_id, name) VALUES ($1, $2) RETURNING id")
+            .bind(user_id)
+            .bind(name)
+            .fetch_one(&state.db)
+            .await
+            .map_err(duplicate_name)?;
+
+    let collection = fetch_collection(&state.db, user_id, collection_id).await?;
+    Ok((StatusCode::CREATED, Json(collection)))
+}
+
+// PUT /me/wishlist-collections/:id
+pub async fn rename_collection(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(collection_id): Path<Uuid>,
+    Json(payload): Json<WishlistCollectionRequest>,
+) -> Result<Json<WishlistCollection>> {
+    let name = validate_name(&payload.name)?;
+
+    let result = sqlx::query("UPDATE wishlist_collections SET name = $3 WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .bind(name)
+        .execute(&state.db)
+        .await
+        .map_err(duplicate_name)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Collection not found".to_string()));
+    }
+
+    Ok(Json(fetch_collection(&state.db, user_id, collection_id).await?))
+}
+
+// DELETE /me/wishlist-collections/:id
+// SPBU di dalamnya tetap ada di wishlist, hanya dikeluarkan dari koleksi
+pub async fn delete_collection(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(collection_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let result = sqlx::query("DELETE FROM wishlist_collections WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Collection not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /me/wishlist-collections/:id/share
+// Buat link baca-saja; token lama tetap dipakai jika koleksi sudah dibagikan
+pub async fn share_collection(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(collection_id): Path<Uuid>,
+) -> Result<Json<WishlistCollection>> {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+
+    let result = sqlx::query(
+        r#"
+        UPDATE wishlist_collections
+        SET share_token = COALESCE(share_token, $3)
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(collection_id)
+    .bind(user_id)
+    .bind(hex::encode(bytes))
+    .execute(&state.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Collection not found".to_string()));
+    }
+
+    Ok(Json(fetch_collection(&state.db, user_id, collection_id).await?))
+}
+
+// DELETE /me/wishlist-collections/:id/share
+// Link yang sudah dibagikan tidak bisa dibuka lagi
+pub async fn unshare_collection(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Path(collection_id): Path<Uuid>,
+) -> Result<Json<WishlistCollection>> {
+    let result = sqlx::query("UPDATE wishlist_collections SET share_token = NULL WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Collection not found".to_string()));
+    }
+
+    Ok(Json(fetch_collection(&state.db, user_id, collection_id).await?))
+}
+
+// GET /shared/wishlists/:token?lat=...&lng=...
+// Tanpa login; tidak menampilkan pemilik dan catatan pribadinya
+pub async fn get_shared_collection(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(query): Query<WishlistQuery>,
+) -> Result<Json<SharedWishlist>> {
+    let origin = query_origin(&query)?;
+
+    let (collection_id, name, updated_at): (Uuid, String, chrono::DateTime<chrono::Utc>) =
+        sqlx::query_as("SELECT id, name, updated_at FROM wishlist_collections WHERE share_token = $1")
+            .bind(&token)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Shared wishlist not found".to_string()))?;
+
+    let items = load_wishlist_items(&state, None, Some(collection_id), origin)
+        .await?
+        .into_iter()
+        .map(|item| SharedWishlistItem {
+            position: item.position,
+            spbu: item.spbu,
+        })
+        .collect();
+
+    Ok(Json(SharedWishlist {
+        name,
+        updated_at,
+        items,
+    }))
+}
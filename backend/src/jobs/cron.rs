@@ -0,0 +1,223 @@
+//! Parser ekspresi cron 5 kolom (`menit jam tanggal bulan hari`) dalam UTC.
+//!
+//! Mendukung `*`, angka, rentang `a-b`, langkah `*/n` atau `a-b/n`, dan daftar `a,b,c`.
+//! Hari minggu boleh ditulis 0 atau 7. Seperti cron biasa, jika kolom tanggal dan hari
+//! sama-sama dibatasi maka cukup salah satunya yang cocok.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronParseError(String);
+
+impl fmt::Display for CronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid cron expression: {}", self.0)
+    }
+}
+
+// Bitmask nilai yang cocok untuk satu kolom
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, CronParseError> {
+    let invalid = || CronParseError(format!("'{}' (allowed {}-{})", field, min, max));
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse().map_err(|_| invalid())?,
+                end.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value: u32 = range.parse().map_err(|_| invalid())?;
+            // `5/15` berarti mulai dari 5 sampai batas atas
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(CronParseError(format!("'{}' must have 5 fields", expression)));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        // 7 = minggu, sama dengan 0
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            days_of_month_restricted: day_of_month != "*",
+            days_of_week_restricted: day_of_week != "*",
+        })
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+impl CronSchedule {
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.days_of_month_restricted, self.days_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    /// Waktu pertama yang cocok setelah `after` (tidak termasuk menit `after` sendiri).
+    /// `None` jika tidak ada yang cocok dalam 5 tahun, mis. `0 0 31 2 *`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.naive_utc().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit_year = start.year() + 5;
+        let mut t = start;
+
+        while t.year() <= limit_year {
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = start_of_hour(t)? + Duration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t.and_utc());
+        }
+
+        None
+    }
+}
+
+fn start_of_hour(t: NaiveDateTime) -> Option<NaiveDateTime> {
+    t.with_minute(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        expression.parse::<CronSchedule>().unwrap().next_after(after)
+    }
+
+    #[test]
+    fn parse_field_supports_ranges_steps_and_lists() {
+        assert_eq!(parse_field("*", 0, 3), Ok(0b1111));
+        assert_eq!(parse_field("5", 0, 59), Ok(1 << 5));
+        assert_eq!(parse_field("1-3", 0, 59), Ok(0b1110));
+        assert_eq!(parse_field("*/15", 0, 59), Ok(1 | 1 << 15 | 1 << 30 | 1 << 45));
+        assert_eq!(parse_field("10-20/5", 0, 59), Ok(1 << 10 | 1 << 15 | 1 << 20));
+        assert_eq!(parse_field("50/5", 0, 59), Ok(1 << 50 | 1 << 55));
+        assert_eq!(parse_field("1,4,7", 1, 12), Ok(1 << 1 | 1 << 4 | 1 << 7));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "1,,2 * * * *",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_err(), "{} should be rejected", expression);
+        }
+    }
+
+    #[test]
+    fn sunday_can_be_written_as_zero_or_seven() {
+        assert_eq!("0 0 * * 0".parse::<CronSchedule>(), "0 0 * * 7".parse::<CronSchedule>());
+    }
+
+    #[test]
+    fn next_after_skips_current_minute() {
+        assert_eq!(next("* * * * *", at(2025, 1, 1, 10, 30)), Some(at(2025, 1, 1, 10, 31)));
+        assert_eq!(next("*/15 * * * *", at(2025, 1, 1, 10, 30)), Some(at(2025, 1, 1, 10, 45)));
+        assert_eq!(next("0 * * * *", at(2025, 1, 1, 23, 59)), Some(at(2025, 1, 2, 0, 0)));
+    }
+
+    #[test]
+    fn next_after_rolls_over_months_and_years() {
+        assert_eq!(next("30 2 1 * *", at(2025, 1, 15, 0, 0)), Some(at(2025, 2, 1, 2, 30)));
+        assert_eq!(next("0 0 1 1 *", at(2025, 6, 1, 0, 0)), Some(at(2026, 1, 1, 0, 0)));
+        assert_eq!(next("0 0 29 2 *", at(2025, 3, 1, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn next_after_matches_day_of_month_or_day_of_week() {
+        // 2025-01-01 adalah hari Rabu; Senin berikutnya 2025-01-06
+        assert_eq!(next("0 9 * * 1", at(2025, 1, 1, 12, 0)), Some(at(2025, 1, 6, 9, 0)));
+        // Tanggal 15 atau hari Senin, mana yang lebih dulu
+        assert_eq!(next("0 9 15 * 1", at(2025, 1, 7, 0, 0)), Some(at(2025, 1, 13, 9, 0)));
+        assert_eq!(next("0 9 15 * 1", at(2025, 1, 13, 10, 0)), Some(at(2025, 1, 15, 9, 0)));
+    }
+
+    #[test]
+    fn next_after_gives_up_on_impossible_dates() {
+        assert_eq!(next("0 0 31 2 *", at(2025, 1, 1, 0, 0)), None);
+    }
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::jobs::{Job, JobContext};
+use crate::notifications::channel::{ChannelError, ChannelKind};
+
+/// Kirim satu notifikasi lewat satu kanal eksternal. Satu job per kanal supaya retry
+/// tidak mengirim ulang ke kanal yang sudah berhasil.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeliverNotification {
+    pub user_id: Uuid,
+    pub channel: ChannelKind,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct Recipient {
+    email: String,
+    no_hp: Option<String>,
+}
+
+#[async_trait]
+impl Job for DeliverNotification {
+    const JOB_TYPE: &'static str = "deliver_notification";
+
+    async fn perform(&self, ctx: &JobContext) -> Result<()> {
+        let Some(recipient) = sqlx::query_as::<_, Recipient>("SELECT email, no_hp FROM users WHERE id = $1")
+            .bind(self.user_id)
+            .fetch_optional(&ctx.db)
+            .await?
+        else {
+            // User sudah dihapus
+            return Ok(());
+        };
+
+        let sent = match self.channel {
+            ChannelKind::Email => ctx.channels.email.send_email(&recipient.email, &self.title, &self.body).await,
+            ChannelKind::Sms => match recipient.no_hp.as_deref().filter(|p| !p.trim().is_empty()) {
+                Some(phone) => ctx.channels.sms.send_sms(phone, &self.body).await,
+                None => Err(ChannelError::MissingAddress("phone number")),
+            },
+            ChannelKind::Push => ctx.channels.push.send_push(self.user_id, &self.title, &self.body).await,
+        };
+
+        match sent {
+            Ok(()) => Ok(()),
+            // Tidak akan berhasil walaupun dicoba ulang
+            Err(e @ ChannelError::MissingAddress(_)) => {
+                tracing::warn!("Skipping {:?} notification for user {}: {}", self.channel, self.user_id, e);
+                Ok(())
+            }
+            Err(e) => Err(AppError::InternalServerError(e.to_string())),
+        }
+    }
+}
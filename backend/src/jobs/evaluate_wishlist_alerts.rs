@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::handlers::wishlist_alert::evaluate_spbu_alerts;
+use crate::jobs::{Job, JobContext};
+
+/// Evaluasi aturan alert wishlist untuk satu SPBU. Di-enqueue setiap kali harga, stok
+/// atau antrian SPBU berubah.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EvaluateWishlistAlerts {
+    pub spbu_id: Uuid,
+}
+
+#[async_trait]
+impl Job for EvaluateWishlistAlerts {
+    const JOB_TYPE: &'static str = "evaluate_wishlist_alerts";
+
+    async fn perform(&self, ctx: &JobContext) -> Result<()> {
+        let mut tx = ctx.db.begin().await?;
+        let sent = evaluate_spbu_alerts(&mut tx, self.spbu_id).await?;
+        tx.commit().await?;
+        if sent > 0 {
+            tracing::info!("Sent {} wishlist alerts for SPBU {}", sent, self.spbu_id);
+        }
+        Ok(())
+    }
+}
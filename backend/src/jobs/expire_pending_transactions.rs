@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::handlers::transaction::{release_cancelled_transaction, TRANSACTION_COLUMNS};
+use crate::jobs::{env_u64, ScheduledJob};
+use crate::models::notification::NotificationEvent;
+use crate::models::Transaction;
+use crate::notifications::notify_transaction;
+
+// Batasi jumlah transaksi per run supaya lock tidak ditahan terlalu lama
+const BATCH_SIZE: i64 = 500;
+
+/// Batalkan transaksi `pending` yang tidak dibayar dalam `PENDING_TRANSACTION_TTL_MINS`
+/// (default 30 menit). Kuota, promo dan poin yang dipakai transaksi ikut dilepas.
+pub struct ExpirePendingTransactions {
+    ttl: Duration,
+    interval: Duration,
+}
+
+impl ExpirePendingTransactions {
+    pub fn from_env() -> Self {
+        ExpirePendingTransactions {
+            ttl: Duration::from_secs(env_u64("PENDING_TRANSACTION_TTL_MINS", 30) * 60),
+            interval: Duration::from_secs(env_u64("EXPIRE_PENDING_INTERVAL_SECS", 60).max(1)),
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for ExpirePendingTransactions {
+    fn name(&self) -> &'static str {
+        "expire_pending_transactions"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(&self, conn: &mut PgConnection) -> Result<u64> {
+        // SKIP LOCKED: transaksi yang sedang dibayar/dibatalkan user dilewati dan dicek run berikutnya
+        let expired = sqlx::query_as::<_, Transaction>(&format!(
+            r#"
+            UPDATE transactions
+            SET status = 'cancelled', updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM transactions
+                WHERE status = 'pending'
+                  AND created_at < NOW() - make_interval(secs => $1)
+                ORDER BY created_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            TRANSACTION_COLUMNS
+        ))
+        .bind(self.ttl.as_secs() as f64)
+        .bind(BATCH_SIZE)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for transaction in &expired {
+            release_cancelled_transaction(&mut *conn, transaction.id).await?;
+            notify_transaction(&mut *conn, NotificationEvent::TransactionExpired, transaction, serde_json::json!({}))
+                .await?;
+        }
+
+        Ok(expired.len() as u64)
+    }
+}
@@ -0,0 +1,161 @@
+//! Background job yang berjalan di luar jalur request.
+//!
+//! Ada dua jenis:
+//! - [`ScheduledJob`]: dijalankan berkala di tokio task sendiri. Sebelum run, job mengambil
+//!   advisory lock transaksi `job:<nama>` sehingga jika ada beberapa instance hanya satu yang
+//!   mengeksekusinya. Setiap run yang berjalan dicatat di tabel `job_runs`.
+//! - [`queue::Job`]: dimasukkan ke antrian `queued_jobs` (langsung, terjadwal, atau lewat cron)
+//!   lalu dijalankan worker dengan retry.
+
+pub mod cron;
+pub mod deliver_notification;
+pub mod evaluate_wishlist_alerts;
+pub mod expire_pending_transactions;
+pub mod queue;
+pub mod refresh_spbu_stats;
+pub mod schedule;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+use crate::error::{AppError, Result};
+use crate::notifications::channel::Channels;
+
+pub use deliver_notification::DeliverNotification;
+pub use evaluate_wishlist_alerts::EvaluateWishlistAlerts;
+pub use expire_pending_transactions::ExpirePendingTransactions;
+pub use queue::{Job, JobContext, JobRegistry};
+pub use refresh_spbu_stats::RefreshDirtySpbuStats;
+pub use schedule::CronJob;
+
+#[async_trait]
+pub trait ScheduledJob: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn interval(&self) -> Duration;
+    /// Dijalankan di dalam transaksi database; mengembalikan jumlah baris yang diproses.
+    /// Jika gagal, semua perubahan run tersebut di-rollback.
+    async fn run(&self, conn: &mut PgConnection) -> Result<u64>;
+}
+
+/// Baca variabel env berupa angka, pakai `default` jika tidak diset atau tidak valid
+pub fn env_u64(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Semua job antrian yang bisa dijalankan worker
+pub fn registry() -> JobRegistry {
+    JobRegistry::default()
+        .register::<RefreshDirtySpbuStats>()
+        .register::<DeliverNotification>()
+        .register::<EvaluateWishlistAlerts>()
+}
+
+/// Job antrian yang dijadwalkan dengan cron (UTC)
+fn cron_jobs() -> Vec<CronJob> {
+    vec![CronJob::new("refresh_spbu_stats", "*/5 * * * *", &RefreshDirtySpbuStats::default())]
+}
+
+/// Jalankan job terjadwal dan `QUEUE_WORKERS` worker antrian (default 2).
+/// Set `JOBS_ENABLED=false` untuk instance yang hanya melayani HTTP, misalnya jika job
+/// dijalankan di proses terpisah dengan `backend worker`.
+pub fn spawn_all(db: PgPool) {
+    if env::var("JOBS_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("false") || v == "0") {
+        tracing::info!("Background jobs are disabled");
+        return;
+    }
+
+    let jobs: Vec<Arc<dyn ScheduledJob>> = vec![
+        Arc::new(ExpirePendingTransactions::from_env()),
+        Arc::new(queue::RequeueStaleJobs::from_env()),
+        Arc::new(schedule::EnqueueCronJobs::new(cron_jobs())),
+    ];
+    for job in jobs {
+        tracing::info!("Scheduling job {} every {:?}", job.name(), job.interval());
+        tokio::spawn(run_forever(db.clone(), job));
+    }
+
+    let ctx = JobContext {
+        db,
+        channels: Channels::from_env(),
+    };
+    let registry = Arc::new(registry());
+    let workers = env_u64("QUEUE_WORKERS", 2);
+    tracing::info!("Starting {} job queue workers", workers);
+    for i in 0..workers {
+        let worker_id = format!("{}-{}", std::process::id(), i);
+        tokio::spawn(queue::run_worker(ctx.clone(), registry.clone(), worker_id));
+    }
+}
+
+async fn run_forever(db: PgPool, job: Arc<dyn ScheduledJob>) {
+    let mut ticker = tokio::time::interval(job.interval());
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match run_once(&db, job.as_ref()).await {
+            Ok(Some(affected)) if affected > 0 => {
+                tracing::info!("Job {} processed {} rows", job.name(), affected)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Job {} failed: {:?}", job.name(), e),
+        }
+    }
+}
+
+/// Satu run job. `Ok(None)` jika instance lain sedang menjalankan job yang sama.
+pub async fn run_once(db: &PgPool, job: &dyn ScheduledJob) -> Result<Option<u64>> {
+    let started_at = Utc::now();
+    let mut tx = db.begin().await?;
+
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock(hashtext('job:' || $1))")
+        .bind(job.name())
+        .fetch_one(&mut *tx)
+        .await?;
+    if !locked {
+        return Ok(None);
+    }
+
+    match job.run(&mut tx).await {
+        Ok(affected) => {
+            record_run(&mut *tx, job.name(), started_at, affected, None).await?;
+            tx.commit().await?;
+            Ok(Some(affected))
+        }
+        Err(e) => {
+            tx.rollback().await?;
+            record_run(db, job.name(), started_at, 0, Some(format!("{:?}", e))).await?;
+            Err(e)
+        }
+    }
+}
+
+async fn record_run<'e, E: PgExecutor<'e>>(
+    executor: E,
+    job_name: &str,
+    started_at: DateTime<Utc>,
+    affected_rows: u64,
+    error: Option<String>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO job_runs (job_name, status, started_at, affected_rows, error)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(job_name)
+    .bind(if error.is_none() { "succeeded" } else { "failed" })
+    .bind(started_at)
+    .bind(i64::try_from(affected_rows).map_err(|e| AppError::InternalServerError(e.to_string()))?)
+    .bind(error)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
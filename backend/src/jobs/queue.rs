@@ -0,0 +1,286 @@
+//! Antrian job di Postgres (`queued_jobs`).
+//!
+//! Job di-enqueue sebagai JSON bersama `job_type`-nya, lalu diambil worker dengan
+//! `FOR UPDATE SKIP LOCKED` sehingga beberapa worker (di server atau proses `backend worker`)
+//! bisa berjalan bersamaan tanpa mengambil job yang sama. Job yang gagal dicoba lagi dengan
+//! backoff eksponensial sampai `max_attempts`, setelah itu berstatus `dead`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::jobs::{env_u64, ScheduledJob};
+use crate::notifications::channel::Channels;
+
+// Backoff retry: 10 detik, 20 detik, 40 detik, ... maksimal 1 jam
+const BACKOFF_BASE_SECS: i64 = 10;
+const BACKOFF_MAX_SECS: i64 = 3600;
+
+/// Dependensi yang tersedia untuk job saat dijalankan
+#[derive(Clone)]
+pub struct JobContext {
+    pub db: PgPool,
+    pub channels: Channels,
+}
+
+/// Job yang bisa dimasukkan ke antrian. Payload job adalah struct itu sendiri (JSON).
+#[async_trait]
+pub trait Job: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Nama unik yang disimpan di kolom `job_type`
+    const JOB_TYPE: &'static str;
+    const MAX_ATTEMPTS: i32 = 5;
+
+    async fn perform(&self, ctx: &JobContext) -> Result<()>;
+}
+
+// Job dengan tipe yang sudah dihapus, dipakai registry untuk menjalankan payload JSON
+#[async_trait]
+trait JobHandler: Send + Sync {
+    async fn perform(&self, payload: serde_json::Value, ctx: &JobContext) -> Result<()>;
+}
+
+struct TypedHandler<J>(PhantomData<fn() -> J>);
+
+#[async_trait]
+impl<J: Job> JobHandler for TypedHandler<J> {
+    async fn perform(&self, payload: serde_json::Value, ctx: &JobContext) -> Result<()> {
+        let job: J = serde_json::from_value(payload)
+            .map_err(|e| AppError::BadRequest(format!("Invalid {} payload: {}", J::JOB_TYPE, e)))?;
+        job.perform(ctx).await
+    }
+}
+
+/// Daftar job yang bisa dijalankan worker, per `job_type`
+#[derive(Default)]
+pub struct JobRegistry {
+    handlers: HashMap<&'static str, Arc<dyn JobHandler>>,
+}
+
+impl JobRegistry {
+    pub fn register<J: Job>(mut self) -> Self {
+        self.handlers.insert(J::JOB_TYPE, Arc::new(TypedHandler::<J>(PhantomData)));
+        self
+    }
+}
+
+/// Masukkan job ke antrian. Jika `dedupe_key` sudah ada di antrian, job tidak dimasukkan
+/// lagi dan hasilnya `None`.
+pub async fn enqueue_raw<'e, E: PgExecutor<'e>>(
+    executor: E,
+    job_type: &str,
+    payload: serde_json::Value,
+    max_attempts: i32,
+    run_at: DateTime<Utc>,
+    dedupe_key: Option<&str>,
+) -> Result<Option<Uuid>> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO queued_jobs (job_type, payload, max_attempts, run_at, dedupe_key)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (dedupe_key) WHERE dedupe_key IS NOT NULL DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(job_type)
+    .bind(payload)
+    .bind(max_attempts)
+    .bind(run_at)
+    .bind(dedupe_key)
+    .fetch_optional(executor)
+    .await?;
+    Ok(id)
+}
+
+/// Masukkan job untuk dijalankan secepatnya
+pub async fn enqueue<'e, E: PgExecutor<'e>, J: Job>(executor: E, job: &J) -> Result<Option<Uuid>> {
+    let payload = serde_json::to_value(job).map_err(|e| AppError::InternalServerError(e.to_string()))?;
+    enqueue_raw(executor, J::JOB_TYPE, payload, J::MAX_ATTEMPTS, Utc::now(), None).await
+}
+
+fn backoff_secs(attempts: i32) -> i64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    BACKOFF_BASE_SECS.saturating_mul(2i64.saturating_pow(exponent)).min(BACKOFF_MAX_SECS)
+}
+
+#[derive(sqlx::FromRow)]
+struct ClaimedJob {
+    id: Uuid,
+    job_type: String,
+    payload: serde_json::Value,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+// Ambil satu job yang sudah waktunya dan tandai running
+async fn claim_next(db: &PgPool, worker_id: &str) -> Result<Option<ClaimedJob>> {
+    let job = sqlx::query_as::<_, ClaimedJob>(
+        r#"
+        UPDATE queued_jobs
+        SET status = 'running', attempts = attempts + 1, locked_at = NOW(), locked_by = $1
+        WHERE id = (
+            SELECT id FROM queued_jobs
+            WHERE status = 'pending' AND run_at <= NOW()
+            ORDER BY run_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, job_type, payload, attempts, max_attempts
+        "#,
+    )
+    .bind(worker_id)
+    .fetch_optional(db)
+    .await?;
+    Ok(job)
+}
+
+async fn finish_job(db: &PgPool, job: &ClaimedJob, outcome: Result<()>) -> Result<()> {
+    match outcome {
+        Ok(()) => {
+            sqlx::query(
+                r#"
+                UPDATE queued_jobs
+                SET status = 'succeeded', finished_at = NOW(), locked_at = NULL, locked_by = NULL
+                WHERE id = $1
+                "#,
+            )
+            .bind(job.id)
+            .execute(db)
+            .await?;
+        }
+        Err(e) => {
+            let error = format!("{:?}", e);
+            let dead = job.attempts >= job.max_attempts;
+            if dead {
+                tracing::error!("Job {} ({}) is dead after {} attempts: {}", job.id, job.job_type, job.attempts, error);
+            } else {
+                tracing::warn!("Job {} ({}) attempt {} failed: {}", job.id, job.job_type, job.attempts, error);
+            }
+            sqlx::query(
+                r#"
+                UPDATE queued_jobs
+                SET status = CASE WHEN $2 THEN 'dead' ELSE 'pending' END,
+                    run_at = CASE WHEN $2 THEN run_at ELSE NOW() + make_interval(secs => $3) END,
+                    finished_at = CASE WHEN $2 THEN NOW() END,
+                    last_error = $4,
+                    locked_at = NULL,
+                    locked_by = NULL
+                WHERE id = $1
+                "#,
+            )
+            .bind(job.id)
+            .bind(dead)
+            .bind(backoff_secs(job.attempts) as f64)
+            .bind(error)
+            .execute(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Jalankan satu job jika ada. `Ok(false)` jika antrian kosong.
+pub async fn work_once(ctx: &JobContext, registry: &JobRegistry, worker_id: &str) -> Result<bool> {
+    let Some(mut job) = claim_next(&ctx.db, worker_id).await? else {
+        return Ok(false);
+    };
+
+    let outcome = match registry.handlers.get(job.job_type.as_str()) {
+        Some(handler) => handler.perform(job.payload.take(), ctx).await,
+        None => {
+            // Tidak ada worker yang bisa menjalankannya, langsung dead
+            job.attempts = job.max_attempts;
+            Err(AppError::InternalServerError(format!("Unknown job type {}", job.job_type)))
+        }
+    };
+
+    finish_job(&ctx.db, &job, outcome).await?;
+    Ok(true)
+}
+
+/// Loop worker: ambil job terus-menerus, tidur `JOB_POLL_INTERVAL_MS` saat antrian kosong
+pub async fn run_worker(ctx: JobContext, registry: Arc<JobRegistry>, worker_id: String) {
+    let idle = Duration::from_millis(env_u64("JOB_POLL_INTERVAL_MS", 1000).max(10));
+    loop {
+        match work_once(&ctx, &registry, &worker_id).await {
+            Ok(true) => {}
+            Ok(false) => tokio::time::sleep(idle).await,
+            Err(e) => {
+                tracing::error!("Job worker {} error: {:?}", worker_id, e);
+                tokio::time::sleep(idle).await;
+            }
+        }
+    }
+}
+
+/// Kembalikan job `running` yang ditinggal worker mati (lebih lama dari
+/// `JOB_LOCK_TIMEOUT_SECS`, default 10 menit) ke antrian
+pub struct RequeueStaleJobs {
+    timeout: Duration,
+}
+
+impl RequeueStaleJobs {
+    pub fn from_env() -> Self {
+        RequeueStaleJobs {
+            timeout: Duration::from_secs(env_u64("JOB_LOCK_TIMEOUT_SECS", 600)),
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for RequeueStaleJobs {
+    fn name(&self) -> &'static str {
+        "requeue_stale_jobs"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self, conn: &mut PgConnection) -> Result<u64> {
+        // Percobaan yang terputus tetap dihitung; jika sudah habis langsung dead
+        let result = sqlx::query(
+            r#"
+            UPDATE queued_jobs
+            SET status = CASE WHEN attempts >= max_attempts THEN 'dead' ELSE 'pending' END,
+                finished_at = CASE WHEN attempts >= max_attempts THEN NOW() END,
+                last_error = 'Worker stopped while running job (locked by ' || COALESCE(locked_by, '?') || ')',
+                locked_at = NULL,
+                locked_by = NULL,
+                run_at = NOW()
+            WHERE status = 'running' AND locked_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(self.timeout.as_secs() as f64)
+        .execute(&mut *conn)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_from_base() {
+        assert_eq!(backoff_secs(1), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(2), BACKOFF_BASE_SECS * 2);
+        assert_eq!(backoff_secs(3), BACKOFF_BASE_SECS * 4);
+        assert_eq!(backoff_secs(5), BACKOFF_BASE_SECS * 16);
+    }
+
+    #[test]
+    fn backoff_is_capped_and_handles_out_of_range_attempts() {
+        assert_eq!(backoff_secs(0), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(-3), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(20), BACKOFF_MAX_SECS);
+        assert_eq!(backoff_secs(i32::MAX), BACKOFF_MAX_SECS);
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::handlers::spbu_stats::refresh_spbu_stats;
+use crate::jobs::{Job, JobContext};
+
+/// Perbarui rollup statistik semua SPBU yang punya bucket kotor, supaya dashboard
+/// operator tidak perlu menghitung ulang saat dibuka
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RefreshDirtySpbuStats {}
+
+#[async_trait]
+impl Job for RefreshDirtySpbuStats {
+    const JOB_TYPE: &'static str = "refresh_dirty_spbu_stats";
+
+    async fn perform(&self, ctx: &JobContext) -> Result<()> {
+        let spbu_ids: Vec<Uuid> = sqlx::query_scalar("SELECT DISTINCT spbu_id FROM spbu_stats_dirty")
+            .fetch_all(&ctx.db)
+            .await?;
+
+        // Satu transaksi per SPBU supaya lock tidak ditahan untuk semua SPBU sekaligus
+        for spbu_id in spbu_ids {
+            let mut tx = ctx.db.begin().await?;
+            refresh_spbu_stats(&mut tx, spbu_id).await?;
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+}
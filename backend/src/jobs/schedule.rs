@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use std::time::Duration;
+
+use crate::error::{AppError, Result};
+use crate::jobs::cron::CronSchedule;
+use crate::jobs::queue::{enqueue_raw, Job};
+use crate::jobs::ScheduledJob;
+
+/// Job antrian yang dimasukkan otomatis sesuai jadwal cron (UTC)
+pub struct CronJob {
+    name: &'static str,
+    expression: &'static str,
+    schedule: CronSchedule,
+    job_type: &'static str,
+    payload: serde_json::Value,
+    max_attempts: i32,
+}
+
+impl CronJob {
+    /// Panic jika ekspresi cron tidak valid; jadwal ditulis di kode, bukan input user
+    pub fn new<J: Job>(name: &'static str, expression: &'static str, job: &J) -> Self {
+        CronJob {
+            name,
+            expression,
+            schedule: expression.parse().unwrap_or_else(|e| panic!("Cron job {}: {}", name, e)),
+            job_type: J::JOB_TYPE,
+            payload: serde_json::to_value(job).expect("job payload must serialize to JSON"),
+            max_attempts: J::MAX_ATTEMPTS,
+        }
+    }
+}
+
+/// Masukkan job cron yang sudah jatuh tempo ke antrian. `next_run_at` setiap jadwal disimpan di
+/// `job_schedules`, sehingga jadwal yang terlewat saat server mati tetap dijalankan sekali.
+pub struct EnqueueCronJobs {
+    jobs: Vec<CronJob>,
+}
+
+impl EnqueueCronJobs {
+    pub fn new(jobs: Vec<CronJob>) -> Self {
+        EnqueueCronJobs { jobs }
+    }
+}
+
+#[async_trait]
+impl ScheduledJob for EnqueueCronJobs {
+    fn name(&self) -> &'static str {
+        "enqueue_cron_jobs"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(15)
+    }
+
+    async fn run(&self, conn: &mut PgConnection) -> Result<u64> {
+        let now = Utc::now();
+        let mut enqueued = 0;
+
+        for job in &self.jobs {
+            let next_run_at = job.schedule.next_after(now).ok_or_else(|| {
+                AppError::InternalServerError(format!("Cron job {} never runs", job.name))
+            })?;
+
+            // Jadwal baru atau ekspresi yang diubah mulai dihitung dari sekarang
+            let due_at: DateTime<Utc> = sqlx::query_scalar(
+                r#"
+                INSERT INTO job_schedules (name, cron_expression, next_run_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (name) DO UPDATE
+                SET cron_expression = EXCLUDED.cron_expression,
+                    next_run_at = CASE
+                        WHEN job_schedules.cron_expression = EXCLUDED.cron_expression THEN job_schedules.next_run_at
+                        ELSE EXCLUDED.next_run_at
+                    END
+                RETURNING next_run_at
+                "#,
+            )
+            .bind(job.name)
+            .bind(job.expression)
+            .bind(next_run_at)
+            .fetch_one(&mut *conn)
+            .await?;
+
+            if due_at > now {
+                continue;
+            }
+
+            let dedupe_key = format!("cron:{}:{}", job.name, due_at.timestamp());
+            if enqueue_raw(
+                &mut *conn,
+                job.job_type,
+                job.payload.clone(),
+                job.max_attempts,
+                now,
+                Some(&dedupe_key),
+            )
+            .await?
+            .is_some()
+            {
+                enqueued += 1;
+            }
+
+            sqlx::query("UPDATE job_schedules SET next_run_at = $2, last_enqueued_at = NOW() WHERE name = $1")
+                .bind(job.name)
+                .bind(next_run_at)
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        Ok(enqueued)
+    }
+}
@@ -1,7 +1,15 @@
 mod auth;
+mod error;
+mod geocoding;
 mod handlers;
+mod jobs;
 mod middleware;
 mod models;
+mod notifications;
+mod payment;
+mod pdf;
+mod pickup;
+mod storage;
 mod types;
 mod utils;
 
@@ -14,18 +22,65 @@ use axum::{
 use tower_http::cors::CorsLayer;
 use crate::handlers::user::{register_user, get_users, get_user_by_id, update_user_by_id, delete_user_by_id, login_user, forgot_password};
 use crate::handlers::brand::{get_all_brands, create_brands, update_brands, delete_brands};
-use crate::handlers::spbu::{get_all_spbu, get_spbu_by_id, create_spbu, update_spbu, delete_spbu};
+use crate::handlers::spbu::{get_all_spbu, get_spbu_by_id, create_spbu, update_spbu, delete_spbu, find_spbu_along_route};
 use crate::handlers::service::{get_all_services, create_service, get_service_by_id, update_service, delete_service};
 use crate::handlers::spbu_service::{add_service_to_spbu, remove_service_from_spbu, get_services_by_spbu, get_spbus_by_service};
-use crate::handlers::wishlist::{add_to_wishlist, remove_from_wishlist, get_user_wishlists};
+use crate::handlers::wishlist::{
+    add_to_wishlist, remove_from_wishlist, get_user_wishlists, update_wishlist_item, reorder_wishlist,
+    bulk_add_to_wishlist, bulk_remove_from_wishlist, sync_wishlist,
+};
+use crate::handlers::wishlist_collection::{
+    list_collections, create_collection, rename_collection, delete_collection,
+    share_collection, unshare_collection, get_shared_collection,
+};
+use crate::handlers::wishlist_alert::{list_alerts, create_alert, update_alert, delete_alert};
 use crate::handlers::review::{
     create_review, get_review, update_review, delete_review,
-    get_spbu_reviews, get_spbu_rating,
+    get_spbu_reviews, get_spbu_rating, vote_helpful, remove_helpful_vote,
 };
 use crate::handlers::transaction::{
     create_transaction, get_transaction, list_transactions,
     cancel_transaction, process_payment,
 };
+use crate::handlers::review_reply::{create_reply, update_reply, get_reply_history, get_spbu_review_stats};
+use crate::handlers::media::{
+    upload_spbu_photos, get_spbu_photos, delete_spbu_photo,
+    upload_brand_logo, get_brand_logo, upload_service_icon, get_service_icon,
+    upload_user_avatar, get_user_avatar, download_media, MAX_PHOTOS_PER_UPLOAD,
+    upload_review_photos, delete_review_photo,
+};
+use crate::handlers::fuel_price::get_cheapest_fuel;
+use crate::handlers::fill_up::{list_fill_ups, create_fill_up, delete_fill_up, get_vehicle_efficiency};
+use crate::handlers::loyalty::{get_my_points, list_loyalty_rules, create_loyalty_rule, update_loyalty_rule, delete_loyalty_rule};
+use crate::handlers::promotion::{validate_promotion, list_promotions, create_promotion, update_promotion, delete_promotion};
+use crate::handlers::wallet::{get_my_wallet, list_topups, create_topup, pay_topup};
+use crate::handlers::organization::{
+    create_organization, list_my_organizations, get_organization, update_organization,
+    list_members, add_member, update_member, remove_member,
+    list_organization_vehicles, add_organization_vehicle, update_organization_vehicle, remove_organization_vehicle,
+    list_organization_transactions, list_invoices, create_invoice, get_invoice, pay_invoice,
+};
+use crate::handlers::statement::{
+    list_my_statements, get_my_statement, issue_my_statement, list_organization_statements,
+    get_organization_statement, issue_organization_statement,
+};
+use crate::handlers::operator::{
+    list_my_stations, list_staff, add_staff, assign_owner, update_staff, remove_staff, list_station_fuels,
+    update_fuel_price, update_fuel_stock, remove_fuel, update_queue, update_hours, list_station_transactions,
+};
+use crate::handlers::spbu_stats::get_spbu_stats;
+use crate::handlers::pump::{list_pumps, create_pump, update_pump, update_pump_status, delete_pump, assign_pump, get_wait_time};
+use crate::handlers::job::{list_jobs, job_summary, get_job, retry_job, list_job_runs};
+use crate::handlers::notification::{
+    list_notifications, update_notification, mark_all_read, get_preferences, update_preferences,
+};
+use crate::handlers::pickup::{get_pickup_code, redeem_pickup, complete_pickup};
+use crate::handlers::quota::{get_my_quota, list_quota_policies, upsert_quota_policy, delete_quota_policy};
+use crate::handlers::vehicle::{list_vehicles, create_vehicle, get_vehicle, update_vehicle, delete_vehicle};
+use crate::storage::{LocalStorage, MediaConfig, Storage};
+use crate::geocoding::{GazetteerGeocoder, Geocoder};
+use axum::extract::DefaultBodyLimit;
+use std::sync::Arc;
 
 // Temporary handler to list SPBUs and their fuel prices
 use axum::extract::State;
@@ -86,6 +141,9 @@ use std::net::SocketAddr;
 #[derive(Clone)]
 pub struct AppState {
     pub db: sqlx::PgPool,
+    pub storage: Arc<dyn Storage>,
+    pub media: MediaConfig,
+    pub geocoder: Arc<dyn Geocoder>,
 }
 
 #[tokio::main]
@@ -95,6 +153,8 @@ async fn main() {
     
     dotenv::dotenv().ok();
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    // Kode QR pickup tidak boleh ditandatangani dengan kunci bawaan
+    pickup::init_signing_key().expect("PICKUP_SIGNING_KEY or JWT_SECRET must be set");
 
     tracing::info!("Connecting to database...");
     let pool = PgPoolOptions::new()
@@ -105,7 +165,36 @@ async fn main() {
     
     tracing::info!("Database connection established");
 
-    let app_state = AppState { db: pool };
+    // Storage untuk file upload (foto, logo, icon)
+    let storage_dir = std::env::var("MEDIA_STORAGE_DIR").unwrap_or_else(|_| "./uploads".to_string());
+    let media = MediaConfig::from_env().expect("MEDIA_SIGNING_KEY or JWT_SECRET must be set");
+    // Body request upload boleh berisi beberapa file sekaligus
+    let upload_body_limit = media.max_upload_bytes * MAX_PHOTOS_PER_UPLOAD + 64 * 1024;
+
+    // Geocoder offline dari file gazetteer
+    let gazetteer_path = std::env::var("GAZETTEER_PATH").unwrap_or_else(|_| "data/gazetteer_id.csv".to_string());
+    let geocoder = GazetteerGeocoder::from_file(&gazetteer_path).unwrap_or_else(|e| {
+        tracing::warn!("Failed to load gazetteer {}: {}", gazetteer_path, e);
+        GazetteerGeocoder::parse("")
+    });
+    tracing::info!("Loaded {} gazetteer places", geocoder.place_count());
+
+    let app_state = AppState {
+        db: pool,
+        storage: Arc::new(LocalStorage::new(storage_dir)),
+        media,
+        geocoder: Arc::new(geocoder),
+    };
+
+    // Background job terjadwal (kedaluwarsa transaksi pending, dll.) dan worker antrian job
+    jobs::spawn_all(app_state.db.clone());
+
+    // `backend worker`: hanya menjalankan job, tanpa HTTP server
+    if std::env::args().nth(1).as_deref() == Some("worker") {
+        tracing::info!("Running in worker mode");
+        tokio::signal::ctrl_c().await.expect("Failed to listen for shutdown signal");
+        return;
+    }
 
     // Public routes (tidak memerlukan autentikasi)
     let public_routes = Router::new()
@@ -120,12 +209,26 @@ async fn main() {
         .route("/forgot_password", post(forgot_password))
         .route("/brands", get(get_all_brands))
         .route("/spbu", get(get_all_spbu))
+        .route("/spbu/along-route", post(find_spbu_along_route))
+        .route("/fuel-prices/cheapest", get(get_cheapest_fuel))
+        .route("/quota-policies", get(list_quota_policies))
+        .route("/loyalty-rules", get(list_loyalty_rules))
         .route("/services", get(get_all_services))
         .route("/services/:id", get(get_service_by_id))
         .route("/spbu/:spbu_id/services", get(get_services_by_spbu))
         .route("/services/:service_id/spbus", get(get_spbus_by_service))
         .route("/spbu/:spbu_id/reviews", get(get_spbu_reviews))
+        .route("/spbu/:spbu_id/review-stats", get(get_spbu_review_stats))
+        .route("/reviews/:review_id/reply/history", get(get_reply_history))
         .route("/spbu/:spbu_id/rating", get(get_spbu_rating))
+        .route("/spbu/:spbu_id/photos", get(get_spbu_photos))
+        .route("/spbu/:spbu_id/pumps", get(list_pumps))
+        .route("/spbu/:spbu_id/wait-time", get(get_wait_time))
+        .route("/shared/wishlists/:token", get(get_shared_collection))
+        .route("/brands/:id/logo", get(get_brand_logo))
+        .route("/services/:id/icon", get(get_service_icon))
+        .route("/user/:id/avatar", get(get_user_avatar))
+        .route("/media/*key", get(download_media))
         .route("/debug/spbus-with-prices", get(list_spbus_with_prices));
 
     // Protected routes (membutuhkan autentikasi JWT)
@@ -166,9 +269,51 @@ async fn main() {
             post(add_to_wishlist).layer(from_fn(middleware::auth::auth_middleware))
                 .get(get_user_wishlists).layer(from_fn(middleware::auth::auth_middleware))
         )
+        .route(
+            "/wishlist/order",
+            put(reorder_wishlist).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/wishlist/bulk-add",
+            post(bulk_add_to_wishlist).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/wishlist/bulk-remove",
+            post(bulk_remove_from_wishlist).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/wishlist/sync",
+            post(sync_wishlist).layer(from_fn(middleware::auth::auth_middleware))
+        )
         .route(
             "/wishlist/:spbu_id", 
             delete(remove_from_wishlist).layer(from_fn(middleware::auth::auth_middleware))
+                .put(update_wishlist_item).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/wishlist-collections",
+            get(list_collections).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_collection).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/wishlist-collections/:id",
+            put(rename_collection).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_collection).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/wishlist-collections/:id/share",
+            post(share_collection).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(unshare_collection).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/wishlist/:spbu_id/alerts",
+            get(list_alerts).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_alert).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/wishlist/:spbu_id/alerts/:alert_id",
+            put(update_alert).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_alert).layer(from_fn(middleware::auth::auth_middleware))
         )
         
         // Review routes
@@ -182,6 +327,11 @@ async fn main() {
                 .put(update_review).layer(from_fn(middleware::auth::auth_middleware))
                 .delete(delete_review).layer(from_fn(middleware::auth::auth_middleware))
         )
+        .route(
+            "/reviews/:review_id/helpful",
+            post(vote_helpful).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(remove_helpful_vote).layer(from_fn(middleware::auth::auth_middleware))
+        )
         
         // Transaction routes
         .route(
@@ -203,6 +353,213 @@ async fn main() {
         .route(
             "/transactions/:id/pay",
             post(process_payment).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/transactions/:id/pickup-code",
+            get(get_pickup_code).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Vehicle (garasi) routes
+        .route(
+            "/me/vehicles",
+            get(list_vehicles).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/vehicles/:id",
+            get(get_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+                .put(update_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/vehicles/:id/fill-ups",
+            get(list_fill_ups).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_fill_up).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/vehicles/:id/fill-ups/:fill_up_id",
+            delete(delete_fill_up).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/me/vehicles/:id/efficiency",
+            get(get_vehicle_efficiency).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Kuota BBM bersubsidi
+        .route("/me/quota", get(get_my_quota).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/quota-policies", put(upsert_quota_policy).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/quota-policies/:id", delete(delete_quota_policy).layer(from_fn(middleware::auth::auth_middleware)))
+
+        // Notifikasi in-app dan preferensi kanal
+        .route("/me/notifications", get(list_notifications).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/me/notifications/read-all", post(mark_all_read).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/me/notifications/:id", put(update_notification).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/me/notification-preferences",
+            get(get_preferences).layer(from_fn(middleware::auth::auth_middleware))
+                .put(update_preferences).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Promo / voucher
+        .route("/promotions/validate", post(validate_promotion).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/promotions",
+            get(list_promotions).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_promotion).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/promotions/:id",
+            put(update_promotion).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_promotion).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Dompet
+        .route("/me/wallet", get(get_my_wallet).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/me/wallet/topups",
+            get(list_topups).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_topup).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/me/wallet/topups/:id/pay", post(pay_topup).layer(from_fn(middleware::auth::auth_middleware)))
+
+        // Akun organisasi / armada
+        .route("/organizations", post(create_organization).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/me/organizations", get(list_my_organizations).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/organizations/:id",
+            get(get_organization).layer(from_fn(middleware::auth::auth_middleware))
+                .put(update_organization).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/organizations/:id/members",
+            get(list_members).layer(from_fn(middleware::auth::auth_middleware))
+                .post(add_member).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/organizations/:id/members/:user_id",
+            put(update_member).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(remove_member).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/organizations/:id/vehicles",
+            get(list_organization_vehicles).layer(from_fn(middleware::auth::auth_middleware))
+                .post(add_organization_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/organizations/:id/vehicles/:vehicle_id",
+            put(update_organization_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(remove_organization_vehicle).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/organizations/:id/transactions", get(list_organization_transactions).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/organizations/:id/invoices",
+            get(list_invoices).layer(from_fn(middleware::auth::auth_middleware))
+                .post(create_invoice).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/organizations/:id/invoices/:invoice_id", get(get_invoice).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/organizations/:id/invoices/:invoice_id/pay", post(pay_invoice).layer(from_fn(middleware::auth::auth_middleware)))
+
+        // Statement bulanan
+        .route("/me/statements", get(list_my_statements).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/me/statements/:period",
+            get(get_my_statement).layer(from_fn(middleware::auth::auth_middleware))
+                .post(issue_my_statement).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/organizations/:id/statements", get(list_organization_statements).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/organizations/:id/statements/:period",
+            get(get_organization_statement).layer(from_fn(middleware::auth::auth_middleware))
+                .post(issue_organization_statement).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Operator SPBU
+        .route("/operator/spbu", get(list_my_stations).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/operator/spbu/:id/staff",
+            get(list_staff).layer(from_fn(middleware::auth::auth_middleware))
+                .post(add_staff).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/operator/spbu/:id/staff/:user_id",
+            put(update_staff).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(remove_staff).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/operator/spbu/:id/fuels", get(list_station_fuels).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/fuels/:fuel_type", delete(remove_fuel).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/fuels/:fuel_type/price", put(update_fuel_price).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/fuels/:fuel_type/stock", put(update_fuel_stock).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/queue", put(update_queue).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/hours", put(update_hours).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/transactions", get(list_station_transactions).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/stats", get(get_spbu_stats).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/pumps", post(create_pump).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/operator/spbu/:id/pumps/:pump_id",
+            put(update_pump).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_pump).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route("/operator/spbu/:id/pumps/:pump_id/status", put(update_pump_status).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/transactions/:transaction_id/pump", put(assign_pump).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/spbu/:id/transactions/:transaction_id/complete", post(complete_pickup).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/operator/redeem", post(redeem_pickup).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/operator/reviews/:review_id/reply",
+            post(create_reply).layer(from_fn(middleware::auth::auth_middleware))
+                .put(update_reply).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Admin antrian job
+        .route("/admin/jobs", get(list_jobs).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/admin/jobs/summary", get(job_summary).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/admin/jobs/:id", get(get_job).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/admin/jobs/:id/retry", post(retry_job).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/admin/job-runs", get(list_job_runs).layer(from_fn(middleware::auth::auth_middleware)))
+        // Admin: tetapkan owner SPBU
+        .route("/admin/spbu/:id/owner", post(assign_owner).layer(from_fn(middleware::auth::auth_middleware)))
+
+        // Poin loyalitas
+        .route("/me/points", get(get_my_points).layer(from_fn(middleware::auth::auth_middleware)))
+        .route("/loyalty-rules", post(create_loyalty_rule).layer(from_fn(middleware::auth::auth_middleware)))
+        .route(
+            "/loyalty-rules/:id",
+            put(update_loyalty_rule).layer(from_fn(middleware::auth::auth_middleware))
+                .delete(delete_loyalty_rule).layer(from_fn(middleware::auth::auth_middleware))
+        )
+
+        // Media upload routes
+        .route(
+            "/spbu/:spbu_id/photos",
+            post(upload_spbu_photos).layer(DefaultBodyLimit::max(upload_body_limit))
+                .layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/spbu/:spbu_id/photos/:photo_id",
+            delete(delete_spbu_photo).layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/brands/:id/logo",
+            post(upload_brand_logo).layer(DefaultBodyLimit::max(upload_body_limit))
+                .layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/services/:id/icon",
+            post(upload_service_icon).layer(DefaultBodyLimit::max(upload_body_limit))
+                .layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/user/:id/avatar",
+            post(upload_user_avatar).layer(DefaultBodyLimit::max(upload_body_limit))
+                .layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/reviews/:review_id/photos",
+            post(upload_review_photos).layer(DefaultBodyLimit::max(upload_body_limit))
+                .layer(from_fn(middleware::auth::auth_middleware))
+        )
+        .route(
+            "/reviews/:review_id/photos/:photo_id",
+            delete(delete_review_photo).layer(from_fn(middleware::auth::auth_middleware))
         );
 
     // Setup CORS
@@ -0,0 +1,289 @@
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize)]
+pub struct FillUp {
+    pub id: Uuid,
+    pub vehicle_id: Uuid,
+    pub user_id: Uuid,
+    pub transaction_id: Option<Uuid>,
+    pub spbu_id: Option<Uuid>,
+    pub station_name: Option<String>,
+    pub fuel_type: Option<String>,
+    pub liters: Decimal,
+    pub odometer_km: i32,
+    pub is_full_tank: bool,
+    pub filled_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// liters harus di-select sebagai text (lihat FILL_UP_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for FillUp {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let liters: String = row.try_get("liters")?;
+        Ok(FillUp {
+            id: row.try_get("id")?,
+            vehicle_id: row.try_get("vehicle_id")?,
+            user_id: row.try_get("user_id")?,
+            transaction_id: row.try_get("transaction_id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            station_name: row.try_get("station_name")?,
+            fuel_type: row.try_get("fuel_type")?,
+            liters: Decimal::from_str(&liters).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            odometer_km: row.try_get("odometer_km")?,
+            is_full_tank: row.try_get("is_full_tank")?,
+            filled_at: row.try_get("filled_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+// Pengisian manual, misalnya di SPBU yang belum terdaftar di aplikasi
+#[derive(Debug, Deserialize)]
+pub struct CreateFillUpRequest {
+    pub spbu_id: Option<Uuid>,
+    pub station_name: Option<String>,
+    pub fuel_type: Option<String>,
+    pub liters: Decimal,
+    pub odometer_km: i32,
+    pub is_full_tank: Option<bool>,
+    pub filled_at: Option<DateTime<Utc>>,
+}
+
+/// Segmen antara dua pengisian penuh berurutan
+#[derive(Debug, Serialize)]
+pub struct EfficiencySegment {
+    pub start_odometer_km: i32,
+    pub end_odometer_km: i32,
+    pub distance_km: i32,
+    pub liters: f64,
+    pub km_per_liter: f64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<EfficiencyAnomaly>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EfficiencyAnomaly {
+    // Jauh lebih boros dari biasanya: kemungkinan kebocoran, ban kempis, atau pengisian tidak tercatat penuh
+    UnusuallyLow,
+    // Jauh lebih irit dari biasanya: kemungkinan ada pengisian yang tidak tercatat
+    UnusuallyHigh,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EfficiencyTrend {
+    Improving,
+    Stable,
+    Declining,
+}
+
+// Response GET /me/vehicles/:id/efficiency
+#[derive(Debug, Serialize)]
+pub struct EfficiencyReport {
+    pub vehicle_id: Uuid,
+    pub fill_up_count: usize,
+    pub total_distance_km: i32,
+    pub total_liters: f64,
+    pub average_km_per_liter: Option<f64>,
+    pub recent_km_per_liter: Option<f64>,
+    pub trend: Option<EfficiencyTrend>,
+    pub segments: Vec<EfficiencySegment>,
+}
+
+// Deviasi dari median yang dianggap anomali
+const ANOMALY_THRESHOLD: f64 = 0.3;
+// Perubahan rata-rata terbaru dibanding keseluruhan yang dianggap tren
+const TREND_THRESHOLD: f64 = 0.05;
+// Jumlah segmen terakhir untuk rata-rata terbaru
+const RECENT_SEGMENTS: usize = 3;
+
+impl EfficiencyReport {
+    /// Hitung km/liter dengan metode full-to-full: liter dari semua pengisian setelah
+    /// pengisian penuh sebelumnya (termasuk pengisian parsial) dibagi jarak tempuh.
+    /// `fill_ups` harus sudah terurut berdasarkan odometer.
+    pub fn compute(vehicle_id: Uuid, fill_ups: &[FillUp]) -> Self {
+        let mut segments = Vec::new();
+        let mut last_full: Option<&FillUp> = None;
+        let mut pending_liters = 0.0;
+
+        for fill_up in fill_ups {
+            let liters = fill_up.liters.0.to_f64().unwrap_or(0.0);
+            if let Some(start) = last_full {
+                pending_liters += liters;
+                let distance_km = fill_up.odometer_km - start.odometer_km;
+                if fill_up.is_full_tank && distance_km > 0 && pending_liters > 0.0 {
+                    segments.push(EfficiencySegment {
+                        start_odometer_km: start.odometer_km,
+                        end_odometer_km: fill_up.odometer_km,
+                        distance_km,
+                        liters: pending_liters,
+                        km_per_liter: distance_km as f64 / pending_liters,
+                        started_at: start.filled_at,
+                        ended_at: fill_up.filled_at,
+                        anomaly: None,
+                    });
+                }
+            }
+            if fill_up.is_full_tank {
+                last_full = Some(fill_up);
+                pending_liters = 0.0;
+            }
+        }
+
+        let total_distance_km: i32 = segments.iter().map(|s| s.distance_km).sum();
+        let total_liters: f64 = segments.iter().map(|s| s.liters).sum();
+        let average_km_per_liter = (total_liters > 0.0).then(|| total_distance_km as f64 / total_liters);
+
+        let recent = &segments[segments.len().saturating_sub(RECENT_SEGMENTS)..];
+        let recent_distance: i32 = recent.iter().map(|s| s.distance_km).sum();
+        let recent_liters: f64 = recent.iter().map(|s| s.liters).sum();
+        let recent_km_per_liter = (recent_liters > 0.0).then(|| recent_distance as f64 / recent_liters);
+
+        // Tren hanya berarti jika ada segmen lama untuk dibandingkan
+        let trend = match (average_km_per_liter, recent_km_per_liter) {
+            (Some(average), Some(recent)) if segments.len() > RECENT_SEGMENTS => {
+                let change = (recent - average) / average;
+                Some(if change > TREND_THRESHOLD {
+                    EfficiencyTrend::Improving
+                } else if change < -TREND_THRESHOLD {
+                    EfficiencyTrend::Declining
+                } else {
+                    EfficiencyTrend::Stable
+                })
+            }
+            _ => None,
+        };
+
+        // Anomali dibandingkan dengan median agar tidak terpengaruh segmen ekstrem
+        if segments.len() >= 3 {
+            let mut values: Vec<f64> = segments.iter().map(|s| s.km_per_liter).collect();
+            values.sort_by(|a, b| a.total_cmp(b));
+            let median = values[values.len() / 2];
+            for segment in &mut segments {
+                let deviation = (segment.km_per_liter - median) / median;
+                if deviation < -ANOMALY_THRESHOLD {
+                    segment.anomaly = Some(EfficiencyAnomaly::UnusuallyLow);
+                } else if deviation > ANOMALY_THRESHOLD {
+                    segment.anomaly = Some(EfficiencyAnomaly::UnusuallyHigh);
+                }
+            }
+        }
+
+        EfficiencyReport {
+            vehicle_id,
+            fill_up_count: fill_ups.len(),
+            total_distance_km,
+            total_liters,
+            average_km_per_liter,
+            recent_km_per_liter,
+            trend,
+            segments,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fill_up(day: u32, odometer_km: i32, liters: &str, is_full_tank: bool) -> FillUp {
+        let filled_at = Utc.with_ymd_and_hms(2025, 9, day, 8, 0, 0).unwrap();
+        FillUp {
+            id: Uuid::new_v4(),
+            vehicle_id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            transaction_id: None,
+            spbu_id: None,
+            station_name: None,
+            fuel_type: None,
+            liters: liters.parse().unwrap(),
+            odometer_km,
+            is_full_tank,
+            filled_at,
+            created_at: filled_at,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn single_full_fill_has_no_segment() {
+        let report = EfficiencyReport::compute(Uuid::nil(), &[fill_up(1, 1000, "40", true)]);
+        assert_eq!(report.fill_up_count, 1);
+        assert!(report.segments.is_empty());
+        assert_eq!(report.total_distance_km, 0);
+        assert_eq!(report.average_km_per_liter, None);
+        assert_eq!(report.recent_km_per_liter, None);
+        assert_eq!(report.trend, None);
+    }
+
+    #[test]
+    fn partial_fills_are_added_to_the_next_full_fill() {
+        let fill_ups = [
+            fill_up(1, 1000, "40", true),
+            fill_up(3, 1150, "10", false),
+            fill_up(5, 1300, "5.5", false),
+            fill_up(7, 1500, "24.5", true),
+        ];
+        let report = EfficiencyReport::compute(Uuid::nil(), &fill_ups);
+
+        // Liter pengisian penuh pertama tidak dihitung: itu bensin untuk segmen sebelumnya
+        assert_eq!(report.segments.len(), 1);
+        let segment = &report.segments[0];
+        assert_eq!((segment.start_odometer_km, segment.end_odometer_km), (1000, 1500));
+        assert_eq!(segment.distance_km, 500);
+        assert_close(segment.liters, 40.0);
+        assert_close(segment.km_per_liter, 12.5);
+        assert_eq!(segment.anomaly, None);
+        assert_close(report.average_km_per_liter.unwrap(), 12.5);
+    }
+
+    #[test]
+    fn equal_odometer_does_not_create_a_segment() {
+        let fill_ups = [
+            fill_up(1, 1000, "40", true),
+            // Diisi penuh lagi tanpa berjalan; jarak nol tidak boleh membagi
+            fill_up(1, 1000, "2", true),
+            fill_up(4, 1400, "32", true),
+        ];
+        let report = EfficiencyReport::compute(Uuid::nil(), &fill_ups);
+
+        assert_eq!(report.segments.len(), 1);
+        assert_eq!(report.segments[0].distance_km, 400);
+        assert_close(report.segments[0].km_per_liter, 12.5);
+    }
+
+    #[test]
+    fn clear_outlier_is_flagged_against_the_median() {
+        let fill_ups = [
+            fill_up(1, 1000, "40", true),
+            fill_up(4, 1400, "32", true),
+            fill_up(8, 1800, "32", true),
+            fill_up(12, 2200, "32", true),
+            // 400 km dengan 80 liter: 5 km/liter dari median 12.5
+            fill_up(16, 2600, "80", true),
+        ];
+        let report = EfficiencyReport::compute(Uuid::nil(), &fill_ups);
+
+        let anomalies: Vec<_> = report.segments.iter().map(|s| s.anomaly).collect();
+        assert_eq!(anomalies, vec![None, None, None, Some(EfficiencyAnomaly::UnusuallyLow)]);
+        assert_eq!(report.total_distance_km, 1600);
+        assert_close(report.total_liters, 176.0);
+        // Tiga segmen terakhir: 1200 km / 144 liter, jauh di bawah rata-rata 1600 / 176
+        assert_close(report.recent_km_per_liter.unwrap(), 1200.0 / 144.0);
+        assert_eq!(report.trend, Some(EfficiencyTrend::Declining));
+    }
+}
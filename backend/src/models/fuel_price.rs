@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::types::Decimal;
+
+// Harga satu jenis BBM di satu SPBU
+#[derive(Debug, Clone, Serialize)]
+pub struct FuelPrice {
+    pub spbu_id: Uuid,
+    pub fuel_type: String,
+    pub price: Decimal,
+}
+
+// Query GET /fuel-prices/cheapest
+#[derive(Debug, Deserialize)]
+pub struct CheapestFuelQuery {
+    pub fuel_type: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub radius_km: Option<f64>,
+    // Jumlah liter yang ingin dibeli, untuk estimasi total biaya
+    pub liters: Option<Decimal>,
+    // Konsumsi kendaraan (km/liter), untuk menghitung biaya BBM perjalanan ke SPBU
+    pub km_per_liter: Option<f64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheapestFuelOption {
+    pub spbu_id: Uuid,
+    pub spbu_name: String,
+    pub alamat: Option<String>,
+    pub brand_id: Option<Uuid>,
+    pub brand_name: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub fuel_type: String,
+    pub price_per_liter: Decimal,
+    pub distance_km: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_total: Option<Decimal>,
+    // Biaya BBM pulang-pergi ke SPBU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detour_fuel_cost: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_total: Option<Decimal>,
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum QueuedJobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    // Gagal sampai max_attempts, menunggu di-retry admin
+    Dead,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct QueuedJob {
+    pub id: Uuid,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub status: QueuedJobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub locked_by: Option<String>,
+    pub last_error: Option<String>,
+    pub dedupe_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// Query GET /admin/jobs
+#[derive(Debug, Deserialize)]
+pub struct QueuedJobQuery {
+    pub status: Option<QueuedJobStatus>,
+    pub job_type: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct QueuedJobCount {
+    pub job_type: String,
+    pub status: QueuedJobStatus,
+    pub count: i64,
+}
+
+// Riwayat run job terjadwal (tabel job_runs)
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job_name: String,
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub affected_rows: i64,
+    pub error: Option<String>,
+}
+
+// Query GET /admin/job-runs
+#[derive(Debug, Deserialize)]
+pub struct JobRunQuery {
+    pub job_name: Option<String>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LoyaltyAccountType {
+    Member,
+    Issuance,
+    Redemption,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum JournalKind {
+    Earn,
+    Redeem,
+    Reversal,
+}
+
+impl JournalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalKind::Earn => "earn",
+            JournalKind::Redeem => "redeem",
+            JournalKind::Reversal => "reversal",
+        }
+    }
+}
+
+/// Aturan perolehan poin per brand, jenis BBM, dan minimal nominal transaksi
+#[derive(Debug, Serialize)]
+pub struct LoyaltyRule {
+    pub id: Uuid,
+    pub brand_id: Option<Uuid>,
+    pub fuel_type: Option<String>,
+    pub min_amount: Decimal,
+    pub points_per_thousand: Decimal,
+    pub bonus_points: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// min_amount/points_per_thousand harus di-select sebagai text (lihat LOYALTY_RULE_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for LoyaltyRule {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let min_amount: String = row.try_get("min_amount")?;
+        let points_per_thousand: String = row.try_get("points_per_thousand")?;
+        Ok(LoyaltyRule {
+            id: row.try_get("id")?,
+            brand_id: row.try_get("brand_id")?,
+            fuel_type: row.try_get("fuel_type")?,
+            min_amount: Decimal::from_str(&min_amount).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            points_per_thousand: Decimal::from_str(&points_per_thousand)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            bonus_points: row.try_get("bonus_points")?,
+            is_active: row.try_get("is_active")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateLoyaltyRuleRequest {
+    pub brand_id: Option<Uuid>,
+    pub fuel_type: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub points_per_thousand: Decimal,
+    pub bonus_points: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateLoyaltyRuleRequest {
+    pub min_amount: Option<Decimal>,
+    pub points_per_thousand: Option<Decimal>,
+    pub bonus_points: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+// Mutasi poin pada akun member
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PointsEntry {
+    pub journal_id: Uuid,
+    pub kind: JournalKind,
+    pub transaction_id: Option<Uuid>,
+    pub amount: i64,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PointsQuery {
+    pub limit: Option<i64>,
+}
+
+// Response GET /me/points
+#[derive(Debug, Serialize)]
+pub struct PointsResponse {
+    pub balance: i64,
+    // Nilai rupiah satu poin saat ditukar
+    pub point_value: Decimal,
+    pub entries: Vec<PointsEntry>,
+}
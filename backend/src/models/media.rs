@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MediaObject {
+    pub id: Uuid,
+    pub owner_type: String,
+    pub owner_id: Uuid,
+    pub kind: String,
+    pub storage_key: String,
+    pub thumbnail_key: Option<String>,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sort_order: i32,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Response dengan URL download bertanda tangan
+#[derive(Debug, Serialize)]
+pub struct MediaResponse {
+    pub id: Uuid,
+    pub owner_type: String,
+    pub owner_id: Uuid,
+    pub kind: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sort_order: i32,
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Query string untuk GET /media/*key
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlQuery {
+    pub expires: i64,
+    pub signature: String,
+}
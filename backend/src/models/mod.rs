@@ -6,6 +6,29 @@ pub mod spbu_service;
 pub mod wishlist;
 pub mod review;
 pub mod transaction;
+pub mod media;
+pub mod fuel_price;
+pub mod vehicle;
+pub mod quota;
+pub mod fill_up;
+pub mod loyalty;
+pub mod promotion;
+pub mod wallet;
+pub mod organization;
+pub mod statement;
+pub mod operator;
+pub mod spbu_stats;
+pub mod pump;
+pub mod pickup;
+pub mod job;
+pub mod notification;
+
+use serde::{Deserialize, Deserializer};
+
+// Bedakan field yang tidak dikirim (None) dari null (Some(None))
+pub(crate) fn deserialize_some<'de, T: Deserialize<'de>, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    T::deserialize(deserializer).map(Some)
+}
 
 // Re-export commonly used models
-pub use transaction::{Transaction, CreateTransactionRequest, TransactionResponse, TransactionStatus, PaymentStatus};
+pub use transaction::{AdjustmentStatus, Transaction, CreateTransactionRequest, TransactionResponse, TransactionStatus, PaymentStatus};
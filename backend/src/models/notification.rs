@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    PaymentSucceeded,
+    PaymentFailed,
+    TransactionCompleted,
+    // Dibatalkan otomatis karena tidak dibayar
+    TransactionExpired,
+    RefundIssued,
+    // Aturan alert SPBU di wishlist
+    WishlistPriceDrop,
+    WishlistBackInStock,
+    WishlistQueueShort,
+    // Operator SPBU membalas review user
+    ReviewReplied,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 9] = [
+        NotificationEvent::PaymentSucceeded,
+        NotificationEvent::PaymentFailed,
+        NotificationEvent::TransactionCompleted,
+        NotificationEvent::TransactionExpired,
+        NotificationEvent::RefundIssued,
+        NotificationEvent::WishlistPriceDrop,
+        NotificationEvent::WishlistBackInStock,
+        NotificationEvent::WishlistQueueShort,
+        NotificationEvent::ReviewReplied,
+    ];
+
+    /// Kanal yang aktif jika user belum mengatur preferensi untuk event ini
+    pub fn default_channels(self) -> NotificationChannels {
+        let email = matches!(self, NotificationEvent::PaymentSucceeded | NotificationEvent::RefundIssued);
+        NotificationChannels {
+            in_app: true,
+            email,
+            sms: false,
+            push: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Default)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationLanguage {
+    #[default]
+    Id,
+    En,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationChannels {
+    pub in_app: bool,
+    pub email: bool,
+    pub sms: bool,
+    pub push: bool,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub event: NotificationEvent,
+    pub title: String,
+    pub body: String,
+    pub data: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Query GET /me/notifications
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    #[serde(default)]
+    pub unread_only: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationInbox {
+    pub unread_count: i64,
+    pub notifications: Vec<Notification>,
+}
+
+// PUT /me/notifications/:id
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationRequest {
+    pub read: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventPreference {
+    pub event: NotificationEvent,
+    #[serde(flatten)]
+    pub channels: NotificationChannels,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationPreferences {
+    pub language: NotificationLanguage,
+    pub events: Vec<EventPreference>,
+}
+
+// Kanal yang tidak dikirim tetap seperti pengaturan sebelumnya
+#[derive(Debug, Deserialize)]
+pub struct UpdateEventPreference {
+    pub event: NotificationEvent,
+    pub in_app: Option<bool>,
+    pub email: Option<bool>,
+    pub sms: Option<bool>,
+    pub push: Option<bool>,
+}
+
+// PUT /me/notification-preferences
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub language: Option<NotificationLanguage>,
+    #[serde(default)]
+    pub events: Vec<UpdateEventPreference>,
+}
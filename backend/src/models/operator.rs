@@ -0,0 +1,122 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::spbu::Spbu;
+use crate::models::TransactionStatus;
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SpbuStaffRole {
+    Owner,
+    Manager,
+    Cashier,
+}
+
+impl SpbuStaffRole {
+    /// Mengubah data SPBU: harga, stok, jam operasional, layanan
+    pub fn can_manage_station(self) -> bool {
+        matches!(self, SpbuStaffRole::Owner | SpbuStaffRole::Manager)
+    }
+
+    /// Menambah/mengubah/menghapus staf dan menghapus SPBU
+    pub fn can_manage_staff(self) -> bool {
+        self == SpbuStaffRole::Owner
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SpbuStaff {
+    pub id: Uuid,
+    pub spbu_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub nama_lengkap: Option<String>,
+    pub role: SpbuStaffRole,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Item response GET /operator/spbu
+#[derive(Debug, Serialize)]
+pub struct OperatorSpbu {
+    #[serde(flatten)]
+    pub spbu: Spbu,
+    pub role: SpbuStaffRole,
+}
+
+// Harga dan stok satu jenis BBM di SPBU operator
+#[derive(Debug, Serialize)]
+pub struct StationFuel {
+    pub fuel_type: String,
+    pub price: Decimal,
+    pub stock_liters: Option<Decimal>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// price dan stock_liters harus di-select sebagai text
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StationFuel {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let price: String = row.try_get("price")?;
+        let stock_liters: Option<String> = row.try_get("stock_liters")?;
+        Ok(StationFuel {
+            fuel_type: row.try_get("fuel_type")?,
+            price: Decimal::from_str(&price).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            stock_liters: stock_liters
+                .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+                .transpose()?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddStaffRequest {
+    pub email: String,
+    pub role: Option<SpbuStaffRole>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignOwnerRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateStaffRequest {
+    pub role: SpbuStaffRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateFuelPriceRequest {
+    pub price: Decimal,
+}
+
+// stock_liters null = berhenti memantau stok
+#[derive(Debug, Deserialize)]
+pub struct UpdateFuelStockRequest {
+    pub stock_liters: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateQueueRequest {
+    pub jumlah_antrian: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateHoursRequest {
+    pub buka_24_jam: bool,
+    pub jam_buka: Option<NaiveTime>,
+    pub jam_tutup: Option<NaiveTime>,
+}
+
+// Query GET /operator/spbu/:id/transactions
+#[derive(Debug, Deserialize)]
+pub struct OperatorTransactionQuery {
+    pub status: Option<TransactionStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -0,0 +1,252 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::vehicle::VehicleType;
+use crate::models::TransactionResponse;
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Admin,
+    Driver,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceStatus {
+    Open,
+    Paid,
+}
+
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Option<Decimal>, sqlx::Error> {
+    row.try_get::<Option<String>, _>(column)?
+        .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+        .transpose()
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub billing_email: Option<String>,
+    pub tax_id: Option<String>,
+    pub allowed_fuel_types: Option<Vec<String>>,
+    pub allowed_brand_ids: Option<Vec<Uuid>>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    pub fn allows_fuel_type(&self, fuel_type: &str) -> bool {
+        self.allowed_fuel_types
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|f| f.eq_ignore_ascii_case(fuel_type)))
+    }
+
+    pub fn allows_brand(&self, brand_id: Option<Uuid>) -> bool {
+        match &self.allowed_brand_ids {
+            None => true,
+            Some(allowed) => brand_id.is_some_and(|id| allowed.contains(&id)),
+        }
+    }
+}
+
+/// Batas belanja harian/bulanan dalam rupiah dan liter. None = tidak dibatasi.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SpendingLimits {
+    pub daily_amount_limit: Option<Decimal>,
+    pub monthly_amount_limit: Option<Decimal>,
+    pub daily_liter_limit: Option<Decimal>,
+    pub monthly_liter_limit: Option<Decimal>,
+}
+
+impl SpendingLimits {
+    // Kolom limit harus di-select sebagai text
+    pub fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(SpendingLimits {
+            daily_amount_limit: decimal_column(row, "daily_amount_limit")?,
+            monthly_amount_limit: decimal_column(row, "monthly_amount_limit")?,
+            daily_liter_limit: decimal_column(row, "daily_liter_limit")?,
+            monthly_liter_limit: decimal_column(row, "monthly_liter_limit")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrganizationMember {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub nama_lengkap: Option<String>,
+    pub role: OrganizationRole,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrganizationMember {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(OrganizationMember {
+            id: row.try_get("id")?,
+            organization_id: row.try_get("organization_id")?,
+            user_id: row.try_get("user_id")?,
+            email: row.try_get("email")?,
+            nama_lengkap: row.try_get("nama_lengkap")?,
+            role: row.try_get("role")?,
+            limits: SpendingLimits::from_row(row)?,
+            is_active: row.try_get("is_active")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrganizationVehicle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub vehicle_id: Uuid,
+    pub plate_number: String,
+    pub vehicle_type: VehicleType,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrganizationVehicle {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(OrganizationVehicle {
+            id: row.try_get("id")?,
+            organization_id: row.try_get("organization_id")?,
+            vehicle_id: row.try_get("vehicle_id")?,
+            plate_number: row.try_get("plate_number")?,
+            vehicle_type: row.try_get("vehicle_type")?,
+            limits: SpendingLimits::from_row(row)?,
+            is_active: row.try_get("is_active")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrganizationInvoice {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub transaction_count: i32,
+    pub total_amount: Decimal,
+    pub status: InvoiceStatus,
+    pub due_date: NaiveDate,
+    pub issued_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+// total_amount harus di-select sebagai text (lihat INVOICE_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrganizationInvoice {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(OrganizationInvoice {
+            id: row.try_get("id")?,
+            organization_id: row.try_get("organization_id")?,
+            period_start: row.try_get("period_start")?,
+            period_end: row.try_get("period_end")?,
+            transaction_count: row.try_get("transaction_count")?,
+            total_amount: decimal_column(row, "total_amount")?.unwrap_or_default(),
+            status: row.try_get("status")?,
+            due_date: row.try_get("due_date")?,
+            issued_at: row.try_get("issued_at")?,
+            paid_at: row.try_get("paid_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceDetail {
+    #[serde(flatten)]
+    pub invoice: OrganizationInvoice,
+    pub transactions: Vec<TransactionResponse>,
+}
+
+// Item response GET /me/organizations
+#[derive(Debug, Serialize)]
+pub struct MyOrganization {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    pub billing_email: Option<String>,
+    pub tax_id: Option<String>,
+    pub allowed_fuel_types: Option<Vec<String>>,
+    pub allowed_brand_ids: Option<Vec<Uuid>>,
+}
+
+// allowed_fuel_types/allowed_brand_ids: kirim [] untuk menghapus pembatasan
+#[derive(Debug, Deserialize)]
+pub struct UpdateOrganizationRequest {
+    pub name: Option<String>,
+    pub billing_email: Option<String>,
+    pub tax_id: Option<String>,
+    pub allowed_fuel_types: Option<Vec<String>>,
+    pub allowed_brand_ids: Option<Vec<Uuid>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddMemberRequest {
+    pub email: String,
+    pub role: Option<OrganizationRole>,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+}
+
+// Limit selalu diganti seluruhnya; limit yang tidak dikirim menjadi tidak dibatasi
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: Option<OrganizationRole>,
+    pub is_active: Option<bool>,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddOrganizationVehicleRequest {
+    pub vehicle_id: Uuid,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOrganizationVehicleRequest {
+    pub is_active: Option<bool>,
+    #[serde(flatten)]
+    pub limits: SpendingLimits,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvoiceRequest {
+    // Format YYYY-MM
+    pub period: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayInvoiceRequest {
+    pub payment_method: String,
+}
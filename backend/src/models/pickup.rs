@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+use crate::types::Decimal;
+
+// POST /operator/redeem
+// dispensed_liters diisi jika pengisian sudah selesai saat kode dipindai
+#[derive(Debug, Deserialize)]
+pub struct RedeemPickupRequest {
+    pub code: String,
+    pub dispensed_liters: Option<Decimal>,
+}
+
+// POST /operator/spbu/:id/transactions/:transaction_id/complete
+#[derive(Debug, Deserialize)]
+pub struct CompletePickupRequest {
+    pub dispensed_liters: Decimal,
+}
@@ -0,0 +1,136 @@
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DiscountType {
+    Percentage,
+    Fixed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Promotion {
+    pub id: Uuid,
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: DiscountType,
+    pub discount_value: Decimal,
+    pub max_discount: Option<Decimal>,
+    pub min_purchase: Decimal,
+    pub brand_id: Option<Uuid>,
+    pub spbu_id: Option<Uuid>,
+    pub fuel_type: Option<String>,
+    pub first_time_only: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_user: Option<i32>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Option<Decimal>, sqlx::Error> {
+    row.try_get::<Option<String>, _>(column)?
+        .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+        .transpose()
+}
+
+// Kolom numeric harus di-select sebagai text (lihat PROMOTION_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Promotion {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Promotion {
+            id: row.try_get("id")?,
+            code: row.try_get("code")?,
+            description: row.try_get("description")?,
+            discount_type: row.try_get("discount_type")?,
+            discount_value: decimal_column(row, "discount_value")?.unwrap_or_default(),
+            max_discount: decimal_column(row, "max_discount")?,
+            min_purchase: decimal_column(row, "min_purchase")?.unwrap_or_default(),
+            brand_id: row.try_get("brand_id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            fuel_type: row.try_get("fuel_type")?,
+            first_time_only: row.try_get("first_time_only")?,
+            starts_at: row.try_get("starts_at")?,
+            ends_at: row.try_get("ends_at")?,
+            max_uses: row.try_get("max_uses")?,
+            max_uses_per_user: row.try_get("max_uses_per_user")?,
+            is_active: row.try_get("is_active")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl Promotion {
+    /// Potongan untuk nominal pembelian, dibulatkan ke bawah ke rupiah penuh
+    /// dan tidak pernah melebihi nominal pembelian
+    pub fn discount_for(&self, amount: &Decimal) -> Decimal {
+        let discount = match self.discount_type {
+            DiscountType::Percentage => {
+                let discount = &amount.0 * &self.discount_value.0 / BigDecimal::from(100);
+                match &self.max_discount {
+                    Some(max) if discount > max.0 => max.0.clone(),
+                    _ => discount,
+                }
+            }
+            DiscountType::Fixed => self.discount_value.0.clone(),
+        };
+        let discount = discount.with_scale_round(0, RoundingMode::Down);
+        Decimal(if discount > amount.0 { amount.0.clone() } else { discount })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePromotionRequest {
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: DiscountType,
+    pub discount_value: Decimal,
+    pub max_discount: Option<Decimal>,
+    pub min_purchase: Option<Decimal>,
+    pub brand_id: Option<Uuid>,
+    pub spbu_id: Option<Uuid>,
+    pub fuel_type: Option<String>,
+    #[serde(default)]
+    pub first_time_only: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_user: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePromotionRequest {
+    pub description: Option<String>,
+    pub max_discount: Option<Decimal>,
+    pub min_purchase: Option<Decimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_user: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+// Body POST /promotions/validate: cek kode sebelum membuat transaksi
+#[derive(Debug, Deserialize)]
+pub struct ValidatePromotionRequest {
+    pub code: String,
+    pub spbu_id: Uuid,
+    pub fuel_type: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidatePromotionResponse {
+    pub code: String,
+    pub discount: Decimal,
+    pub total_after_discount: Decimal,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PumpStatus {
+    Active,
+    Maintenance,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Pump {
+    pub id: Uuid,
+    pub spbu_id: Uuid,
+    pub pump_number: i32,
+    // Kosong = melayani semua jenis BBM yang dijual SPBU
+    pub fuel_types: Vec<String>,
+    pub status: PumpStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Pump {
+    pub fn serves(&self, fuel_type: &str) -> bool {
+        self.fuel_types.is_empty() || self.fuel_types.iter().any(|f| f.eq_ignore_ascii_case(fuel_type))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePumpRequest {
+    pub pump_number: i32,
+    pub fuel_types: Option<Vec<String>>,
+    pub status: Option<PumpStatus>,
+}
+
+// fuel_types: kirim [] untuk melayani semua jenis BBM
+#[derive(Debug, Deserialize)]
+pub struct UpdatePumpRequest {
+    pub fuel_types: Option<Vec<String>>,
+    pub status: Option<PumpStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePumpStatusRequest {
+    pub status: PumpStatus,
+}
+
+// pump_id null = lepas pesanan dari pompa
+#[derive(Debug, Deserialize)]
+pub struct AssignPumpRequest {
+    pub pump_id: Option<Uuid>,
+}
+
+// Query GET /spbu/:spbu_id/wait-time
+#[derive(Debug, Deserialize)]
+pub struct WaitTimeQuery {
+    pub fuel_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WaitTimeEstimate {
+    pub spbu_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuel_type: Option<String>,
+    pub total_pumps: i64,
+    // Pompa aktif yang melayani fuel_type (atau semua pompa aktif)
+    pub active_pumps: i64,
+    // Antrian yang dilaporkan operator + pesanan aplikasi yang belum selesai
+    pub queue_length: i64,
+    // None jika SPBU belum punya data pompa atau tidak ada pompa aktif
+    pub estimated_wait_minutes: Option<i64>,
+}
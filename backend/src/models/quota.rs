@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::vehicle::VehicleType;
+use crate::types::Decimal;
+
+// Parse kolom numeric yang di-select sebagai text
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Option<Decimal>, sqlx::Error> {
+    row.try_get::<Option<String>, _>(column)?
+        .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+        .transpose()
+}
+
+/// Kebijakan kuota BBM bersubsidi untuk satu jenis BBM dan jenis kendaraan
+#[derive(Debug, Serialize)]
+pub struct QuotaPolicy {
+    pub id: Uuid,
+    pub fuel_type: String,
+    pub vehicle_type: VehicleType,
+    pub daily_limit: Option<Decimal>,
+    pub monthly_limit: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// daily_limit/monthly_limit harus di-select sebagai text (lihat QUOTA_POLICY_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for QuotaPolicy {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(QuotaPolicy {
+            id: row.try_get("id")?,
+            fuel_type: row.try_get("fuel_type")?,
+            vehicle_type: row.try_get("vehicle_type")?,
+            daily_limit: decimal_column(row, "daily_limit")?,
+            monthly_limit: decimal_column(row, "monthly_limit")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertQuotaPolicyRequest {
+    pub fuel_type: String,
+    pub vehicle_type: VehicleType,
+    pub daily_limit: Option<Decimal>,
+    pub monthly_limit: Option<Decimal>,
+}
+
+/// Jendela waktu kuota (rolling, dihitung mundur dari sekarang)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaWindow {
+    Daily,
+    Monthly,
+}
+
+impl QuotaWindow {
+    /// Panjang jendela dalam format interval Postgres
+    pub fn interval(&self) -> &'static str {
+        match self {
+            QuotaWindow::Daily => "24 hours",
+            QuotaWindow::Monthly => "30 days",
+        }
+    }
+}
+
+impl std::fmt::Display for QuotaWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaWindow::Daily => write!(f, "daily"),
+            QuotaWindow::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuotaUsage {
+    pub limit: Decimal,
+    pub used: Decimal,
+    pub remaining: Decimal,
+}
+
+impl QuotaUsage {
+    pub fn new(limit: Decimal, used: Decimal) -> Self {
+        let remaining = if used >= limit {
+            Decimal::from(0)
+        } else {
+            limit.clone() - used.clone()
+        };
+        QuotaUsage { limit, used, remaining }
+    }
+}
+
+// Item response GET /me/quota
+#[derive(Debug, Serialize)]
+pub struct VehicleQuota {
+    pub vehicle_id: Uuid,
+    pub plate_number: String,
+    pub vehicle_type: VehicleType,
+    pub fuel_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily: Option<QuotaUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<QuotaUsage>,
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::models::media::MediaResponse;
+
 #[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct Review {
     pub id: Uuid,
@@ -27,7 +29,7 @@ pub struct UpdateReviewRequest {
     pub comment: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, FromRow)]
 pub struct ReviewResponse {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -36,10 +38,54 @@ pub struct ReviewResponse {
     pub spbu_name: String,
     pub rating: f64,
     pub comment: Option<String>,
+    #[sqlx(skip)]
+    pub photos: Vec<MediaResponse>,
+    // Balasan operator SPBU, jika ada
+    #[sqlx(skip)]
+    pub reply: Option<ReviewReply>,
+    pub helpful_count: i64,
+    // Apakah user yang sedang login sudah memberi vote "membantu"
+    pub has_voted: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewSort {
+    #[default]
+    Newest,
+    Highest,
+    Lowest,
+    MostHelpful,
+}
+
+// Query GET /spbu/:spbu_id/reviews
+#[derive(Debug, Deserialize)]
+pub struct ReviewQuery {
+    #[serde(default)]
+    pub sort: ReviewSort,
+    // Filter bintang 1-5; rating 4.5 termasuk bintang 4
+    pub rating: Option<i32>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewList {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub reviews: Vec<ReviewResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HelpfulVoteResponse {
+    pub review_id: Uuid,
+    pub helpful_count: i64,
+    pub has_voted: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RatingCount {
     pub rating: i32,
@@ -52,3 +98,38 @@ pub struct SpbuRatingResponse {
     pub total_reviews: i64,
     pub rating_distribution: Vec<RatingCount>,
 }
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReviewReply {
+    pub id: Uuid,
+    pub review_id: Uuid,
+    pub body: String,
+    // true jika balasan pernah diubah; riwayatnya di GET /reviews/:review_id/reply/history
+    pub edited: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// POST/PUT /operator/reviews/:review_id/reply
+#[derive(Debug, Deserialize)]
+pub struct ReviewReplyRequest {
+    pub body: String,
+}
+
+// Isi balasan sebelum diubah
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReviewReplyEdit {
+    pub body: String,
+    pub edited_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpbuReviewStats {
+    pub spbu_id: Uuid,
+    pub total_reviews: i64,
+    pub replied_reviews: i64,
+    // replied_reviews / total_reviews, 0 jika belum ada review
+    pub response_rate: f64,
+    // Rata-rata jarak waktu review ke balasan pertama, dalam jam
+    pub average_response_hours: Option<f64>,
+}
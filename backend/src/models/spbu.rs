@@ -1,6 +1,9 @@
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, NaiveTime};
+
+use crate::geocoding::Coordinates;
+use crate::models::fuel_price::FuelPrice;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Spbu {
@@ -14,6 +17,55 @@ pub struct Spbu {
     pub jumlah_pompa: Option<i32>,
     pub jumlah_antrian: Option<i32>,
     pub foto: Option<String>,
+    // Jam operasional, diatur operator lewat PUT /operator/spbu/:id/hours
+    pub buka_24_jam: Option<bool>,
+    pub jam_buka: Option<NaiveTime>,
+    pub jam_tutup: Option<NaiveTime>,
+    // Diisi server: NULL jika lokasi valid, atau alasan lokasi perlu dicek
+    pub location_flag: Option<String>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
-}
\ No newline at end of file
+}
+// Request pencarian SPBU di sepanjang rute; isi salah satu dari polyline atau waypoints
+#[derive(Debug, Deserialize)]
+pub struct AlongRouteRequest {
+    pub polyline: Option<String>,
+    pub waypoints: Option<Vec<Coordinates>>,
+    pub max_detour_km: Option<f64>,
+    pub fuel_type: Option<String>,
+    pub brand_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpbuAlongRoute {
+    #[serde(flatten)]
+    pub spbu: Spbu,
+    pub distance_along_route_km: f64,
+    pub distance_from_route_km: f64,
+    pub fuel_prices: Vec<FuelPrice>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AlongRouteResponse {
+    pub route_length_km: f64,
+    pub spbus: Vec<SpbuAlongRoute>,
+}
+
+/// Apakah SPBU buka pada jam lokal `time`. `None` jika jam operasional belum diisi operator.
+pub fn is_open_at(
+    buka_24_jam: Option<bool>,
+    jam_buka: Option<NaiveTime>,
+    jam_tutup: Option<NaiveTime>,
+    time: NaiveTime,
+) -> Option<bool> {
+    if buka_24_jam == Some(true) {
+        return Some(true);
+    }
+    let (buka, tutup) = (jam_buka?, jam_tutup?);
+    if buka < tutup {
+        Some(buka <= time && time < tutup)
+    } else {
+        // Tutup keesokan harinya
+        Some(time >= buka || time < tutup)
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsGranularity {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl StatsGranularity {
+    // Argumen date_trunc di PostgreSQL
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatsGranularity::Hour => "hour",
+            StatsGranularity::Day => "day",
+            StatsGranularity::Week => "week",
+        }
+    }
+}
+
+// Query GET /operator/spbu/:id/stats; from/to inklusif, tanggal WIB
+#[derive(Debug, Deserialize)]
+pub struct SpbuStatsQuery {
+    pub granularity: Option<StatsGranularity>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Decimal, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    Decimal::from_str(&value).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsSummary {
+    pub transaction_count: i64,
+    pub completed_count: i64,
+    pub cancelled_count: i64,
+    // cancelled / semua transaksi dalam rentang
+    pub cancellation_rate: f64,
+    pub liters_sold: Decimal,
+    pub revenue: Decimal,
+    // Rata-rata nilai transaksi completed
+    pub average_ticket: Option<Decimal>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StatsSummary {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let transaction_count: i64 = row.try_get("transaction_count")?;
+        let cancelled_count: i64 = row.try_get("cancelled_count")?;
+        let average_ticket: Option<String> = row.try_get("average_ticket")?;
+        Ok(StatsSummary {
+            transaction_count,
+            completed_count: row.try_get("completed_count")?,
+            cancelled_count,
+            cancellation_rate: if transaction_count > 0 {
+                cancelled_count as f64 / transaction_count as f64
+            } else {
+                0.0
+            },
+            liters_sold: decimal_column(row, "liters_sold")?,
+            revenue: decimal_column(row, "revenue")?,
+            average_ticket: average_ticket
+                .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+                .transpose()?,
+        })
+    }
+}
+
+// Penjualan (transaksi completed) per jenis BBM per bucket
+#[derive(Debug, Serialize)]
+pub struct SalesBucket {
+    // Waktu lokal WIB
+    pub bucket_start: NaiveDateTime,
+    pub fuel_type: String,
+    pub transaction_count: i64,
+    pub liters_sold: Decimal,
+    pub revenue: Decimal,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SalesBucket {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(SalesBucket {
+            bucket_start: row.try_get("bucket_start")?,
+            fuel_type: row.try_get("fuel_type")?,
+            transaction_count: row.try_get("transaction_count")?,
+            liters_sold: decimal_column(row, "liters_sold")?,
+            revenue: decimal_column(row, "revenue")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentMethodShare {
+    pub payment_method: String,
+    pub transaction_count: i64,
+    pub revenue: Decimal,
+    // Porsi dari jumlah transaksi completed
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReviewBucket {
+    pub bucket_start: NaiveDateTime,
+    pub review_count: i64,
+    pub average_rating: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewStats {
+    // Review yang dibuat dalam rentang
+    pub review_count: i64,
+    pub average_rating: Option<f64>,
+    pub trend: Vec<ReviewBucket>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WishlistStats {
+    pub total: i64,
+    // Ditambahkan dalam rentang
+    pub added: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpbuStats {
+    pub spbu_id: Uuid,
+    pub granularity: StatsGranularity,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: &'static str,
+    pub summary: StatsSummary,
+    pub sales: Vec<SalesBucket>,
+    pub payment_methods: Vec<PaymentMethodShare>,
+    pub reviews: ReviewStats,
+    pub wishlists: WishlistStats,
+    pub refreshed_at: DateTime<Utc>,
+}
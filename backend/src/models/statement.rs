@@ -0,0 +1,380 @@
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::pdf;
+use crate::types::Decimal;
+
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Decimal, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    Decimal::from_str(&value).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+/// Pisahkan nominal yang sudah termasuk PPN menjadi (DPP, PPN), dibulatkan ke sen
+pub fn split_tax(amount: &Decimal, tax_rate: &Decimal) -> (Decimal, Decimal) {
+    let hundred = BigDecimal::from(100);
+    let subtotal = (&amount.0 * &hundred / (&hundred + &tax_rate.0)).with_scale_round(2, RoundingMode::HalfUp);
+    let tax = &amount.0 - &subtotal;
+    (Decimal(subtotal), Decimal(tax))
+}
+
+/// Header statement yang sudah diterbitkan
+#[derive(Debug)]
+pub struct Statement {
+    pub id: Uuid,
+    pub statement_number: String,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub tax_rate: Decimal,
+    pub issued_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Statement {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Statement {
+            id: row.try_get("id")?,
+            statement_number: row.try_get("statement_number")?,
+            user_id: row.try_get("user_id")?,
+            organization_id: row.try_get("organization_id")?,
+            tax_rate: decimal_column(row, "tax_rate")?,
+            issued_at: row.try_get("issued_at")?,
+        })
+    }
+}
+
+// Ringkasan statement yang sudah diterbitkan (GET /me/statements)
+#[derive(Debug, Serialize)]
+pub struct StatementSummary {
+    pub id: Uuid,
+    pub statement_number: String,
+    pub period: String,
+    pub transaction_count: i32,
+    pub total_amount: Decimal,
+    pub issued_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StatementSummary {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let period_start: NaiveDate = row.try_get("period_start")?;
+        Ok(StatementSummary {
+            id: row.try_get("id")?,
+            statement_number: row.try_get("statement_number")?,
+            period: period_start.format("%Y-%m").to_string(),
+            transaction_count: row.try_get("transaction_count")?,
+            total_amount: decimal_column(row, "total_amount")?,
+            issued_at: row.try_get("issued_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementLine {
+    pub line_number: i32,
+    pub transaction_id: Option<Uuid>,
+    pub transaction_date: DateTime<Utc>,
+    pub user_id: Option<Uuid>,
+    pub spbu_id: Option<Uuid>,
+    pub spbu_name: String,
+    pub vehicle_id: Option<Uuid>,
+    pub plate_number: Option<String>,
+    pub fuel_type: String,
+    pub quantity: Decimal,
+    pub price_per_liter: Decimal,
+    // Potongan promo + poin
+    pub discount: Decimal,
+    // Nominal dibayar (termasuk PPN)
+    pub amount: Decimal,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+}
+
+// Kolom numeric harus di-select sebagai text (lihat STATEMENT_LINE_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StatementLine {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(StatementLine {
+            line_number: row.try_get("line_number")?,
+            transaction_id: row.try_get("transaction_id")?,
+            transaction_date: row.try_get("transaction_date")?,
+            user_id: row.try_get("user_id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            spbu_name: row.try_get("spbu_name")?,
+            vehicle_id: row.try_get("vehicle_id")?,
+            plate_number: row.try_get("plate_number")?,
+            fuel_type: row.try_get("fuel_type")?,
+            quantity: decimal_column(row, "quantity")?,
+            price_per_liter: decimal_column(row, "price_per_liter")?,
+            discount: decimal_column(row, "discount")?,
+            amount: decimal_column(row, "amount")?,
+            subtotal: decimal_column(row, "subtotal")?,
+            tax_amount: decimal_column(row, "tax_amount")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementBreakdown {
+    pub label: String,
+    pub transaction_count: i64,
+    pub quantity: Decimal,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementStatus {
+    Issued,
+    // Periode belum selesai atau belum ada transaksi; tidak disimpan
+    Preview,
+}
+
+/// Statement lengkap untuk dirender ke JSON, CSV, atau PDF
+#[derive(Debug, Serialize)]
+pub struct StatementDocument {
+    pub statement_number: Option<String>,
+    pub status: StatementStatus,
+    pub period: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub account_name: String,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub transaction_count: usize,
+    pub subtotal: Decimal,
+    pub tax_rate: Decimal,
+    pub tax_amount: Decimal,
+    pub total_amount: Decimal,
+    pub by_vehicle: Vec<StatementBreakdown>,
+    pub by_fuel_type: Vec<StatementBreakdown>,
+    pub by_spbu: Vec<StatementBreakdown>,
+    pub lines: Vec<StatementLine>,
+}
+
+fn breakdown<F>(lines: &[StatementLine], label: F) -> Vec<StatementBreakdown>
+where
+    F: Fn(&StatementLine) -> String,
+{
+    let mut groups: BTreeMap<String, StatementBreakdown> = BTreeMap::new();
+    for line in lines {
+        let key = label(line);
+        let group = groups.entry(key.clone()).or_insert_with(|| StatementBreakdown {
+            label: key,
+            transaction_count: 0,
+            quantity: Decimal::default(),
+            amount: Decimal::default(),
+        });
+        group.transaction_count += 1;
+        group.quantity = group.quantity.clone() + line.quantity.clone();
+        group.amount = group.amount.clone() + line.amount.clone();
+    }
+    groups.into_values().collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl StatementDocument {
+    pub fn new(
+        header: Option<&Statement>,
+        account_name: String,
+        (period_start, period_end): (NaiveDate, NaiveDate),
+        tax_rate: Decimal,
+        lines: Vec<StatementLine>,
+    ) -> Self {
+        let mut subtotal = Decimal::default();
+        let mut tax_amount = Decimal::default();
+        let mut total_amount = Decimal::default();
+        for line in &lines {
+            subtotal = subtotal + line.subtotal.clone();
+            tax_amount = tax_amount + line.tax_amount.clone();
+            total_amount = total_amount + line.amount.clone();
+        }
+
+        StatementDocument {
+            statement_number: header.map(|h| h.statement_number.clone()),
+            status: if header.is_some() { StatementStatus::Issued } else { StatementStatus::Preview },
+            period: period_start.format("%Y-%m").to_string(),
+            period_start,
+            period_end,
+            account_name,
+            user_id: header.and_then(|h| h.user_id),
+            organization_id: header.and_then(|h| h.organization_id),
+            issued_at: header.map(|h| h.issued_at),
+            transaction_count: lines.len(),
+            subtotal,
+            tax_rate,
+            tax_amount,
+            total_amount,
+            by_vehicle: breakdown(&lines, |l| l.plate_number.clone().unwrap_or_else(|| "-".to_string())),
+            by_fuel_type: breakdown(&lines, |l| l.fuel_type.clone()),
+            by_spbu: breakdown(&lines, |l| l.spbu_name.clone()),
+            lines,
+        }
+    }
+
+    fn file_stem(&self) -> String {
+        match &self.statement_number {
+            Some(number) => number.replace('/', "-"),
+            None => format!("preview-{}", self.period),
+        }
+    }
+
+    pub fn file_name(&self, extension: &str) -> String {
+        format!("{}.{}", self.file_stem(), extension)
+    }
+
+    /// Tanggal transaksi ditulis dalam zona waktu `offset`
+    pub fn to_csv(&self, offset: &FixedOffset) -> String {
+        let mut csv = String::from(
+            "line,date,spbu,plate_number,fuel_type,quantity,price_per_liter,discount,subtotal,tax_amount,amount\n",
+        );
+        for line in &self.lines {
+            let fields = [
+                line.line_number.to_string(),
+                line.transaction_date.with_timezone(offset).format("%Y-%m-%d %H:%M").to_string(),
+                line.spbu_name.clone(),
+                line.plate_number.clone().unwrap_or_default(),
+                line.fuel_type.clone(),
+                line.quantity.to_string(),
+                line.price_per_liter.to_string(),
+                line.discount.to_string(),
+                line.subtotal.to_string(),
+                line.tax_amount.to_string(),
+                line.amount.to_string(),
+            ];
+            let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv.push_str(&format!(
+            "TOTAL,,,,,,,,{},{},{}\n",
+            self.subtotal, self.tax_amount, self.total_amount
+        ));
+        csv
+    }
+
+    fn text_lines(&self, offset: &FixedOffset) -> Vec<String> {
+        let rule = "-".repeat(pdf::LINE_WIDTH.min(130));
+        let mut out = vec![
+            "STATEMENT TRANSAKSI BBM".to_string(),
+            format!(
+                "No. Statement : {}",
+                self.statement_number.as_deref().unwrap_or("PREVIEW (belum diterbitkan)")
+            ),
+            format!("Akun          : {}", self.account_name),
+            format!(
+                "Periode       : {} ({} s/d {})",
+                self.period, self.period_start, self.period_end.pred_opt().unwrap_or(self.period_end)
+            ),
+        ];
+        if let Some(issued_at) = self.issued_at {
+            out.push(format!(
+                "Diterbitkan   : {}",
+                issued_at.with_timezone(offset).format("%Y-%m-%d %H:%M (UTC%:z)")
+            ));
+        }
+        out.push(String::new());
+        out.push(format!(
+            "{:>3}  {:<16}  {:<24}  {:<10}  {:<14}  {:>8}  {:>10}  {:>10}  {:>12}  {:>10}  {:>12}",
+            "No", "Tanggal", "SPBU", "Nopol", "BBM", "Liter", "Harga/L", "Diskon", "DPP", "PPN", "Jumlah"
+        ));
+        out.push(rule.clone());
+        for line in &self.lines {
+            out.push(format!(
+                "{:>3}  {:<16}  {:<24}  {:<10}  {:<14}  {:>8}  {:>10}  {:>10}  {:>12}  {:>10}  {:>12}",
+                line.line_number,
+                line.transaction_date.with_timezone(offset).format("%Y-%m-%d %H:%M").to_string(),
+                line.spbu_name.chars().take(24).collect::<String>(),
+                line.plate_number.as_deref().unwrap_or("-"),
+                line.fuel_type.chars().take(14).collect::<String>(),
+                line.quantity.to_string(),
+                line.price_per_liter.to_string(),
+                line.discount.to_string(),
+                line.subtotal.to_string(),
+                line.tax_amount.to_string(),
+                line.amount.to_string(),
+            ));
+        }
+        out.push(rule.clone());
+        out.push(format!("Jumlah transaksi : {}", self.transaction_count));
+        out.push(format!("DPP              : Rp {}", self.subtotal));
+        out.push(format!("PPN {:>5}%       : Rp {}", self.tax_rate.to_string(), self.tax_amount));
+        out.push(format!("Total            : Rp {}", self.total_amount));
+
+        for (title, groups) in [
+            ("Per kendaraan", &self.by_vehicle),
+            ("Per jenis BBM", &self.by_fuel_type),
+            ("Per SPBU", &self.by_spbu),
+        ] {
+            out.push(String::new());
+            out.push(title.to_string());
+            for group in groups {
+                out.push(format!(
+                    "  {:<40}  {:>4} trx  {:>10} L  Rp {:>14}",
+                    group.label.chars().take(40).collect::<String>(),
+                    group.transaction_count,
+                    group.quantity.to_string(),
+                    group.amount.to_string()
+                ));
+            }
+        }
+        out
+    }
+
+    pub fn to_pdf(&self, offset: &FixedOffset) -> Vec<u8> {
+        pdf::render_text(&self.text_lines(offset))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    // json (default), csv, atau pdf
+    pub format: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(amount: &str) -> (String, String) {
+        let (subtotal, tax) = split_tax(&amount.parse().unwrap(), &Decimal::from(11));
+        (subtotal.to_string(), tax.to_string())
+    }
+
+    #[test]
+    fn split_tax_exact_amount() {
+        assert_eq!(split("111000"), ("100000.00".to_string(), "11000.00".to_string()));
+    }
+
+    #[test]
+    fn split_tax_rounds_subtotal_and_keeps_sum() {
+        // 10000 x 100 / 111 = 9009.009... -> 9009.01
+        let (subtotal, tax) = split("10000");
+        assert_eq!(subtotal, "9009.01");
+        assert_eq!(tax, "990.99");
+
+        for amount in ["1", "0.01", "12345.67", "987654.32"] {
+            let (subtotal, tax) = split_tax(&amount.parse().unwrap(), &Decimal::from(11));
+            assert_eq!(subtotal.0 + tax.0, amount.parse::<BigDecimal>().unwrap());
+        }
+    }
+
+    #[test]
+    fn split_tax_zero_amount_and_rate() {
+        let (subtotal, tax) = split_tax(&Decimal::default(), &Decimal::from(11));
+        assert_eq!(subtotal.0, BigDecimal::from(0));
+        assert_eq!(tax.0, BigDecimal::from(0));
+        let (subtotal, tax) = split_tax(&"5000".parse().unwrap(), &Decimal::from(0));
+        assert_eq!(subtotal.0, BigDecimal::from(5000));
+        assert_eq!(tax.0, BigDecimal::from(0));
+    }
+}
@@ -5,6 +5,7 @@ use uuid::Uuid;
 use std::str::FromStr;
 use std::fmt;
 
+use crate::pickup::PickupCode;
 use crate::types::Decimal;
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 
@@ -13,10 +14,24 @@ pub struct Transaction {
     pub id: Uuid,
     pub user_id: Uuid,
     pub spbu_id: Uuid,
+    pub vehicle_id: Option<Uuid>,
+    pub plate_number: Option<String>,
     pub fuel_type: String,
     pub quantity: Decimal,
     pub price_per_liter: Decimal,
     pub total_price: Decimal,
+    pub points_redeemed: i64,
+    pub points_discount: Decimal,
+    pub promo_code: Option<String>,
+    pub promo_discount: Decimal,
+    pub organization_id: Option<Uuid>,
+    pub invoice_id: Option<Uuid>,
+    pub pump_id: Option<Uuid>,
+    pub dispensed_quantity: Option<Decimal>,
+    pub final_price: Option<Decimal>,
+    pub adjustment_amount: Option<Decimal>,
+    pub adjustment_status: Option<AdjustmentStatus>,
+    pub pickup_redeemed_at: Option<DateTime<Utc>>,
     pub status: TransactionStatus,
     pub payment_method: String,
     pub payment_status: PaymentStatus,
@@ -28,6 +43,28 @@ pub struct Transaction {
     pub paid_at: Option<DateTime<Utc>>,
 }
 
+impl Transaction {
+    /// Harga akhir setelah rekonsiliasi liter aktual, atau harga pesanan jika belum direkonsiliasi
+    pub fn billed_price(&self) -> &Decimal {
+        self.final_price.as_ref().unwrap_or(&self.total_price)
+    }
+}
+
+// Hasil rekonsiliasi saat liter aktual berbeda dari pesanan
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AdjustmentStatus {
+    // Kelebihan bayar dikembalikan ke dompet
+    Refunded,
+    // Liter aktual lebih banyak dari pesanan, selisihnya belum dibayar
+    Underpaid,
+    // Transaksi organisasi; selisih ikut tagihan bulanan berikutnya karena tagihan memakai harga akhir
+    #[sqlx(rename = "pending_invoice")]
+    #[serde(rename = "pending_invoice")]
+    PendingInvoice,
+}
+
 #[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
 #[sqlx(type_name = "transaction_status", rename_all = "lowercase")]
 pub enum TransactionStatus {
@@ -67,6 +104,8 @@ pub enum PaymentStatus {
     Paid,
     #[serde(rename = "failed")]
     Failed,
+    #[serde(rename = "refunded")]
+    Refunded,
 }
 
 impl std::str::FromStr for PaymentStatus {
@@ -77,6 +116,7 @@ impl std::str::FromStr for PaymentStatus {
             "pending" => Ok(PaymentStatus::Pending),
             "paid" => Ok(PaymentStatus::Paid),
             "failed" => Ok(PaymentStatus::Failed),
+            "refunded" => Ok(PaymentStatus::Refunded),
             _ => Err(format!("Invalid payment status: {}", s)),
         }
     }
@@ -89,6 +129,17 @@ pub struct CreateTransactionRequest {
     #[serde(deserialize_with = "deserialize_decimal")]
     pub quantity: Decimal,  // Use Decimal for precision
     pub payment_method: String,
+    // Kendaraan dari garasi user, untuk cek kecocokan BBM dan kapasitas tangki
+    pub vehicle_id: Option<Uuid>,
+    // Odometer saat pengisian (butuh vehicle_id), dicatat sebagai fill-up untuk statistik efisiensi
+    pub odometer_km: Option<i32>,
+    pub is_full_tank: Option<bool>,
+    // Poin loyalitas yang ditukar sebagai potongan harga
+    pub redeem_points: Option<i64>,
+    // Kode promo / voucher
+    pub promo_code: Option<String>,
+    // Isi BBM atas tagihan organisasi (akun armada), dibayar lewat tagihan bulanan
+    pub organization_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -96,10 +147,39 @@ pub struct TransactionResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub spbu_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vehicle_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plate_number: Option<String>,
     pub fuel_type: String,
     pub quantity: String,  // Serialized as string for precision
     pub price_per_liter: String,  // Serialized as string for precision
     pub total_price: String,  // Serialized as string for precision
+    pub points_redeemed: i64,
+    pub points_discount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promo_code: Option<String>,
+    pub promo_discount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_id: Option<Uuid>,
+    // Pompa yang melayani pesanan, diatur operator SPBU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pump_id: Option<Uuid>,
+    // Liter yang benar-benar dikeluarkan pompa, dicatat operator saat pesanan selesai
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dispensed_quantity: Option<String>,
+    // Harga berdasarkan liter aktual; total_price tetap harga pesanan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_price: Option<String>,
+    // final_price - total_price, negatif berarti ada pengembalian dana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustment_amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustment_status: Option<AdjustmentStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pickup_redeemed_at: Option<DateTime<Utc>>,
     pub status: String,
     pub payment_method: String,
     pub payment_status: String,
@@ -109,6 +189,15 @@ pub struct TransactionResponse {
     pub updated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub paid_at: Option<DateTime<Utc>>,
+    // Peringatan non-fatal, mis. BBM bukan yang direkomendasikan untuk kendaraan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    // Poin yang didapat saat transaksi diselesaikan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points_earned: Option<i64>,
+    // Kode QR untuk ditunjukkan ke petugas pompa, diisi setelah pembayaran berhasil
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pickup_code: Option<PickupCode>,
 }
 
 fn deserialize_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
@@ -157,16 +246,33 @@ impl From<Transaction> for TransactionResponse {
             id: transaction.id,
             user_id: transaction.user_id,
             spbu_id: transaction.spbu_id,
+            vehicle_id: transaction.vehicle_id,
+            plate_number: transaction.plate_number,
             fuel_type: transaction.fuel_type,
             quantity: transaction.quantity.to_string(),
             price_per_liter: transaction.price_per_liter.to_string(),
             total_price: transaction.total_price.to_string(),
+            points_redeemed: transaction.points_redeemed,
+            points_discount: transaction.points_discount.to_string(),
+            promo_code: transaction.promo_code,
+            promo_discount: transaction.promo_discount.to_string(),
+            organization_id: transaction.organization_id,
+            invoice_id: transaction.invoice_id,
+            pump_id: transaction.pump_id,
+            dispensed_quantity: transaction.dispensed_quantity.map(|q| q.to_string()),
+            final_price: transaction.final_price.map(|p| p.to_string()),
+            adjustment_amount: transaction.adjustment_amount.map(|a| a.to_string()),
+            adjustment_status: transaction.adjustment_status,
+            pickup_redeemed_at: transaction.pickup_redeemed_at,
             status: transaction.status.to_string(),
             payment_method: transaction.payment_method,
             payment_status: transaction.payment_status.to_string(),
             created_at: transaction.created_at,
             updated_at: transaction.updated_at,
             paid_at: transaction.paid_at,
+            warnings: Vec::new(),
+            points_earned: None,
+            pickup_code: None,
         }
     }
 }
@@ -191,6 +297,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Transaction {
         let id: Uuid = row.try_get("id")?;
         let user_id: Uuid = row.try_get("user_id")?;
         let spbu_id: Uuid = row.try_get("spbu_id")?;
+        let vehicle_id: Option<Uuid> = row.try_get("vehicle_id").ok().flatten();
+        let plate_number: Option<String> = row.try_get("plate_number").ok().flatten();
         let fuel_type: String = row.try_get("fuel_type")?;
         
         // Handle decimal fields with fallback to string parsing if needed
@@ -208,7 +316,37 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Transaction {
             Ok(s) => Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
             Err(_) => Decimal::from(0),
         };
+
+        let points_redeemed: i64 = row.try_get("points_redeemed").unwrap_or(0);
+        let points_discount = match row.try_get::<String, _>("points_discount") {
+            Ok(s) => Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            Err(_) => Decimal::from(0),
+        };
+
+        let promo_code: Option<String> = row.try_get("promo_code").ok().flatten();
+        let promo_discount = match row.try_get::<String, _>("promo_discount") {
+            Ok(s) => Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            Err(_) => Decimal::from(0),
+        };
         
+        let organization_id: Option<Uuid> = row.try_get("organization_id").ok().flatten();
+        let invoice_id: Option<Uuid> = row.try_get("invoice_id").ok().flatten();
+        let pump_id: Option<Uuid> = row.try_get("pump_id").ok().flatten();
+        let dispensed_quantity = match row.try_get::<Option<String>, _>("dispensed_quantity") {
+            Ok(Some(s)) => Some(Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?),
+            _ => None,
+        };
+        let final_price = match row.try_get::<Option<String>, _>("final_price") {
+            Ok(Some(s)) => Some(Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?),
+            _ => None,
+        };
+        let adjustment_amount = match row.try_get::<Option<String>, _>("adjustment_amount") {
+            Ok(Some(s)) => Some(Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e)))?),
+            _ => None,
+        };
+        let adjustment_status: Option<AdjustmentStatus> = row.try_get("adjustment_status").ok().flatten();
+        let pickup_redeemed_at: Option<DateTime<Utc>> = row.try_get("pickup_redeemed_at").ok().flatten();
+
         let status: TransactionStatus = row.try_get("status")?;
         let payment_method: String = row.try_get("payment_method")?;
         let payment_status: PaymentStatus = row.try_get("payment_status")?;
@@ -220,10 +358,24 @@ impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Transaction {
             id,
             user_id,
             spbu_id,
+            vehicle_id,
+            plate_number,
             fuel_type,
             quantity,
             price_per_liter,
             total_price,
+            points_redeemed,
+            points_discount,
+            promo_code,
+            promo_discount,
+            organization_id,
+            invoice_id,
+            pump_id,
+            dispensed_quantity,
+            final_price,
+            adjustment_amount,
+            adjustment_status,
+            pickup_redeemed_at,
             status,
             payment_method,
             payment_status,
@@ -251,6 +403,7 @@ impl std::fmt::Display for PaymentStatus {
                 Self::Pending => "pending",
                 Self::Paid => "paid",
                 Self::Failed => "failed",
+                Self::Refunded => "refunded",
             }
         )
     }
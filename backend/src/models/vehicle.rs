@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::deserialize_some;
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "vehicle_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum VehicleType {
+    Motorcycle,
+    Car,
+    Truck,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Vehicle {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub plate_number: String,
+    pub vehicle_type: VehicleType,
+    pub name: Option<String>,
+    pub tank_capacity: Decimal,
+    pub recommended_fuel_types: Vec<String>,
+    pub avg_consumption_km_per_liter: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// tank_capacity harus di-select sebagai text (lihat VEHICLE_COLUMNS)
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Vehicle {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let tank_capacity: String = row.try_get("tank_capacity")?;
+        Ok(Vehicle {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            plate_number: row.try_get("plate_number")?,
+            vehicle_type: row.try_get("vehicle_type")?,
+            name: row.try_get("name")?,
+            tank_capacity: Decimal::from_str(&tank_capacity).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            recommended_fuel_types: row.try_get("recommended_fuel_types")?,
+            avg_consumption_km_per_liter: row.try_get("avg_consumption_km_per_liter")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateVehicleRequest {
+    pub plate_number: String,
+    pub vehicle_type: VehicleType,
+    pub name: Option<String>,
+    pub tank_capacity: Decimal,
+    #[serde(default)]
+    pub recommended_fuel_types: Vec<String>,
+    pub avg_consumption_km_per_liter: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateVehicleRequest {
+    pub plate_number: Option<String>,
+    pub vehicle_type: Option<VehicleType>,
+    // null menghapus nama
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub name: Option<Option<String>>,
+    pub tank_capacity: Option<Decimal>,
+    pub recommended_fuel_types: Option<Vec<String>>,
+    pub avg_consumption_km_per_liter: Option<f64>,
+}
+
+/// Normalisasi nomor polisi: huruf besar tanpa spasi/tanda hubung.
+/// Format yang diterima: 1-2 huruf kode wilayah, 1-4 angka, 0-3 huruf akhiran.
+pub fn normalize_plate_number(plate: &str) -> Option<String> {
+    let plate: String = plate
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+
+    let region = plate.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+    let number = plate[region..].chars().take_while(|c| c.is_ascii_digit()).count();
+    let suffix = plate[region + number..].chars().take_while(|c| c.is_ascii_alphabetic()).count();
+
+    let valid = (1..=2).contains(&region)
+        && (1..=4).contains(&number)
+        && suffix <= 3
+        && region + number + suffix == plate.len();
+    valid.then_some(plate)
+}
+
+/// Golongan BBM, untuk mencegah salah isi bensin/solar
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FuelFamily {
+    Gasoline,
+    Diesel,
+}
+
+impl FuelFamily {
+    pub fn of(fuel_type: &str) -> Self {
+        let fuel_type = fuel_type.to_uppercase();
+        if ["SOLAR", "DEX", "DIESEL"].iter().any(|k| fuel_type.contains(k)) {
+            FuelFamily::Diesel
+        } else {
+            FuelFamily::Gasoline
+        }
+    }
+}
+
+/// Hasil cek kecocokan BBM dengan kendaraan
+#[derive(Debug, PartialEq, Eq)]
+pub enum FuelCompatibility {
+    Recommended,
+    // Golongan sama tetapi bukan BBM yang direkomendasikan
+    NotRecommended,
+    // Beda golongan (bensin vs solar), bisa merusak mesin
+    Incompatible,
+}
+
+impl Vehicle {
+    pub fn fuel_compatibility(&self, fuel_type: &str) -> FuelCompatibility {
+        // Tanpa rekomendasi, semua BBM dianggap cocok
+        if self.recommended_fuel_types.is_empty()
+            || self
+                .recommended_fuel_types
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(fuel_type))
+        {
+            return FuelCompatibility::Recommended;
+        }
+
+        let family = FuelFamily::of(fuel_type);
+        if self
+            .recommended_fuel_types
+            .iter()
+            .any(|f| FuelFamily::of(f) == family)
+        {
+            FuelCompatibility::NotRecommended
+        } else {
+            FuelCompatibility::Incompatible
+        }
+    }
+}
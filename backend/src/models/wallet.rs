@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::PaymentStatus;
+use crate::types::Decimal;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WalletEntryType {
+    Topup,
+    Payment,
+    Refund,
+}
+
+fn decimal_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<Decimal, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    Decimal::from_str(&value).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+// Kolom amount/balance_after harus di-select sebagai text
+#[derive(Debug, Serialize)]
+pub struct WalletEntry {
+    pub id: Uuid,
+    pub entry_type: WalletEntryType,
+    pub amount: Decimal,
+    pub balance_after: Decimal,
+    pub transaction_id: Option<Uuid>,
+    pub topup_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for WalletEntry {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(WalletEntry {
+            id: row.try_get("id")?,
+            entry_type: row.try_get("entry_type")?,
+            amount: decimal_column(row, "amount")?,
+            balance_after: decimal_column(row, "balance_after")?,
+            transaction_id: row.try_get("transaction_id")?,
+            topup_id: row.try_get("topup_id")?,
+            description: row.try_get("description")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletTopup {
+    pub id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: Decimal,
+    pub payment_method: String,
+    pub payment_status: PaymentStatus,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for WalletTopup {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(WalletTopup {
+            id: row.try_get("id")?,
+            wallet_id: row.try_get("wallet_id")?,
+            amount: decimal_column(row, "amount")?,
+            payment_method: row.try_get("payment_method")?,
+            payment_status: row.try_get("payment_status")?,
+            created_at: row.try_get("created_at")?,
+            paid_at: row.try_get("paid_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTopupRequest {
+    pub amount: Decimal,
+    pub payment_method: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalletQuery {
+    pub limit: Option<i64>,
+}
+
+// Response GET /me/wallet
+#[derive(Debug, Serialize)]
+pub struct WalletResponse {
+    pub balance: Decimal,
+    pub entries: Vec<WalletEntry>,
+}
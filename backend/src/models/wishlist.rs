@@ -1,7 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, Row};
+use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::models::deserialize_some;
+use crate::models::fuel_price::FuelPrice;
+use crate::types::Decimal;
+
 #[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct Wishlist {
     pub id: Uuid,
@@ -25,3 +31,176 @@ pub struct WishlistResponse {
     pub spbu_address: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WishlistAlertKind {
+    // Harga fuel_type di bawah threshold
+    PriceBelow,
+    // fuel_type dijual lagi dan stoknya tidak habis
+    BackInStock,
+    // jumlah_antrian di bawah threshold
+    QueueBelow,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WishlistAlert {
+    pub id: Uuid,
+    pub spbu_id: Uuid,
+    pub kind: WishlistAlertKind,
+    pub fuel_type: Option<String>,
+    pub threshold: Option<Decimal>,
+    pub is_active: bool,
+    pub triggered: bool,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// threshold harus di-select sebagai text
+impl<'r> FromRow<'r, sqlx::postgres::PgRow> for WishlistAlert {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        let threshold: Option<String> = row.try_get("threshold")?;
+        Ok(WishlistAlert {
+            id: row.try_get("id")?,
+            spbu_id: row.try_get("spbu_id")?,
+            kind: row.try_get("kind")?,
+            fuel_type: row.try_get("fuel_type")?,
+            threshold: threshold
+                .map(|s| Decimal::from_str(&s).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+                .transpose()?,
+            is_active: row.try_get("is_active")?,
+            triggered: row.try_get("triggered")?,
+            last_triggered_at: row.try_get("last_triggered_at")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+// POST /wishlist/:spbu_id/alerts
+// price_below: fuel_type + threshold (rupiah/liter), back_in_stock: fuel_type,
+// queue_below: threshold (jumlah kendaraan)
+#[derive(Debug, Deserialize)]
+pub struct CreateWishlistAlertRequest {
+    pub kind: WishlistAlertKind,
+    pub fuel_type: Option<String>,
+    pub threshold: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWishlistAlertRequest {
+    pub threshold: Option<Decimal>,
+    pub is_active: Option<bool>,
+}
+
+// Query GET /wishlist dan GET /shared/wishlists/:token
+#[derive(Debug, Deserialize)]
+pub struct WishlistQuery {
+    pub collection_id: Option<Uuid>,
+    // Lokasi user untuk menghitung distance_km
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+}
+
+// Data SPBU terkini yang disertakan di setiap item wishlist
+#[derive(Debug, Serialize)]
+pub struct WishlistSpbuInfo {
+    pub spbu_id: Uuid,
+    pub spbu_name: String,
+    pub spbu_address: Option<String>,
+    pub brand_id: Option<Uuid>,
+    pub rating: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub jumlah_antrian: Option<i32>,
+    // None jika jam operasional belum diisi
+    pub is_open: Option<bool>,
+    pub fuel_prices: Vec<FuelPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WishlistItem {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub collection_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub position: i32,
+    #[serde(flatten)]
+    pub spbu: WishlistSpbuInfo,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// PUT /wishlist/:spbu_id; kirim null untuk mengosongkan note atau collection_id
+#[derive(Debug, Deserialize)]
+pub struct UpdateWishlistItemRequest {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub note: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub collection_id: Option<Option<Uuid>>,
+}
+
+// PUT /wishlist/order
+// SPBU yang dikirim bertukar posisi sesuai urutan ini; item lain tidak berpindah
+#[derive(Debug, Deserialize)]
+pub struct ReorderWishlistRequest {
+    pub spbu_ids: Vec<Uuid>,
+}
+
+// POST /wishlist/bulk-add dan /wishlist/bulk-remove
+#[derive(Debug, Deserialize)]
+pub struct BulkWishlistRequest {
+    pub spbu_ids: Vec<Uuid>,
+}
+
+/// Keadaan satu SPBU di wishlist offline perangkat. `updated_at` adalah waktu perubahan
+/// terakhir di perangkat; `deleted = true` berarti SPBU dihapus dari wishlist.
+#[derive(Debug, Deserialize)]
+pub struct WishlistSyncItem {
+    pub spbu_id: Uuid,
+    #[serde(default)]
+    pub deleted: bool,
+    pub collection_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// POST /wishlist/sync
+#[derive(Debug, Deserialize)]
+pub struct WishlistSyncRequest {
+    pub items: Vec<WishlistSyncItem>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct WishlistCollection {
+    pub id: Uuid,
+    pub name: String,
+    pub share_token: Option<String>,
+    pub item_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WishlistCollectionRequest {
+    pub name: String,
+}
+
+// Item koleksi yang dibagikan, tanpa catatan pribadi pemiliknya
+#[derive(Debug, Serialize)]
+pub struct SharedWishlistItem {
+    pub position: i32,
+    #[serde(flatten)]
+    pub spbu: WishlistSpbuInfo,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SharedWishlist {
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+    pub items: Vec<SharedWishlistItem>,
+}
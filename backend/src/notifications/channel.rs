@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChannelError {
+    #[error("Recipient has no {0}")]
+    MissingAddress(&'static str),
+    #[error("Channel IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    Email,
+    Sms,
+    Push,
+}
+
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send_email(&self, to: &str, subject: &str, body: &str) -> Result<(), ChannelError>;
+}
+
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    async fn send_sms(&self, to_phone: &str, text: &str) -> Result<(), ChannelError>;
+}
+
+#[async_trait]
+pub trait PushSender: Send + Sync {
+    async fn send_push(&self, user_id: Uuid, title: &str, body: &str) -> Result<(), ChannelError>;
+}
+
+/// Kanal pengiriman di luar aplikasi. Provider sungguhan cukup mengimplementasikan trait di atas.
+#[derive(Clone)]
+pub struct Channels {
+    pub email: Arc<dyn EmailSender>,
+    pub sms: Arc<dyn SmsSender>,
+    pub push: Arc<dyn PushSender>,
+}
+
+impl Channels {
+    /// Belum ada provider: semua kanal ditulis ke file log di `NOTIFICATION_LOG_DIR`
+    /// (default `./logs/notifications`)
+    pub fn from_env() -> Self {
+        let dir = PathBuf::from(
+            env::var("NOTIFICATION_LOG_DIR").unwrap_or_else(|_| "./logs/notifications".to_string()),
+        );
+        let log = Arc::new(LogFileChannel { dir });
+        Channels {
+            email: log.clone(),
+            sms: log.clone(),
+            push: log,
+        }
+    }
+}
+
+/// Pengganti provider untuk development: satu baris per pesan di `email.log`, `sms.log`, `push.log`
+pub struct LogFileChannel {
+    dir: PathBuf,
+}
+
+impl LogFileChannel {
+    async fn append(&self, file: &str, line: String) -> Result<(), ChannelError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut f = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(file))
+            .await?;
+        f.write_all(format!("{} {}\n", Utc::now().to_rfc3339(), line).as_bytes()).await?;
+        Ok(())
+    }
+}
+
+// Baris baru di isi pesan diganti spasi agar satu pesan tetap satu baris
+fn one_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+#[async_trait]
+impl EmailSender for LogFileChannel {
+    async fn send_email(&self, to: &str, subject: &str, body: &str) -> Result<(), ChannelError> {
+        self.append(
+            "email.log",
+            format!("to={} subject={:?} body={:?}", to, one_line(subject), one_line(body)),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl SmsSender for LogFileChannel {
+    async fn send_sms(&self, to_phone: &str, text: &str) -> Result<(), ChannelError> {
+        self.append("sms.log", format!("to={} text={:?}", to_phone, one_line(text))).await
+    }
+}
+
+#[async_trait]
+impl PushSender for LogFileChannel {
+    async fn send_push(&self, user_id: Uuid, title: &str, body: &str) -> Result<(), ChannelError> {
+        self.append(
+            "push.log",
+            format!("user={} title={:?} body={:?}", user_id, one_line(title), one_line(body)),
+        )
+        .await
+    }
+}
@@ -0,0 +1,126 @@
+//! Notifikasi ke user.
+//!
+//! [`notify`] dipanggil di dalam transaksi database pemicunya: notifikasi in-app langsung
+//! masuk ke tabel `notifications`, sedangkan email/SMS/push dimasukkan ke antrian job
+//! ([`DeliverNotification`](crate::jobs::DeliverNotification)) sehingga ikut batal jika
+//! transaksinya di-rollback dan dicoba ulang jika provider gagal.
+
+pub mod channel;
+pub mod templates;
+
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::jobs::{queue, DeliverNotification};
+use crate::models::notification::{NotificationChannels, NotificationEvent, NotificationLanguage};
+use crate::models::Transaction;
+use crate::types::Decimal;
+use channel::ChannelKind;
+
+#[derive(sqlx::FromRow)]
+struct RecipientSettings {
+    notification_language: NotificationLanguage,
+    in_app: Option<bool>,
+    email: Option<bool>,
+    sms: Option<bool>,
+    push: Option<bool>,
+}
+
+/// Kirim notifikasi `event` ke user lewat kanal yang diaktifkan di preferensinya.
+/// `data` dipakai untuk mengisi template dan disimpan bersama notifikasi in-app.
+pub async fn notify(conn: &mut PgConnection, user_id: Uuid, event: NotificationEvent, data: Value) -> Result<()> {
+    let settings = sqlx::query_as::<_, RecipientSettings>(
+        r#"
+        SELECT u.notification_language, p.in_app, p.email, p.sms, p.push
+        FROM users u
+        LEFT JOIN notification_preferences p ON p.user_id = u.id AND p.event = $2
+        WHERE u.id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(event)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+    let defaults = event.default_channels();
+    let channels = NotificationChannels {
+        in_app: settings.in_app.unwrap_or(defaults.in_app),
+        email: settings.email.unwrap_or(defaults.email),
+        sms: settings.sms.unwrap_or(defaults.sms),
+        push: settings.push.unwrap_or(defaults.push),
+    };
+
+    let (title, body) = templates::render(event, settings.notification_language, &data);
+
+    if channels.in_app {
+        sqlx::query("INSERT INTO notifications (user_id, event, title, body, data) VALUES ($1, $2, $3, $4, $5)")
+            .bind(user_id)
+            .bind(event)
+            .bind(&title)
+            .bind(&body)
+            .bind(&data)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let external = [
+        (channels.email, ChannelKind::Email),
+        (channels.sms, ChannelKind::Sms),
+        (channels.push, ChannelKind::Push),
+    ];
+    for (_, channel) in external.into_iter().filter(|(enabled, _)| *enabled) {
+        let job = DeliverNotification {
+            user_id,
+            channel,
+            title: title.clone(),
+            body: body.clone(),
+        };
+        queue::enqueue(&mut *conn, &job).await?;
+    }
+
+    Ok(())
+}
+
+/// Notifikasi tentang sebuah transaksi BBM. `extra` menimpa data bawaan (misalnya `amount`
+/// untuk jumlah refund).
+pub async fn notify_transaction(
+    conn: &mut PgConnection,
+    event: NotificationEvent,
+    transaction: &Transaction,
+    extra: Value,
+) -> Result<()> {
+    let spbu_name: Option<String> = sqlx::query_scalar("SELECT nama FROM spbu WHERE id = $1")
+        .bind(transaction.spbu_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    let quantity = transaction.dispensed_quantity.as_ref().unwrap_or(&transaction.quantity);
+    let mut data = json!({
+        "transaction_id": transaction.id,
+        "spbu_id": transaction.spbu_id,
+        "spbu_name": spbu_name.unwrap_or_default(),
+        "fuel_type": transaction.fuel_type,
+        "quantity": trim_zeros(quantity),
+        "amount": transaction.billed_price().to_string(),
+    });
+    if let (Some(data), Value::Object(extra)) = (data.as_object_mut(), extra) {
+        data.extend(extra);
+    }
+
+    notify(conn, transaction.user_id, event, data).await
+}
+
+/// `10.000` -> `10`, `9.50` -> `9.5`
+pub fn trim_zeros(value: &Decimal) -> String {
+    let s = value.to_string();
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
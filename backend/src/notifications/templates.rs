@@ -0,0 +1,150 @@
+use serde_json::Value;
+
+use crate::models::notification::{NotificationEvent, NotificationLanguage};
+
+struct Template {
+    title: &'static str,
+    body: &'static str,
+}
+
+// Placeholder `{nama}` diisi dari data notifikasi; `{nama|rupiah}` diformat sebagai rupiah
+fn template(event: NotificationEvent, language: NotificationLanguage) -> Template {
+    use NotificationEvent::*;
+    use NotificationLanguage::*;
+
+    match (event, language) {
+        (PaymentSucceeded, Id) => Template {
+            title: "Pembayaran berhasil",
+            body: "Pembayaran {amount|rupiah} untuk {quantity} L {fuel_type} di {spbu_name} berhasil. \
+                   Tunjukkan kode QR ke petugas pompa.",
+        },
+        (PaymentSucceeded, En) => Template {
+            title: "Payment successful",
+            body: "Your payment of {amount|rupiah} for {quantity} L of {fuel_type} at {spbu_name} was successful. \
+                   Show the QR code to the pump attendant.",
+        },
+        (PaymentFailed, Id) => Template {
+            title: "Pembayaran gagal",
+            body: "Pembayaran {amount|rupiah} untuk {quantity} L {fuel_type} di {spbu_name} gagal. Silakan coba lagi.",
+        },
+        (PaymentFailed, En) => Template {
+            title: "Payment failed",
+            body: "Your payment of {amount|rupiah} for {quantity} L of {fuel_type} at {spbu_name} failed. Please try again.",
+        },
+        (TransactionCompleted, Id) => Template {
+            title: "Pengisian selesai",
+            body: "Pengisian {quantity} L {fuel_type} di {spbu_name} selesai. Total {amount|rupiah}.",
+        },
+        (TransactionCompleted, En) => Template {
+            title: "Refuelling complete",
+            body: "Your {quantity} L of {fuel_type} at {spbu_name} has been dispensed. Total {amount|rupiah}.",
+        },
+        (TransactionExpired, Id) => Template {
+            title: "Pesanan dibatalkan",
+            body: "Pesanan {quantity} L {fuel_type} di {spbu_name} dibatalkan karena belum dibayar.",
+        },
+        (TransactionExpired, En) => Template {
+            title: "Order cancelled",
+            body: "Your order of {quantity} L of {fuel_type} at {spbu_name} was cancelled because it was not paid in time.",
+        },
+        (RefundIssued, Id) => Template {
+            title: "Dana dikembalikan",
+            body: "{amount|rupiah} untuk transaksi {fuel_type} di {spbu_name} telah dikembalikan ke dompet Anda.",
+        },
+        (RefundIssued, En) => Template {
+            title: "Refund issued",
+            body: "{amount|rupiah} from your {fuel_type} purchase at {spbu_name} has been refunded to your wallet.",
+        },
+        (WishlistPriceDrop, Id) => Template {
+            title: "Harga {fuel_type} turun",
+            body: "{fuel_type} di {spbu_name} sekarang {price|rupiah}/liter, di bawah batas {threshold|rupiah} yang Anda pasang.",
+        },
+        (WishlistPriceDrop, En) => Template {
+            title: "{fuel_type} price drop",
+            body: "{fuel_type} at {spbu_name} is now {price|rupiah} per litre, below your {threshold|rupiah} alert.",
+        },
+        (WishlistBackInStock, Id) => Template {
+            title: "{fuel_type} tersedia kembali",
+            body: "{fuel_type} di {spbu_name} sudah tersedia kembali.",
+        },
+        (WishlistBackInStock, En) => Template {
+            title: "{fuel_type} back in stock",
+            body: "{fuel_type} is available again at {spbu_name}.",
+        },
+        (WishlistQueueShort, Id) => Template {
+            title: "Antrian {spbu_name} pendek",
+            body: "Antrian di {spbu_name} sekarang {queue} kendaraan, di bawah {threshold} yang Anda pasang.",
+        },
+        (WishlistQueueShort, En) => Template {
+            title: "Short queue at {spbu_name}",
+            body: "The queue at {spbu_name} is down to {queue} vehicles, below your alert of {threshold}.",
+        },
+        (ReviewReplied, Id) => Template {
+            title: "{spbu_name} membalas review Anda",
+            body: "{spbu_name}: \"{excerpt}\"",
+        },
+        (ReviewReplied, En) => Template {
+            title: "{spbu_name} replied to your review",
+            body: "{spbu_name}: \"{excerpt}\"",
+        },
+    }
+}
+
+/// Judul dan isi notifikasi dalam bahasa user
+pub fn render(event: NotificationEvent, language: NotificationLanguage, data: &Value) -> (String, String) {
+    let template = template(event, language);
+    (fill(template.title, data), fill(template.body, data))
+}
+
+fn fill(template: &str, data: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start + 1..start + len];
+        let (key, filter) = match placeholder.split_once('|') {
+            Some((key, filter)) => (key, Some(filter)),
+            None => (placeholder, None),
+        };
+        let value = match data.get(key) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        match filter {
+            Some("rupiah") => out.push_str(&format_rupiah(&value)),
+            _ => out.push_str(&value),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `125000.00` -> `Rp125.000`, `1250.5` -> `Rp1.250,50`
+pub fn format_rupiah(amount: &str) -> String {
+    let (negative, amount) = match amount.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, amount),
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let fraction = fraction.trim_end_matches('0');
+
+    let mut grouped = String::new();
+    for (i, c) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push('.');
+        }
+        grouped.push(c);
+    }
+
+    let mut out = format!("{}Rp{}", if negative { "-" } else { "" }, grouped);
+    if !fraction.is_empty() {
+        out.push(',');
+        out.push_str(&format!("{:0<2}", fraction));
+    }
+    out
+}
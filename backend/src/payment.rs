@@ -0,0 +1,26 @@
+use bigdecimal::BigDecimal;
+
+use crate::types::Decimal;
+
+/// Metode pembayaran yang memotong saldo dompet dalam aplikasi
+pub const PAYMENT_METHOD_WALLET: &str = "wallet";
+
+/// Metode pembayaran transaksi organisasi, ditagih lewat tagihan bulanan
+pub const PAYMENT_METHOD_INVOICE: &str = "invoice";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PaymentOutcome {
+    Succeeded,
+    Failed(String),
+}
+
+/// Tagih pembayaran melalui payment gateway eksternal.
+///
+/// Belum ada integrasi gateway; untuk demo semua tagihan dengan nominal valid dianggap berhasil.
+pub async fn charge(payment_method: &str, amount: &Decimal) -> PaymentOutcome {
+    if amount.0 <= BigDecimal::from(0) {
+        return PaymentOutcome::Failed("Amount must be greater than 0".to_string());
+    }
+    tracing::info!("Charging {} via {}", amount, payment_method);
+    PaymentOutcome::Succeeded
+}
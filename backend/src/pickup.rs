@@ -0,0 +1,182 @@
+//! Kode QR pengambilan BBM.
+//!
+//! Payload berbentuk `FQ1.<transaction_id>.<nonce>.<expires>.<signature>`, ditandatangani
+//! HMAC-SHA256. Nonce disimpan di transaksi sehingga hanya kode terakhir yang berlaku.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::env;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+const PREFIX: &str = "FQ1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupCode {
+    pub payload: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PickupError {
+    Malformed,
+    InvalidSignature,
+    Expired,
+}
+
+impl std::fmt::Display for PickupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PickupError::Malformed => write!(f, "Pickup code is not valid"),
+            PickupError::InvalidSignature => write!(f, "Pickup code signature is not valid"),
+            PickupError::Expired => write!(f, "Pickup code has expired"),
+        }
+    }
+}
+
+static SIGNING_KEY: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Baca kunci tanda tangan dari `PICKUP_SIGNING_KEY`, atau `JWT_SECRET` jika tidak diset.
+/// Dipanggil sekali saat startup agar server tidak jalan tanpa kunci.
+pub fn init_signing_key() -> Result<(), env::VarError> {
+    let key = env::var("PICKUP_SIGNING_KEY").or_else(|_| env::var("JWT_SECRET"))?;
+    if key.is_empty() {
+        return Err(env::VarError::NotPresent);
+    }
+    SIGNING_KEY.get_or_init(|| key.into_bytes());
+    Ok(())
+}
+
+fn signing_key() -> &'static [u8] {
+    SIGNING_KEY
+        .get()
+        .expect("pickup::init_signing_key must be called at startup")
+}
+
+/// Masa berlaku kode, default 5 menit
+pub fn ttl_secs() -> i64 {
+    env::var("PICKUP_CODE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(300)
+}
+
+fn mac(transaction_id: Uuid, nonce: Uuid, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_key()).expect("HMAC accepts keys of any length");
+    mac.update(transaction_id.to_string().as_bytes());
+    mac.update(b"\n");
+    mac.update(nonce.to_string().as_bytes());
+    mac.update(b"\n");
+    mac.update(expires.to_string().as_bytes());
+    mac
+}
+
+fn signature(transaction_id: Uuid, nonce: Uuid, expires: i64) -> String {
+    hex::encode(mac(transaction_id, nonce, expires).finalize().into_bytes())
+}
+
+/// Buat payload QR untuk transaksi dengan nonce yang sudah disimpan
+pub fn sign(transaction_id: Uuid, nonce: Uuid) -> PickupCode {
+    let expires = Utc::now().timestamp() + ttl_secs();
+    PickupCode {
+        payload: format!(
+            "{}.{}.{}.{}.{}",
+            PREFIX,
+            transaction_id,
+            nonce,
+            expires,
+            signature(transaction_id, nonce, expires)
+        ),
+        expires_at: DateTime::from_timestamp(expires, 0).unwrap_or_else(Utc::now),
+    }
+}
+
+/// Cek format, tanda tangan dan masa berlaku. Mengembalikan `(transaction_id, nonce)`.
+pub fn verify(payload: &str) -> Result<(Uuid, Uuid), PickupError> {
+    let parts: Vec<&str> = payload.trim().split('.').collect();
+    let [prefix, transaction_id, nonce, expires, signature_hex] = parts[..] else {
+        return Err(PickupError::Malformed);
+    };
+    if prefix != PREFIX {
+        return Err(PickupError::Malformed);
+    }
+    let transaction_id = Uuid::parse_str(transaction_id).map_err(|_| PickupError::Malformed)?;
+    let nonce = Uuid::parse_str(nonce).map_err(|_| PickupError::Malformed)?;
+    let expires: i64 = expires.parse().map_err(|_| PickupError::Malformed)?;
+
+    // verify_slice membandingkan dalam waktu konstan
+    let signature = hex::decode(signature_hex).map_err(|_| PickupError::InvalidSignature)?;
+    mac(transaction_id, nonce, expires)
+        .verify_slice(&signature)
+        .map_err(|_| PickupError::InvalidSignature)?;
+    if expires < Utc::now().timestamp() {
+        return Err(PickupError::Expired);
+    }
+
+    Ok((transaction_id, nonce))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() {
+        SIGNING_KEY.get_or_init(|| b"test-signing-key".to_vec());
+    }
+
+    fn payload(transaction_id: Uuid, nonce: Uuid, expires: i64, signature: &str) -> String {
+        format!("{}.{}.{}.{}.{}", PREFIX, transaction_id, nonce, expires, signature)
+    }
+
+    #[test]
+    fn verify_accepts_signed_code() {
+        setup();
+        let (transaction_id, nonce) = (Uuid::new_v4(), Uuid::new_v4());
+        let code = sign(transaction_id, nonce);
+        assert_eq!(verify(&code.payload), Ok((transaction_id, nonce)));
+        assert_eq!(verify(&format!("  {}\n", code.payload)), Ok((transaction_id, nonce)));
+    }
+
+    #[test]
+    fn verify_rejects_tampered_code() {
+        setup();
+        let (transaction_id, nonce) = (Uuid::new_v4(), Uuid::new_v4());
+        let expires = Utc::now().timestamp() + 60;
+        let valid = signature(transaction_id, nonce, expires);
+
+        let other_transaction = payload(Uuid::new_v4(), nonce, expires, &valid);
+        assert_eq!(verify(&other_transaction), Err(PickupError::InvalidSignature));
+
+        let extended = payload(transaction_id, nonce, expires + 3600, &valid);
+        assert_eq!(verify(&extended), Err(PickupError::InvalidSignature));
+
+        let truncated = payload(transaction_id, nonce, expires, &valid[..valid.len() - 2]);
+        assert_eq!(verify(&truncated), Err(PickupError::InvalidSignature));
+
+        let not_hex = payload(transaction_id, nonce, expires, "zz");
+        assert_eq!(verify(&not_hex), Err(PickupError::InvalidSignature));
+    }
+
+    #[test]
+    fn verify_rejects_expired_code() {
+        setup();
+        let (transaction_id, nonce) = (Uuid::new_v4(), Uuid::new_v4());
+        let expires = Utc::now().timestamp() - 1;
+        let code = payload(transaction_id, nonce, expires, &signature(transaction_id, nonce, expires));
+        assert_eq!(verify(&code), Err(PickupError::Expired));
+    }
+
+    #[test]
+    fn verify_rejects_malformed_code() {
+        setup();
+        let (transaction_id, nonce) = (Uuid::new_v4(), Uuid::new_v4());
+        let code = sign(transaction_id, nonce).payload;
+
+        assert_eq!(verify(""), Err(PickupError::Malformed));
+        assert_eq!(verify(&code.replacen(PREFIX, "FQ2", 1)), Err(PickupError::Malformed));
+        assert_eq!(verify(&format!("{}.extra", code)), Err(PickupError::Malformed));
+        assert_eq!(verify(&code.replacen(&transaction_id.to_string(), "not-a-uuid", 1)), Err(PickupError::Malformed));
+    }
+}
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+
+use super::{Storage, StorageError};
+
+/// Storage yang menyimpan file di filesystem lokal di bawah `root`
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    // Tolak key yang bisa keluar dari root (`..`, path absolut, dll)
+    fn resolve(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let path = Path::new(key);
+        let is_safe = !key.is_empty()
+            && path
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !is_safe {
+            return Err(StorageError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(path))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), StorageError> {
+        let path = self.resolve(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, bytes).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.resolve(key)?;
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.resolve(key)?;
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            // Sudah terhapus, anggap sukses
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+pub mod local;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
+
+pub use local::LocalStorage;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+    #[error("Invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("Storage IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Backend penyimpanan file (foto, logo, icon).
+///
+/// Key selalu berupa path relatif dengan pemisah `/`, misalnya `spbu/<id>/<uuid>.jpg`.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), StorageError>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Konfigurasi upload dan download media, dibaca dari environment
+#[derive(Debug, Clone)]
+pub struct MediaConfig {
+    pub max_upload_bytes: usize,
+    pub url_ttl_secs: i64,
+    pub thumbnail_size: u32,
+    signing_key: String,
+}
+
+impl MediaConfig {
+    /// Gagal jika tidak ada kunci tanda tangan; URL download tidak boleh ditandatangani dengan kunci bawaan
+    pub fn from_env() -> Result<Self, env::VarError> {
+        let max_upload_bytes = env::var("MEDIA_MAX_UPLOAD_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5 * 1024 * 1024);
+        let url_ttl_secs = env::var("MEDIA_URL_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3600);
+        let thumbnail_size = env::var("MEDIA_THUMBNAIL_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(320);
+        // Pakai JWT_SECRET jika MEDIA_SIGNING_KEY tidak diset
+        let signing_key = env::var("MEDIA_SIGNING_KEY").or_else(|_| env::var("JWT_SECRET"))?;
+        if signing_key.is_empty() {
+            return Err(env::VarError::NotPresent);
+        }
+
+        Ok(Self {
+            max_upload_bytes,
+            url_ttl_secs,
+            thumbnail_size,
+            signing_key,
+        })
+    }
+
+    fn mac(&self, key: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(key.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires.to_string().as_bytes());
+        mac
+    }
+
+    fn signature(&self, key: &str, expires: i64) -> String {
+        hex::encode(self.mac(key, expires).finalize().into_bytes())
+    }
+
+    /// Buat URL download bertanda tangan yang berlaku selama `url_ttl_secs`
+    pub fn signed_url(&self, key: &str) -> String {
+        let expires = Utc::now().timestamp() + self.url_ttl_secs;
+        format!(
+            "/media/{}?expires={}&signature={}",
+            key,
+            expires,
+            self.signature(key, expires)
+        )
+    }
+
+    /// Cek tanda tangan dan masa berlaku URL download
+    pub fn verify(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        }
+        // verify_slice membandingkan dalam waktu konstan
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        self.mac(key, expires).verify_slice(&signature).is_ok()
+    }
+}
+
+/// Deteksi tipe gambar dari magic bytes, bukan dari header Content-Type client.
+/// Mengembalikan `(content_type, extension)`.
+pub fn sniff_image_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("image/jpeg", "jpg"))
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some(("image/png", "png"))
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(("image/webp", "webp"))
+    } else {
+        None
+    }
+}
+
+/// Buat thumbnail JPEG dengan sisi terpanjang `max_size` piksel
+pub fn make_thumbnail(bytes: &[u8], max_size: u32) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let thumbnail = image.thumbnail(max_size, max_size).to_rgb8();
+    let mut out = std::io::Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut out, image::ImageOutputFormat::Jpeg(80))
+        .ok()?;
+    Some(out.into_inner())
+}
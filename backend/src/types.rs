@@ -40,9 +40,11 @@ impl<'de> Deserialize<'de> for Decimal {
             }
 
             fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
-                BigDecimal::from_f64(value)
-                    .map(Decimal)
-                    .ok_or_else(|| de::Error::custom("Failed to convert f64 to BigDecimal"))
+                // Lewat representasi desimal terpendek supaya 3.31 tetap 3.31, bukan ekspansi biner f64
+                if !value.is_finite() {
+                    return Err(de::Error::custom("Failed to convert f64 to BigDecimal"));
+                }
+                value.to_string().parse().map_err(de::Error::custom)
             }
 
             fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {